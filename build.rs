@@ -0,0 +1,18 @@
+//! Makes cargo track the environment variables read by the macros, so that
+//! the crates using them are expanded again when they change
+
+/// Environment variables read by the macros at expansion time
+const TRACKED_VARS: [&str; 4] = [
+    "FANCY_IP_STRICT",
+    "FANCY_IP_DEBUG_EXPAND",
+    "FANCY_IP_INVENTORY",
+    "FANCY_IP_NET_PATH",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    for name in TRACKED_VARS {
+        println!("cargo:rerun-if-env-changed={name}");
+    }
+}
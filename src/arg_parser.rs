@@ -1,208 +1,1053 @@
-use std::fmt::Display;
-use std::iter::Iterator;
-
-use proc_macro::token_stream::IntoIter;
-use proc_macro::{Span, TokenStream, TokenTree};
-
-use litrs::{FromIntegerLiteral, Literal};
-
-pub struct ArgParser {
-    parsed: usize,
-    stream: IntoIter,
-}
-
-#[derive(Debug)]
-pub struct Error {
-    kind: ErrorKind,
-    span: Span,
-}
-
-#[derive(Debug)]
-pub enum ErrorKind {
-    BadType {
-        given: LiteralType,
-        expected: LiteralType,
-    },
-    OutOfBound,
-    UnexpectedToken(String),
-}
-
-#[derive(Debug)]
-pub enum LiteralType {
-    Bool,
-    Integer,
-    Float,
-    Char,
-    String,
-    Byte,
-    ByteString,
-}
-
-impl ArgParser {
-    fn try_string_literal(lit: Literal<String>, span: Span) -> Result<String, Error> {
-        if let Literal::String(v) = &lit {
-            Ok(v.value().to_string())
-        } else {
-            Err(Error {
-                span,
-                kind: ErrorKind::BadType {
-                    given: LiteralType::from(lit),
-                    expected: LiteralType::String,
-                },
-            })
-        }
-    }
-
-    fn try_integer_literal<I: FromIntegerLiteral>(
-        lit: Literal<String>,
-        span: Span,
-    ) -> Result<I, Error> {
-        if let Literal::Integer(v) = lit {
-            if let Some(value) = v.value() {
-                Ok(value)
-            } else {
-                Err(Error {
-                    span,
-                    kind: ErrorKind::OutOfBound,
-                })
-            }
-        } else {
-            Err(Error {
-                span,
-                kind: ErrorKind::BadType {
-                    given: LiteralType::from(lit),
-                    expected: LiteralType::Integer,
-                },
-            })
-        }
-    }
-
-    fn next_raw(&mut self) -> Result<Option<(Literal<String>, Span)>, Error> {
-        if let Some(token) = self.stream.next() {
-            if let TokenTree::Literal(ret) = token {
-                if let Some(token) = self.stream.next() {
-                    if let TokenTree::Punct(punct) = &token {
-                        if punct.as_char() != ',' {
-                            return Err(Error {
-                                kind: ErrorKind::UnexpectedToken(token.to_string()),
-                                span: token.span(),
-                            });
-                        }
-                    } else {
-                        return Err(Error {
-                            kind: ErrorKind::UnexpectedToken(token.to_string()),
-                            span: token.span(),
-                        });
-                    }
-                }
-
-                self.parsed += 1;
-                let span = ret.span().clone();
-
-                Ok(Some((Literal::from(ret), span)))
-            } else {
-                Err(Error {
-                    kind: ErrorKind::UnexpectedToken(token.to_string()),
-                    span: token.span(),
-                })
-            }
-        } else {
-            Ok(None)
-        }
-    }
-
-    /// Count argument given to the function
-    ///
-    /// Warning: This function will consule all remaining argument
-    pub fn count_arguments(&mut self) -> usize {
-        while let Ok(Some(_)) = self.next_raw() {}
-
-        self.parsed
-    }
-
-    pub fn ignore_next(&mut self) -> Result<Option<Span>, Error> {
-        Ok(if let Some((_, span)) = self.next_raw()? {
-            Some(span)
-        } else {
-            None
-        })
-    }
-
-    pub fn next_string(&mut self) -> Result<Option<(String, Span)>, Error> {
-        Ok(if let Some((literal, span)) = self.next_raw()? {
-            Some((Self::try_string_literal(literal, span)?, span))
-        } else {
-            None
-        })
-    }
-
-    pub fn next_integer<I: FromIntegerLiteral>(&mut self) -> Result<Option<(I, Span)>, Error> {
-        Ok(if let Some((literal, span)) = self.next_raw()? {
-            Some((Self::try_integer_literal(literal, span)?, span))
-        } else {
-            None
-        })
-    }
-}
-
-impl Error {
-    pub fn span(&self) -> Span {
-        self.span
-    }
-}
-
-impl Display for LiteralType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            Self::Bool => "bool",
-            Self::Byte => "u8",
-            Self::ByteString => "[u8]",
-            Self::Char => "char",
-            Self::String => "str",
-            Self::Float => "float",
-            Self::Integer => "int",
-        })
-    }
-}
-
-impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.kind {
-            ErrorKind::BadType { given, expected } => {
-                writeln!(f, "Unexpected type: given `{given}`, expected `{expected}`")
-            },
-            ErrorKind::OutOfBound => writeln!(
-                f,
-                "The integer value is out of bounds for the required type"
-            ),
-            ErrorKind::UnexpectedToken(token) => {
-                writeln!(f, "Unexpected token `{}`", token)
-            },
-        }
-    }
-}
-
-impl std::error::Error for Error {}
-
-impl From<TokenStream> for ArgParser {
-    fn from(value: TokenStream) -> Self {
-        ArgParser {
-            parsed: 0,
-            stream: value.into_iter(),
-        }
-    }
-}
-
-impl<T: litrs::Buffer> From<Literal<T>> for LiteralType {
-    fn from(value: Literal<T>) -> Self {
-        match value {
-            Literal::Bool(_) => Self::Bool,
-            Literal::Integer(_) => Self::Integer,
-            Literal::Float(_) => Self::Float,
-            Literal::Char(_) => Self::Char,
-            Literal::String(_) => Self::String,
-            Literal::Byte(_) => Self::Byte,
-            Literal::ByteString(_) => Self::ByteString,
-        }
-    }
-}
+use std::fmt::Display;
+use std::iter::{Iterator, Peekable};
+
+use proc_macro::token_stream::IntoIter;
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use litrs::{FromIntegerLiteral, Literal};
+
+use crate::include::Included;
+use crate::tokens;
+
+pub struct ArgParser {
+    parsed: usize,
+    stream: Peekable<IntoIter>,
+    included: Vec<Included>,
+    origin: Option<usize>,
+    /// Environment variables read by `env!` arguments
+    env: Vec<String>,
+}
+
+/// Keyword argument given after the positional ones
+///
+/// An option is either a bare flag (`wrapped`) or a named value (`as = nix`)
+pub struct Opt {
+    name: String,
+    span: Span,
+    value: Vec<TokenTree>,
+}
+
+/// Outer attribute given before an entry of a list, either `#[cfg(...)]` or a
+/// lint attribute such as `#[allow(...)]`
+#[derive(Clone)]
+pub struct Attribute {
+    /// Name of the attribute, such as `cfg`
+    pub name: String,
+    /// Span of the `#`
+    pub span: Span,
+    /// Tokens of the whole attribute, `#` included
+    pub tokens: TokenStream,
+    /// Tokens inside the parentheses following the name
+    pub arguments: TokenStream,
+}
+
+/// Attributes accepted before the entries of a list
+const ENTRY_ATTRIBUTES: [&str; 5] = ["cfg", "allow", "warn", "deny", "expect"];
+
+/// Argument written as an arm of a match, `"<key>" => <value>`
+pub struct Arm {
+    /// The key of the arm, `None` for the `_` fallback arm
+    pub key: Option<String>,
+    /// Span of the key
+    pub span: Span,
+    pub value: TokenStream,
+}
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    span: Span,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    BadType {
+        given: LiteralType,
+        expected: LiteralType,
+    },
+    OutOfBound {
+        name: String,
+        given: String,
+        expected: &'static str,
+        max: u64,
+    },
+    UnexpectedToken(String),
+    MissingValue(String),
+    MissingArmValue(String),
+    UnsupportedAttribute(String),
+    MissingGroup(String),
+    Macro(String),
+}
+
+/// Integer type of a positional argument
+pub trait IntegerArgument: FromIntegerLiteral {
+    const NAME: &'static str;
+    const MAX: u64;
+}
+
+macro_rules! impl_integer_argument {
+    ($($ty:ident),*) => {
+        $(
+            impl IntegerArgument for $ty {
+                const NAME: &'static str = stringify!($ty);
+                const MAX: u64 = $ty::MAX as u64;
+            }
+        )*
+    };
+}
+
+impl_integer_argument!(u8, u16, u32, u64);
+
+/// Elements of an array argument along with their spans, followed by the span
+/// of the whole array
+pub type IntegerArray<I> = (Vec<(I, Span)>, Span);
+
+/// Built-in macros evaluated in place of a string argument
+const BUILTIN_MACROS: [&str; 3] = ["concat", "env", "include_str"];
+
+#[derive(Debug)]
+pub enum LiteralType {
+    Bool,
+    Integer,
+    Float,
+    Char,
+    String,
+    Byte,
+    ByteString,
+}
+
+impl ArgParser {
+    fn try_string_literal(lit: Literal<String>, span: Span) -> Result<String, Error> {
+        if let Literal::String(v) = &lit {
+            Ok(v.value().to_string())
+        } else {
+            Err(Error {
+                span,
+                kind: ErrorKind::BadType {
+                    given: LiteralType::from(lit),
+                    expected: LiteralType::String,
+                },
+            })
+        }
+    }
+
+    fn try_integer_literal<I: IntegerArgument>(
+        lit: Literal<String>,
+        span: Span,
+        name: &str,
+    ) -> Result<I, Error> {
+        if let Literal::Integer(v) = lit {
+            if let Some(value) = v.value() {
+                Ok(value)
+            } else {
+                Err(Error {
+                    span,
+                    kind: ErrorKind::OutOfBound {
+                        name: name.to_string(),
+                        given: v.raw_input().to_string(),
+                        expected: I::NAME,
+                        max: I::MAX,
+                    },
+                })
+            }
+        } else {
+            Err(Error {
+                span,
+                kind: ErrorKind::BadType {
+                    given: LiteralType::from(lit),
+                    expected: LiteralType::Integer,
+                },
+            })
+        }
+    }
+
+    /// Consumes the separator following an argument, if any
+    fn next_separator(&mut self) -> Result<(), Error> {
+        if let Some(token) = self.stream.next() {
+            if let TokenTree::Punct(punct) = &token {
+                if punct.as_char() == ',' {
+                    return Ok(());
+                }
+            }
+
+            Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the next literal of a positional argument
+    ///
+    /// Positional arguments stop at the first option, which is left in the
+    /// stream for [`ArgParser::next_option`]. The first argument is never an
+    /// option. The separator following the literal is left in the stream.
+    fn next_raw(&mut self) -> Result<Option<(Literal<String>, Span)>, Error> {
+        match self.stream.peek() {
+            None => return Ok(None),
+            Some(TokenTree::Ident(_)) if self.parsed > 0 => return Ok(None),
+            Some(_) => {}
+        }
+
+        match self.stream.next() {
+            Some(TokenTree::Literal(ret)) => {
+                let span = ret.span();
+
+                Ok(Some((Literal::from(ret), span)))
+            }
+            Some(token) => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Count argument given to the function
+    ///
+    /// Warning: This function will consule all remaining argument
+    pub fn count_arguments(&mut self) -> usize {
+        while let Ok(Some(_)) = self.ignore_next() {}
+
+        self.parsed
+    }
+
+    /// Skips the next argument whatever its kind, returning its span
+    pub fn ignore_next(&mut self) -> Result<Option<Span>, Error> {
+        let span = if let Some(token) = self.stream.peek() {
+            token.span()
+        } else {
+            return Ok(None);
+        };
+
+        self.skip_argument();
+
+        Ok(Some(span))
+    }
+
+    /// Consumes the remaining tokens of the current argument, up to and
+    /// including its separator
+    fn skip_argument(&mut self) {
+        for token in self.stream.by_ref() {
+            if let TokenTree::Punct(punct) = &token {
+                if punct.as_char() == ',' {
+                    break;
+                }
+            }
+        }
+
+        self.parsed += 1;
+    }
+
+    /// Skips the faulty argument on error, so that the following arguments
+    /// can still be parsed once the error is reported
+    fn recover<T>(&mut self, value: Result<T, Error>) -> Result<T, Error> {
+        if value.is_err() {
+            self.skip_argument();
+        }
+
+        value
+    }
+
+    /// Returns the next option given after the positional arguments
+    pub fn next_option(&mut self) -> Result<Option<Opt>, Error> {
+        let ident = match self.stream.peek() {
+            Some(TokenTree::Ident(ident)) => ident.clone(),
+            _ => return Ok(None),
+        };
+        self.stream.next();
+
+        let mut opt = Opt {
+            name: ident.to_string(),
+            span: ident.span(),
+            value: Vec::new(),
+        };
+
+        match self.stream.next() {
+            None => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                for token in self.stream.by_ref() {
+                    if let TokenTree::Punct(punct) = &token {
+                        if punct.as_char() == ',' {
+                            break;
+                        }
+                    }
+
+                    opt.value.push(token);
+                }
+
+                if opt.value.is_empty() {
+                    return Err(Error {
+                        kind: ErrorKind::MissingValue(opt.name),
+                        span: punct.span(),
+                    });
+                }
+            }
+            Some(token) => {
+                return self.recover(Err(Error {
+                    kind: ErrorKind::UnexpectedToken(token.to_string()),
+                    span: token.span(),
+                }))
+            }
+        }
+
+        self.parsed += 1;
+
+        Ok(Some(opt))
+    }
+
+    /// Consumes the end of a positional argument
+    fn end_argument(&mut self) -> Result<(), Error> {
+        self.next_separator()?;
+        self.parsed += 1;
+
+        Ok(())
+    }
+
+    /// Returns the next string argument
+    ///
+    /// Adjacent string literals are concatenated into a single argument, the
+    /// same way C does, so long literals can be split across lines. The
+    /// returned span is the one of the first literal.
+    ///
+    /// The argument can also be a call of `concat!`, `env!` or `include_str!`,
+    /// which is evaluated the way the compiler would.
+    pub fn next_string(&mut self) -> Result<Option<(String, Span)>, Error> {
+        let value = self.parse_string();
+
+        self.recover(value)
+    }
+
+    fn parse_string(&mut self) -> Result<Option<(String, Span)>, Error> {
+        let value = self.parse_string_value()?;
+
+        if value.is_some() {
+            self.end_argument()?;
+        }
+
+        Ok(value)
+    }
+
+    /// Parses a string argument, leaving its separator in the stream
+    fn parse_string_value(&mut self) -> Result<Option<(String, Span)>, Error> {
+        self.origin = None;
+
+        if self.next_is_macro() {
+            return self.parse_macro().map(Some);
+        }
+
+        let (mut value, span) = if let Some((literal, span)) = self.next_raw()? {
+            (Self::try_string_literal(literal, span)?, span)
+        } else {
+            return Ok(None);
+        };
+
+        while let Some(TokenTree::Literal(_)) = self.stream.peek() {
+            if let Some((literal, next_span)) = self.next_raw()? {
+                value += &Self::try_string_literal(literal, next_span)?;
+            }
+        }
+
+        Ok(Some((value, span)))
+    }
+
+    /// Returns the next `"<key>" => <value>` argument, as in the arms of a
+    /// match
+    ///
+    /// The key is `None` for the `_ => <value>` fallback arm. The value is an
+    /// expression, ending at the first comma outside of a group like the ones
+    /// of [`ArgParser::next_expression`].
+    pub fn next_arm(&mut self) -> Result<Option<Arm>, Error> {
+        let value = self.parse_arm();
+
+        self.recover(value)
+    }
+
+    fn parse_arm(&mut self) -> Result<Option<Arm>, Error> {
+        let (key, span) = match self.stream.peek() {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "_" => {
+                let span = ident.span();
+                self.stream.next();
+
+                (None, span)
+            }
+            _ => match self.parse_string_value()? {
+                Some((key, span)) => (Some(key), span),
+                None => return Ok(None),
+            },
+        };
+
+        for ch in ['=', '>'] {
+            match self.stream.next() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ch => {}
+                Some(token) => {
+                    return Err(Error {
+                        kind: ErrorKind::UnexpectedToken(token.to_string()),
+                        span: token.span(),
+                    })
+                }
+                None => {
+                    return Err(Error {
+                        kind: ErrorKind::MissingArmValue(key.unwrap_or_else(|| "_".to_string())),
+                        span,
+                    })
+                }
+            }
+        }
+
+        // The value is passed through verbatim, up to the comma ending it. The
+        // commas between the generic arguments of a path, such as the ones of
+        // `Pair::<u8, u16>::new()`, don't end it, which tracks the angle
+        // brackets following `::` or starting a qualified path.
+        let mut value = TokenStream::new();
+        let mut depth = 0usize;
+        let mut previous: Option<Punct> = None;
+
+        for token in self.stream.by_ref() {
+            let mut punct = None;
+
+            if let TokenTree::Punct(current) = &token {
+                let joined = previous
+                    .as_ref()
+                    .filter(|v| v.spacing() == Spacing::Joint)
+                    .map(Punct::as_char);
+
+                match current.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' if depth > 0 || joined == Some(':') || value.is_empty() => depth += 1,
+                    // The `>` of `->` doesn't close an angle bracket
+                    '>' if depth > 0 && joined != Some('-') => depth -= 1,
+                    _ => {}
+                }
+
+                punct = Some(current.clone());
+            }
+
+            previous = punct;
+            value.extend([token]);
+        }
+
+        if value.is_empty() {
+            return Err(Error {
+                kind: ErrorKind::MissingArmValue(key.unwrap_or_else(|| "_".to_string())),
+                span,
+            });
+        }
+
+        self.parsed += 1;
+
+        Ok(Some(Arm { key, span, value }))
+    }
+
+    /// Returns the outer attributes given before the next argument
+    ///
+    /// Only the `cfg` and lint attributes are accepted, as these are the only
+    /// ones the list entries can forward to the generated elements.
+    pub fn next_attributes(&mut self) -> Result<Vec<Attribute>, Error> {
+        let value = self.parse_attributes();
+
+        self.recover(value)
+    }
+
+    fn parse_attributes(&mut self) -> Result<Vec<Attribute>, Error> {
+        let mut ret = Vec::new();
+
+        while let Some(TokenTree::Punct(punct)) = self.stream.peek() {
+            if punct.as_char() != '#' {
+                break;
+            }
+
+            let pound = self.stream.next().unwrap();
+            let span = pound.span();
+
+            let group = match self.stream.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
+                Some(token) => {
+                    return Err(Error {
+                        kind: ErrorKind::UnexpectedToken(token.to_string()),
+                        span: token.span(),
+                    })
+                }
+                None => {
+                    return Err(Error {
+                        kind: ErrorKind::UnexpectedToken(pound.to_string()),
+                        span,
+                    })
+                }
+            };
+
+            let mut inner = group.stream().into_iter();
+            let (name, arguments) = match (inner.next(), inner.next(), inner.next()) {
+                (Some(TokenTree::Ident(name)), Some(TokenTree::Group(arguments)), None)
+                    if arguments.delimiter() == Delimiter::Parenthesis =>
+                {
+                    (name, arguments.stream())
+                }
+                (Some(TokenTree::Ident(name)), _, _) => {
+                    return Err(Error {
+                        kind: ErrorKind::UnsupportedAttribute(name.to_string()),
+                        span: name.span(),
+                    })
+                }
+                _ => {
+                    return Err(Error {
+                        kind: ErrorKind::UnsupportedAttribute(group.stream().to_string()),
+                        span: group.span(),
+                    })
+                }
+            };
+
+            if !ENTRY_ATTRIBUTES.contains(&name.to_string().as_str()) {
+                return Err(Error {
+                    kind: ErrorKind::UnsupportedAttribute(name.to_string()),
+                    span: name.span(),
+                });
+            }
+
+            ret.push(Attribute {
+                name: name.to_string(),
+                span,
+                tokens: [pound, TokenTree::Group(group)].into_iter().collect(),
+                arguments,
+            });
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the next `<name>: [<entries>]` argument grouping entries of a
+    /// list, along with the parser of its entries
+    ///
+    /// The files and environment variables read by the entries are given back
+    /// with [`ArgParser::absorb`] once parsed.
+    pub fn next_group(&mut self) -> Result<Option<(Ident, ArgParser)>, Error> {
+        let value = self.parse_group();
+
+        self.recover(value)
+    }
+
+    fn parse_group(&mut self) -> Result<Option<(Ident, ArgParser)>, Error> {
+        let mut stream = self.stream.clone();
+
+        let name = match (stream.next(), stream.next()) {
+            (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(punct)))
+                if punct.as_char() == ':' =>
+            {
+                name
+            }
+            _ => return Ok(None),
+        };
+
+        self.stream.next();
+        self.stream.next();
+
+        match self.stream.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                self.end_argument()?;
+
+                Ok(Some((name, ArgParser::from(group.stream()))))
+            }
+            Some(token) => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+            None => Err(Error {
+                kind: ErrorKind::MissingGroup(name.to_string()),
+                span: name.span(),
+            }),
+        }
+    }
+
+    /// Takes over the files and environment variables read by the parser of
+    /// a group, so that they are tracked along with the ones of this parser
+    pub fn absorb(&mut self, group: ArgParser) {
+        self.included.extend(group.included);
+        self.env.extend(group.env);
+    }
+
+    /// Checks whether the next argument is a string, either a literal or a
+    /// built-in macro call
+    pub fn next_is_string(&mut self) -> bool {
+        match self.stream.peek() {
+            Some(TokenTree::Literal(literal)) => {
+                matches!(Literal::from(literal.clone()), Literal::String(_))
+            }
+            _ => self.next_is_macro(),
+        }
+    }
+
+    /// Returns the tokens of the next argument, given as an expression, along
+    /// with the span of its first token
+    ///
+    /// The expression ends at the first comma outside of a group, so generic
+    /// arguments holding a comma must be put in parentheses.
+    pub fn next_expression(&mut self) -> Option<(TokenStream, Span)> {
+        let span = self.stream.peek()?.span();
+        let mut expr = TokenStream::new();
+
+        for token in self.stream.by_ref() {
+            if let TokenTree::Punct(punct) = &token {
+                if punct.as_char() == ',' {
+                    break;
+                }
+            }
+
+            expr.extend([token]);
+        }
+
+        self.parsed += 1;
+
+        Some((expr, span))
+    }
+
+    fn next_is_macro(&mut self) -> bool {
+        match self.stream.peek() {
+            Some(TokenTree::Ident(ident)) => BUILTIN_MACROS.contains(&ident.to_string().as_str()),
+            _ => false,
+        }
+    }
+
+    /// Parses a call of one of the [`BUILTIN_MACROS`], returning the string
+    /// it expands to
+    fn parse_macro(&mut self) -> Result<(String, Span), Error> {
+        let (name, span) = match self.stream.next() {
+            Some(token) => (token.to_string(), token.span()),
+            None => unreachable!(),
+        };
+        let usage = match name.as_str() {
+            "concat" => "concat!(<literals>)",
+            "env" => "env!(\"<name>\")",
+            _ => "include_str!(\"<path>\")",
+        };
+        let malformed = |span| Error {
+            kind: ErrorKind::Macro(format!("Expected `{usage}`")),
+            span,
+        };
+
+        match self.stream.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '!' => {}
+            Some(token) => return Err(malformed(token.span())),
+            None => return Err(malformed(span)),
+        }
+
+        let group = match self.stream.next() {
+            Some(TokenTree::Group(group)) => group,
+            Some(token) => return Err(malformed(token.span())),
+            None => return Err(malformed(span)),
+        };
+
+        let value = match name.as_str() {
+            "concat" => self.evaluate_concat(&group)?,
+            "env" => self.evaluate_env(&group, malformed)?,
+            _ => self.evaluate_include(&group, span, malformed)?,
+        };
+
+        Ok((value, span))
+    }
+
+    /// Evaluates the arguments of `concat!`, which are literals or built-in
+    /// macro calls
+    fn evaluate_concat(&mut self, group: &Group) -> Result<String, Error> {
+        let mut inner = ArgParser::from(group.stream());
+        let mut value = String::new();
+
+        while inner.stream.peek().is_some() {
+            if inner.next_is_macro() {
+                value += &inner.parse_macro()?.0;
+                inner.next_separator()?;
+
+                continue;
+            }
+
+            match inner.stream.next() {
+                Some(TokenTree::Ident(ident))
+                    if ident.to_string() == "true" || ident.to_string() == "false" =>
+                {
+                    value += &ident.to_string();
+                }
+                Some(TokenTree::Literal(literal)) => {
+                    let span = literal.span();
+
+                    value += &match Literal::from(literal) {
+                        Literal::String(v) => v.value().to_string(),
+                        Literal::Char(v) => v.value().to_string(),
+                        Literal::Integer(v) => match v.value::<u128>() {
+                            Some(v) => v.to_string(),
+                            None => v.raw_main_part().to_string(),
+                        },
+                        Literal::Float(v) => v.number_part().to_string(),
+                        literal => {
+                            return Err(Error {
+                                span,
+                                kind: ErrorKind::BadType {
+                                    given: LiteralType::from(literal),
+                                    expected: LiteralType::String,
+                                },
+                            })
+                        }
+                    };
+                }
+                Some(token) => {
+                    return Err(Error {
+                        kind: ErrorKind::UnexpectedToken(token.to_string()),
+                        span: token.span(),
+                    })
+                }
+                None => unreachable!(),
+            }
+
+            inner.next_separator()?;
+        }
+
+        self.included.append(&mut inner.included);
+        self.env.append(&mut inner.env);
+
+        Ok(value)
+    }
+
+    /// Evaluates `env!("<name>")`, optionally followed by the message of the
+    /// error reported when the variable isn't defined
+    fn evaluate_env(
+        &mut self,
+        group: &Group,
+        malformed: impl Fn(Span) -> Error,
+    ) -> Result<String, Error> {
+        let mut inner = ArgParser::from(group.stream());
+        let (name, name_span) = match inner.next_raw()? {
+            Some((literal, name_span)) => {
+                (Self::try_string_literal(literal, name_span)?, name_span)
+            }
+            None => return Err(malformed(group.span())),
+        };
+        inner.next_separator()?;
+
+        let message = match inner.next_raw()? {
+            Some((literal, span)) => Some(Self::try_string_literal(literal, span)?),
+            None => None,
+        };
+        inner.next_separator()?;
+
+        if let Some(token) = inner.stream.next() {
+            return Err(malformed(token.span()));
+        }
+
+        let value = std::env::var(&name).map_err(|e| Error {
+            kind: ErrorKind::Macro(match (message, e) {
+                (Some(message), _) => message,
+                (None, std::env::VarError::NotPresent) => {
+                    format!("The environment variable `{name}` is not defined")
+                }
+                (None, std::env::VarError::NotUnicode(_)) => {
+                    format!("The value of the environment variable `{name}` is not valid unicode")
+                }
+            }),
+            span: name_span,
+        })?;
+
+        self.env.push(name);
+
+        Ok(value)
+    }
+
+    /// Evaluates `include_str!("<path>")`, returning the content of the file
+    fn evaluate_include(
+        &mut self,
+        group: &Group,
+        span: Span,
+        malformed: impl Fn(Span) -> Error,
+    ) -> Result<String, Error> {
+        let mut inner = ArgParser::from(group.stream());
+        let (path, path_span) = match inner.next_raw()? {
+            Some((literal, path_span)) => {
+                (Self::try_string_literal(literal, path_span)?, path_span)
+            }
+            None => return Err(malformed(group.span())),
+        };
+
+        if let Some(token) = inner.stream.next() {
+            return Err(malformed(token.span()));
+        }
+
+        let (value, included) = Included::read(&path, span).map_err(|message| Error {
+            kind: ErrorKind::Macro(message),
+            span: path_span,
+        })?;
+
+        self.origin = Some(self.included.len());
+        self.included.push(included);
+
+        Ok(value)
+    }
+
+    /// Returns the file the last string argument was included from, if any
+    pub fn origin(&self) -> Option<&Included> {
+        self.origin.map(|i| &self.included[i])
+    }
+
+    /// Makes the compiler track the included files and the environment
+    /// variables read, so that the crate is rebuilt when they change
+    pub fn track(&self, expr: TokenStream) -> TokenStream {
+        if self.included.is_empty() && self.env.is_empty() {
+            return expr;
+        }
+
+        let mut body: TokenStream = self.included.iter().map(Included::track_stream).collect();
+        body.extend(
+            self.env
+                .iter()
+                .map(|name| tokens::tracking_const("env", name)),
+        );
+        body.extend(expr);
+
+        TokenTree::Group(Group::new(Delimiter::Brace, body)).into()
+    }
+
+    /// Returns the next integer argument, `name` being the name of the
+    /// parameter reported in the errors
+    pub fn next_integer<I: IntegerArgument>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<Option<(I, Span)>, Error> {
+        let value = self.parse_integer(name);
+
+        self.recover(value)
+    }
+
+    /// Returns the next argument given as an array of integers, `name` being
+    /// the name of its elements reported in the errors, along with the span
+    /// of the array
+    pub fn next_integer_array<I: IntegerArgument>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<Option<IntegerArray<I>>, Error> {
+        let value = self.parse_integer_array(name);
+
+        self.recover(value)
+    }
+
+    fn parse_integer_array<I: IntegerArgument>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<Option<IntegerArray<I>>, Error> {
+        let group = match self.stream.peek() {
+            None => return Ok(None),
+            Some(TokenTree::Ident(_)) if self.parsed > 0 => return Ok(None),
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                group.clone()
+            }
+            Some(token) => {
+                return Err(Error {
+                    kind: ErrorKind::UnexpectedToken(token.to_string()),
+                    span: token.span(),
+                })
+            }
+        };
+        self.stream.next();
+
+        let mut inner = ArgParser::from(group.stream());
+        let mut values = Vec::new();
+
+        while let Some(value) = inner.parse_integer(name)? {
+            values.push(value);
+        }
+
+        if let Some(token) = inner.stream.next() {
+            return Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            });
+        }
+
+        self.end_argument()?;
+
+        Ok(Some((values, group.span())))
+    }
+
+    fn parse_integer<I: IntegerArgument>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<Option<(I, Span)>, Error> {
+        Ok(if let Some((literal, span)) = self.next_raw()? {
+            let value = Self::try_integer_literal(literal, span, name)?;
+            self.end_argument()?;
+
+            Some((value, span))
+        } else {
+            None
+        })
+    }
+}
+
+impl Opt {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Checks the option is a bare flag, without any value
+    pub fn flag(&self) -> Result<(), Error> {
+        match self.value.first() {
+            None => Ok(()),
+            Some(token) => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+        }
+    }
+
+    /// Returns the value of a `name = value` option when it is an integer
+    /// literal
+    pub fn value_integer<I: IntegerArgument>(&self) -> Result<I, Error> {
+        match self.value.as_slice() {
+            [TokenTree::Literal(literal)] => ArgParser::try_integer_literal(
+                Literal::from(literal.clone()),
+                literal.span(),
+                &self.name,
+            ),
+            [] => Err(Error {
+                kind: ErrorKind::MissingValue(self.name.clone()),
+                span: self.span,
+            }),
+            [token, ..] => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+        }
+    }
+
+    /// Returns the value of a `name = value` option when it is a path, such
+    /// as `my_net` or `::facade::net`
+    pub fn value_path(&self) -> Result<TokenStream, Error> {
+        let invalid = self.value.iter().find(|token| match token {
+            TokenTree::Ident(_) => false,
+            TokenTree::Punct(punct) => punct.as_char() != ':',
+            _ => true,
+        });
+
+        match (self.value.last(), invalid) {
+            (None, _) => Err(Error {
+                kind: ErrorKind::MissingValue(self.name.clone()),
+                span: self.span,
+            }),
+            (_, Some(token)) | (Some(token @ TokenTree::Punct(_)), None) => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+            _ => Ok(self.value.iter().cloned().collect()),
+        }
+    }
+
+    /// Returns the value of a `name = value` option when it is a type, which is
+    /// left to the compiler to check
+    pub fn value_type(&self) -> Result<TokenStream, Error> {
+        if self.value.is_empty() {
+            return Err(Error {
+                kind: ErrorKind::MissingValue(self.name.clone()),
+                span: self.span,
+            });
+        }
+
+        Ok(self.value.iter().cloned().collect())
+    }
+
+    /// Returns the value of a `name = value` option when it is a single
+    /// identifier
+    pub fn value_ident(&self) -> Result<String, Error> {
+        match self.value.as_slice() {
+            [TokenTree::Ident(ident)] => Ok(ident.to_string()),
+            [] => Err(Error {
+                kind: ErrorKind::MissingValue(self.name.clone()),
+                span: self.span,
+            }),
+            [token, ..] => Err(Error {
+                kind: ErrorKind::UnexpectedToken(token.to_string()),
+                span: token.span(),
+            }),
+        }
+    }
+}
+
+impl Error {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Display for LiteralType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Bool => "bool",
+            Self::Byte => "u8",
+            Self::ByteString => "[u8]",
+            Self::Char => "char",
+            Self::String => "str",
+            Self::Float => "float",
+            Self::Integer => "int",
+        })
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ErrorKind::BadType { given, expected } => {
+                writeln!(f, "Unexpected type: given `{given}`, expected `{expected}`")
+            },
+            ErrorKind::OutOfBound { name, given, expected, max } => writeln!(
+                f,
+                "The value `{given}` of `{name}` is out of bounds: expected a `{expected}` between 0 and {max}"
+            ),
+            ErrorKind::UnexpectedToken(token) => {
+                writeln!(f, "Unexpected token `{}`", token)
+            },
+            ErrorKind::MissingValue(name) => {
+                writeln!(f, "Missing value for the option `{name}`")
+            },
+            ErrorKind::MissingArmValue(key) => {
+                writeln!(f, "Missing value for the key `{key}`: expected `=> <value>`")
+            },
+            ErrorKind::UnsupportedAttribute(name) => writeln!(
+                f,
+                "Unsupported attribute `{name}`: expected `cfg(...)` or a lint attribute such as `allow(...)`"
+            ),
+            ErrorKind::MissingGroup(name) => {
+                writeln!(f, "Missing entries for the group `{name}`: expected `{name}: [<entries>]`")
+            },
+            ErrorKind::Macro(message) => writeln!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<TokenStream> for ArgParser {
+    fn from(value: TokenStream) -> Self {
+        ArgParser {
+            parsed: 0,
+            stream: value.into_iter().peekable(),
+            included: Vec::new(),
+            origin: None,
+            env: Vec::new(),
+        }
+    }
+}
+
+impl<T: litrs::Buffer> From<Literal<T>> for LiteralType {
+    fn from(value: Literal<T>) -> Self {
+        match value {
+            Literal::Bool(_) => Self::Bool,
+            Literal::Integer(_) => Self::Integer,
+            Literal::Float(_) => Self::Float,
+            Literal::Char(_) => Self::Char,
+            Literal::String(_) => Self::String,
+            Literal::Byte(_) => Self::Byte,
+            Literal::ByteString(_) => Self::ByteString,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Compile-time checks shared by the address macros
+
+use std::net::{IpAddr, SocketAddr};
+
+use proc_macro::Span;
+use proc_macro_error::abort;
+
+use crate::arg_parser::Opt;
+use crate::classify::{self, Class, Scope};
+use crate::diagnostic::Diagnostics;
+use crate::inventory;
+use crate::policy::Policy;
+use crate::{emit_parse_error, report_error};
+
+/// Environment variable enabling the `strict` option for every call
+const STRICT_VAR: &str = "FANCY_IP_STRICT";
+
+/// Checks whether strict mode is enabled for the whole build, either with
+/// `--cfg fancy_ip_strict` or with the `FANCY_IP_STRICT` variable
+fn global_strict() -> bool {
+    cfg!(fancy_ip_strict) || std::env::var_os(STRICT_VAR).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Parses the address class given as the value of an option
+fn parse_class(opt: &Opt) -> Class {
+    let name = report_error(opt.value_ident());
+
+    match Class::from_name(&name) {
+        Some(class) => class,
+        None => {
+            abort!(
+                opt.span(),
+                "Unknown address class `{}`, expected one of `unspecified`, `broadcast`, `loopback`, \
+                `private`, `unique_local`, `shared`, `link_local`, `documentation`, \
+                `benchmarking`, `multicast` or `global`",
+                name
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Checks {
+    strict: bool,
+    allow_reserved: bool,
+    allow_site_local: bool,
+    deny_special_purpose: bool,
+    target: bool,
+    allow_multicast: bool,
+    allow_outside_ranges: bool,
+    bind: bool,
+    scope: Option<Scope>,
+    expect: Option<Class>,
+    deny: Vec<Class>,
+    warn: Vec<Class>,
+}
+
+impl Checks {
+    /// Parses a check option, returning `false` if the option is unknown
+    pub fn parse_option(&mut self, opt: &Opt) -> bool {
+        match opt.name() {
+            "strict" => self.strict = true,
+            "allow_reserved" => self.allow_reserved = true,
+            "allow_site_local" => self.allow_site_local = true,
+            "deny_special_purpose" => self.deny_special_purpose = true,
+            "target" => self.target = true,
+            "allow_multicast" => self.allow_multicast = true,
+            "allow_outside_ranges" => self.allow_outside_ranges = true,
+            "bind" => self.bind = true,
+            "scope" => {
+                let name = report_error(opt.value_ident());
+
+                match Scope::from_name(&name) {
+                    Some(scope) => self.scope = Some(scope),
+                    None => {
+                        abort!(
+                            opt.span(),
+                            "Unknown scope `{}`, expected one of `interface_local`, `link_local`, \
+                            `realm_local`, `admin_local`, `site_local`, `organization_local` or `global`",
+                            name
+                        );
+                    }
+                }
+
+                return true;
+            }
+            "expect" => {
+                self.expect = Some(parse_class(opt));
+
+                return true;
+            }
+            "deny" => {
+                self.deny.push(parse_class(opt));
+
+                return true;
+            }
+            "warn" => {
+                self.warn.push(parse_class(opt));
+
+                return true;
+            }
+            _ => return false,
+        }
+
+        emit_parse_error(opt.flag());
+
+        true
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::new(self.strict || global_strict())
+            .allowing_outside_ranges(self.allow_outside_ranges)
+    }
+
+    pub fn check(&self, diagnostics: &mut Diagnostics, addr: &IpAddr, span: Span) {
+        diagnostics.track_policy();
+
+        match &*Policy::get() {
+            Ok(policy) => {
+                if let Some(entry) = policy.denied(addr) {
+                    let message = match &entry.message {
+                        Some(message) => format!(": {message}"),
+                        None => String::new(),
+                    };
+
+                    diagnostics.error(
+                        span,
+                        format!(
+                            "The address `{addr}` is denied by the prefix `{}` of the address policy{message}",
+                            entry.prefix
+                        ),
+                    );
+                }
+
+                if !self.allow_outside_ranges && !policy.allowed(addr) {
+                    diagnostics.error(
+                        span,
+                        format!(
+                            "The address `{addr}` is outside of the allowed ranges of the address policy, \
+                            add the `allow_outside_ranges` option if this is intended"
+                        ),
+                    );
+                }
+            }
+            Err(e) => diagnostics.error(span, e.clone()),
+        }
+
+        if !self.allow_reserved {
+            if let Some(block) = classify::reserved_block(addr) {
+                diagnostics.warn(
+                    span,
+                    format!(
+                        "The address `{addr}` belongs to the reserved block {block}, \
+                        add the `allow_reserved` option if this is intended"
+                    ),
+                );
+            }
+        }
+
+        if !self.allow_site_local && classify::is_site_local(addr) {
+            diagnostics.warn(
+                span,
+                format!(
+                    "The address `{addr}` is a site-local address, deprecated by RFC 3879, \
+                    use a unique local address of `fc00::/7` instead or add the `allow_site_local` \
+                    option if this is intended"
+                ),
+            );
+        }
+
+        if self.deny_special_purpose {
+            if let Some((block, name)) = classify::special_purpose_block(addr) {
+                diagnostics.error(
+                    span,
+                    format!(
+                        "The address `{addr}` belongs to the special-purpose block {block} ({name}), \
+                        which the `deny_special_purpose` option rejects"
+                    ),
+                );
+            }
+        }
+
+        if self.target {
+            let kind = if addr.is_unspecified() {
+                Some("the unspecified address")
+            } else if matches!(addr, IpAddr::V4(addr) if addr.is_broadcast()) {
+                Some("the broadcast address")
+            } else if addr.is_multicast() && !self.allow_multicast {
+                Some("a multicast address (add the `allow_multicast` option if this is intended)")
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                diagnostics.error(
+                    span,
+                    format!("The address `{addr}` is not a valid destination: it is {kind}"),
+                );
+            }
+        }
+
+        if let Some(expected) = self.scope {
+            let scope = classify::scope(addr);
+
+            if scope != Some(expected) {
+                let scope = scope.map(|v| v.name()).unwrap_or("a reserved");
+
+                diagnostics.error(
+                    span,
+                    format!(
+                        "The address `{addr}` has {scope} scope, expected {} scope",
+                        expected.name()
+                    ),
+                );
+            }
+        }
+
+        for (classes, option, verb) in [
+            (&self.deny, "deny", "rejects"),
+            (&self.warn, "warn", "reports"),
+        ] {
+            if let Some(class) = classes.iter().find(|class| class.contains(addr)) {
+                let message = format!(
+                    "The address `{addr}` is {}, which the `{option} = {}` option {verb}",
+                    class.description(),
+                    class.name()
+                );
+
+                if option == "deny" {
+                    diagnostics.error(span, message);
+                } else {
+                    diagnostics.warn(span, message);
+                }
+            }
+        }
+
+        if let Some(expected) = self.expect {
+            if !expected.contains(addr) {
+                diagnostics.error(
+                    span,
+                    format!(
+                        "The address `{addr}` is not {}, as expected by the `expect` option",
+                        expected.description()
+                    ),
+                );
+            }
+        }
+    }
+
+    pub fn check_socket(&self, diagnostics: &mut Diagnostics, socket: &SocketAddr, span: Span) {
+        self.check(diagnostics, &socket.ip(), span);
+
+        if self.target && socket.port() == 0 {
+            diagnostics.error(
+                span,
+                format!("The socket address `{socket}` is not a valid destination: its port is 0"),
+            );
+        }
+
+        if self.bind {
+            if let Some(location) = inventory::register_bind(socket, span) {
+                diagnostics.warn(
+                    span,
+                    format!(
+                        "The socket address `{socket}` collides with the one bound at {location}"
+                    ),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,496 @@
+//! Address classification used by the compile-time checks
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Checks whether an IPv4 address belongs to the given prefix
+pub fn in_ipv4_prefix(addr: &Ipv4Addr, network: [u8; 4], len: u32) -> bool {
+    let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+
+    u32::from(*addr) & mask == u32::from_be_bytes(network) & mask
+}
+
+/// Checks whether an IPv6 address belongs to the given prefix
+pub fn in_ipv6_prefix(addr: &Ipv6Addr, network: [u16; 8], len: u32) -> bool {
+    let mask = u128::MAX.checked_shl(128 - len).unwrap_or(0);
+    let [a, b, c, d, e, f, g, h] = network;
+
+    u128::from(*addr) & mask == u128::from(Ipv6Addr::new(a, b, c, d, e, f, g, h)) & mask
+}
+
+/// IPv6 blocks reserved by the IETF, outside of the IPv4-embedding ones
+const IPV6_RESERVED_BLOCKS: [([u16; 8], u32, &str); 14] = [
+    ([0x0100, 0, 0, 0, 0, 0, 0, 0], 8, "100::/8"),
+    ([0x0200, 0, 0, 0, 0, 0, 0, 0], 7, "200::/7"),
+    ([0x0400, 0, 0, 0, 0, 0, 0, 0], 6, "400::/6"),
+    ([0x0800, 0, 0, 0, 0, 0, 0, 0], 5, "800::/5"),
+    ([0x1000, 0, 0, 0, 0, 0, 0, 0], 4, "1000::/4"),
+    ([0x4000, 0, 0, 0, 0, 0, 0, 0], 3, "4000::/3"),
+    ([0x6000, 0, 0, 0, 0, 0, 0, 0], 3, "6000::/3"),
+    ([0x8000, 0, 0, 0, 0, 0, 0, 0], 3, "8000::/3"),
+    ([0xa000, 0, 0, 0, 0, 0, 0, 0], 3, "a000::/3"),
+    ([0xc000, 0, 0, 0, 0, 0, 0, 0], 3, "c000::/3"),
+    ([0xe000, 0, 0, 0, 0, 0, 0, 0], 4, "e000::/4"),
+    ([0xf000, 0, 0, 0, 0, 0, 0, 0], 5, "f000::/5"),
+    ([0xf800, 0, 0, 0, 0, 0, 0, 0], 6, "f800::/6"),
+    ([0xfe00, 0, 0, 0, 0, 0, 0, 0], 9, "fe00::/9"),
+];
+
+/// Blocks of the IANA IPv4 special-purpose address registry
+const IPV4_SPECIAL_PURPOSE_BLOCKS: [([u8; 4], u32, &str, &str); 18] = [
+    ([0, 0, 0, 0], 8, "0.0.0.0/8", "This network"),
+    ([10, 0, 0, 0], 8, "10.0.0.0/8", "Private-Use"),
+    ([100, 64, 0, 0], 10, "100.64.0.0/10", "Shared Address Space"),
+    ([127, 0, 0, 0], 8, "127.0.0.0/8", "Loopback"),
+    ([169, 254, 0, 0], 16, "169.254.0.0/16", "Link Local"),
+    ([172, 16, 0, 0], 12, "172.16.0.0/12", "Private-Use"),
+    (
+        [192, 0, 0, 0],
+        24,
+        "192.0.0.0/24",
+        "IETF Protocol Assignments",
+    ),
+    (
+        [192, 0, 2, 0],
+        24,
+        "192.0.2.0/24",
+        "Documentation (TEST-NET-1)",
+    ),
+    ([192, 31, 196, 0], 24, "192.31.196.0/24", "AS112-v4"),
+    ([192, 52, 193, 0], 24, "192.52.193.0/24", "AMT"),
+    (
+        [192, 88, 99, 0],
+        24,
+        "192.88.99.0/24",
+        "Deprecated (6to4 Relay Anycast)",
+    ),
+    ([192, 168, 0, 0], 16, "192.168.0.0/16", "Private-Use"),
+    (
+        [192, 175, 48, 0],
+        24,
+        "192.175.48.0/24",
+        "Direct Delegation AS112 Service",
+    ),
+    ([198, 18, 0, 0], 15, "198.18.0.0/15", "Benchmarking"),
+    (
+        [198, 51, 100, 0],
+        24,
+        "198.51.100.0/24",
+        "Documentation (TEST-NET-2)",
+    ),
+    (
+        [203, 0, 113, 0],
+        24,
+        "203.0.113.0/24",
+        "Documentation (TEST-NET-3)",
+    ),
+    ([240, 0, 0, 0], 4, "240.0.0.0/4", "Reserved"),
+    (
+        [255, 255, 255, 255],
+        32,
+        "255.255.255.255/32",
+        "Limited Broadcast",
+    ),
+];
+
+/// Blocks of the IANA IPv6 special-purpose address registry
+const IPV6_SPECIAL_PURPOSE_BLOCKS: [([u16; 8], u32, &str, &str); 25] = [
+    ([0, 0, 0, 0, 0, 0, 0, 1], 128, "::1/128", "Loopback Address"),
+    (
+        [0, 0, 0, 0, 0, 0, 0, 0],
+        128,
+        "::/128",
+        "Unspecified Address",
+    ),
+    (
+        [0, 0, 0, 0, 0, 0xffff, 0, 0],
+        96,
+        "::ffff:0:0/96",
+        "IPv4-mapped Address",
+    ),
+    (
+        [0x64, 0xff9b, 0, 0, 0, 0, 0, 0],
+        96,
+        "64:ff9b::/96",
+        "IPv4-IPv6 Translat.",
+    ),
+    (
+        [0x64, 0xff9b, 1, 0, 0, 0, 0, 0],
+        48,
+        "64:ff9b:1::/48",
+        "IPv4-IPv6 Translat.",
+    ),
+    (
+        [0x0100, 0, 0, 0, 0, 0, 0, 0],
+        64,
+        "100::/64",
+        "Discard-Only Address Block",
+    ),
+    (
+        [0x0100, 0, 0, 1, 0, 0, 0, 0],
+        64,
+        "100:0:0:1::/64",
+        "Dummy IPv6 Prefix",
+    ),
+    (
+        [0x2001, 0, 0, 0, 0, 0, 0, 0],
+        23,
+        "2001::/23",
+        "IETF Protocol Assignments",
+    ),
+    ([0x2001, 0, 0, 0, 0, 0, 0, 0], 32, "2001::/32", "TEREDO"),
+    (
+        [0x2001, 1, 0, 0, 0, 0, 0, 1],
+        128,
+        "2001:1::1/128",
+        "Port Control Protocol Anycast",
+    ),
+    (
+        [0x2001, 1, 0, 0, 0, 0, 0, 2],
+        128,
+        "2001:1::2/128",
+        "Traversal Using Relays around NAT Anycast",
+    ),
+    (
+        [0x2001, 1, 0, 0, 0, 0, 0, 3],
+        128,
+        "2001:1::3/128",
+        "DNS-SD Service Registration Protocol Anycast",
+    ),
+    (
+        [0x2001, 2, 0, 0, 0, 0, 0, 0],
+        48,
+        "2001:2::/48",
+        "Benchmarking",
+    ),
+    ([0x2001, 3, 0, 0, 0, 0, 0, 0], 32, "2001:3::/32", "AMT"),
+    (
+        [0x2001, 4, 0x0112, 0, 0, 0, 0, 0],
+        48,
+        "2001:4:112::/48",
+        "AS112-v6",
+    ),
+    (
+        [0x2001, 0x0010, 0, 0, 0, 0, 0, 0],
+        28,
+        "2001:10::/28",
+        "Deprecated (previously ORCHID)",
+    ),
+    (
+        [0x2001, 0x0020, 0, 0, 0, 0, 0, 0],
+        28,
+        "2001:20::/28",
+        "ORCHIDv2",
+    ),
+    (
+        [0x2001, 0x0030, 0, 0, 0, 0, 0, 0],
+        28,
+        "2001:30::/28",
+        "Drone Remote ID Protocol Entity Tags (DETs) Prefix",
+    ),
+    (
+        [0x2001, 0x0db8, 0, 0, 0, 0, 0, 0],
+        32,
+        "2001:db8::/32",
+        "Documentation",
+    ),
+    ([0x2002, 0, 0, 0, 0, 0, 0, 0], 16, "2002::/16", "6to4"),
+    (
+        [0x2620, 0x004f, 0x8000, 0, 0, 0, 0, 0],
+        48,
+        "2620:4f:8000::/48",
+        "Direct Delegation AS112 Service",
+    ),
+    (
+        [0x3fff, 0, 0, 0, 0, 0, 0, 0],
+        20,
+        "3fff::/20",
+        "Documentation",
+    ),
+    (
+        [0x5f00, 0, 0, 0, 0, 0, 0, 0],
+        16,
+        "5f00::/16",
+        "Segment Routing (SRv6) SIDs",
+    ),
+    ([0xfc00, 0, 0, 0, 0, 0, 0, 0], 7, "fc00::/7", "Unique-Local"),
+    (
+        [0xfe80, 0, 0, 0, 0, 0, 0, 0],
+        10,
+        "fe80::/10",
+        "Link-Local Unicast",
+    ),
+];
+
+/// Returns the block of the IANA special-purpose address registries the
+/// address belongs to, if any, along with its name
+///
+/// When the address belongs to nested blocks, the most specific one is
+/// returned.
+pub fn special_purpose_block(addr: &IpAddr) -> Option<(&'static str, &'static str)> {
+    match addr {
+        IpAddr::V4(addr) => IPV4_SPECIAL_PURPOSE_BLOCKS
+            .iter()
+            .filter(|(network, len, ..)| in_ipv4_prefix(addr, *network, *len))
+            .max_by_key(|(_, len, ..)| *len)
+            .map(|(_, _, block, name)| (*block, *name)),
+        IpAddr::V6(addr) => IPV6_SPECIAL_PURPOSE_BLOCKS
+            .iter()
+            .filter(|(network, len, ..)| in_ipv6_prefix(addr, *network, *len))
+            .max_by_key(|(_, len, ..)| *len)
+            .map(|(_, _, block, name)| (*block, *name)),
+    }
+}
+
+/// Returns the reserved block the address belongs to, if any
+///
+/// Reserved blocks are the ones which are not expected to be used by any
+/// host: `0.0.0.0/8`, `240.0.0.0/4` and the IPv6 blocks reserved by the IETF.
+/// The unspecified and broadcast addresses, although being part of such blocks,
+/// are well-known and are not reported, nor are the IPv6 addresses of the
+/// special-purpose blocks assigned out of the reserved ones.
+pub fn reserved_block(addr: &IpAddr) -> Option<&'static str> {
+    match addr {
+        IpAddr::V4(addr) => {
+            if addr.is_unspecified() || addr.is_broadcast() {
+                None
+            } else if in_ipv4_prefix(addr, [0, 0, 0, 0], 8) {
+                Some("0.0.0.0/8")
+            } else if in_ipv4_prefix(addr, [240, 0, 0, 0], 4) {
+                Some("240.0.0.0/4")
+            } else {
+                None
+            }
+        }
+        IpAddr::V6(addr) => {
+            if special_purpose_block(&IpAddr::V6(*addr)).is_some() {
+                // Assigned out of a reserved block, such as the discard-only
+                // `100::/64` or the SRv6 SIDs of `5f00::/16`
+                return None;
+            }
+
+            IPV6_RESERVED_BLOCKS
+                .iter()
+                .find(|(network, len, _)| in_ipv6_prefix(addr, *network, *len))
+                .map(|(_, _, name)| *name)
+        }
+    }
+}
+
+/// Checks whether the address is a deprecated `fec0::/10` IPv6 site-local
+/// unicast address
+pub fn is_site_local(addr: &IpAddr) -> bool {
+    matches!(addr, IpAddr::V6(addr) if in_ipv6_prefix(addr, [0xfec0, 0, 0, 0, 0, 0, 0, 0], 10))
+}
+
+/// Reach of an address, as defined for IPv6 multicast addresses by RFC 7346
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    InterfaceLocal,
+    LinkLocal,
+    RealmLocal,
+    AdminLocal,
+    SiteLocal,
+    OrganizationLocal,
+    Global,
+}
+
+impl Scope {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "interface_local" => Self::InterfaceLocal,
+            "link_local" => Self::LinkLocal,
+            "realm_local" => Self::RealmLocal,
+            "admin_local" => Self::AdminLocal,
+            "site_local" => Self::SiteLocal,
+            "organization_local" => Self::OrganizationLocal,
+            "global" => Self::Global,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InterfaceLocal => "interface_local",
+            Self::LinkLocal => "link_local",
+            Self::RealmLocal => "realm_local",
+            Self::AdminLocal => "admin_local",
+            Self::SiteLocal => "site_local",
+            Self::OrganizationLocal => "organization_local",
+            Self::Global => "global",
+        }
+    }
+}
+
+/// Returns the scope of an address
+///
+/// The scope of IPv6 multicast addresses is given by their scope field, the
+/// scope of IPv4 multicast addresses follows RFC 2365. Loopback addresses are
+/// interface-local and link-local unicast addresses are link-local. Other
+/// unicast addresses are global, except for the deprecated IPv6 site-local
+/// ones. Returns `None` for the reserved multicast scopes.
+pub fn scope(addr: &IpAddr) -> Option<Scope> {
+    Some(match addr {
+        IpAddr::V4(addr) => {
+            if addr.is_loopback() {
+                Scope::InterfaceLocal
+            } else if addr.is_link_local() || in_ipv4_prefix(addr, [224, 0, 0, 0], 24) {
+                Scope::LinkLocal
+            } else if in_ipv4_prefix(addr, [239, 255, 0, 0], 16) {
+                Scope::SiteLocal
+            } else if in_ipv4_prefix(addr, [239, 0, 0, 0], 8) {
+                Scope::OrganizationLocal
+            } else {
+                Scope::Global
+            }
+        }
+        IpAddr::V6(addr) => {
+            if addr.is_multicast() {
+                match addr.segments()[0] & 0xf {
+                    0x1 => Scope::InterfaceLocal,
+                    0x2 => Scope::LinkLocal,
+                    0x3 => Scope::RealmLocal,
+                    0x4 => Scope::AdminLocal,
+                    0x5 => Scope::SiteLocal,
+                    0x8 => Scope::OrganizationLocal,
+                    0xe => Scope::Global,
+                    _ => return None,
+                }
+            } else if addr.is_loopback() {
+                Scope::InterfaceLocal
+            } else if in_ipv6_prefix(addr, [0xfe80, 0, 0, 0, 0, 0, 0, 0], 10) {
+                Scope::LinkLocal
+            } else if is_site_local(&IpAddr::V6(*addr)) {
+                Scope::SiteLocal
+            } else {
+                Scope::Global
+            }
+        }
+    })
+}
+
+/// Address classes checked by the `expect`, `deny` and `warn` options
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Unspecified,
+    Broadcast,
+    Loopback,
+    Private,
+    UniqueLocal,
+    Shared,
+    LinkLocal,
+    Documentation,
+    Benchmarking,
+    Multicast,
+    Global,
+}
+
+impl Class {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "unspecified" => Self::Unspecified,
+            "broadcast" => Self::Broadcast,
+            "loopback" => Self::Loopback,
+            "private" => Self::Private,
+            "unique_local" => Self::UniqueLocal,
+            "shared" => Self::Shared,
+            "link_local" => Self::LinkLocal,
+            "documentation" => Self::Documentation,
+            "benchmarking" => Self::Benchmarking,
+            "multicast" => Self::Multicast,
+            "global" => Self::Global,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "unspecified",
+            Self::Broadcast => "broadcast",
+            Self::Loopback => "loopback",
+            Self::Private => "private",
+            Self::UniqueLocal => "unique_local",
+            Self::Shared => "shared",
+            Self::LinkLocal => "link_local",
+            Self::Documentation => "documentation",
+            Self::Benchmarking => "benchmarking",
+            Self::Multicast => "multicast",
+            Self::Global => "global",
+        }
+    }
+
+    /// Returns the description of the class, used in the error messages
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "the unspecified address",
+            Self::Broadcast => "the broadcast address",
+            Self::Loopback => "a loopback address",
+            Self::Private => "a private address",
+            Self::UniqueLocal => "a unique local address",
+            Self::Shared => "a shared address",
+            Self::LinkLocal => "a link-local address",
+            Self::Documentation => "a documentation address",
+            Self::Benchmarking => "a benchmarking address",
+            Self::Multicast => "a multicast address",
+            Self::Global => "a globally reachable address",
+        }
+    }
+
+    /// Checks whether the address belongs to the class
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self, addr) {
+            (Self::Unspecified, _) => addr.is_unspecified(),
+            (Self::Broadcast, IpAddr::V4(addr)) => addr.is_broadcast(),
+            (Self::Broadcast, IpAddr::V6(_)) => false,
+            (Self::Loopback, _) => addr.is_loopback(),
+            (Self::Private, IpAddr::V4(addr)) => addr.is_private(),
+            (Self::Private | Self::UniqueLocal, IpAddr::V6(addr)) => {
+                in_ipv6_prefix(addr, [0xfc00, 0, 0, 0, 0, 0, 0, 0], 7)
+            }
+            (Self::UniqueLocal, IpAddr::V4(_)) => false,
+            (Self::Shared, IpAddr::V4(addr)) => in_ipv4_prefix(addr, [100, 64, 0, 0], 10),
+            (Self::Shared, IpAddr::V6(_)) => false,
+            (Self::LinkLocal, IpAddr::V4(addr)) => addr.is_link_local(),
+            (Self::LinkLocal, IpAddr::V6(addr)) => {
+                in_ipv6_prefix(addr, [0xfe80, 0, 0, 0, 0, 0, 0, 0], 10)
+            }
+            (Self::Documentation, IpAddr::V4(addr)) => addr.is_documentation(),
+            (Self::Documentation, IpAddr::V6(addr)) => {
+                in_ipv6_prefix(addr, [0x2001, 0x0db8, 0, 0, 0, 0, 0, 0], 32)
+                    || in_ipv6_prefix(addr, [0x3fff, 0, 0, 0, 0, 0, 0, 0], 20)
+            }
+            (Self::Benchmarking, IpAddr::V4(addr)) => in_ipv4_prefix(addr, [198, 18, 0, 0], 15),
+            (Self::Benchmarking, IpAddr::V6(addr)) => {
+                in_ipv6_prefix(addr, [0x2001, 0x0002, 0, 0, 0, 0, 0, 0], 48)
+            }
+            (Self::Multicast, _) => addr.is_multicast(),
+            (Self::Global, _) => is_global(addr),
+        }
+    }
+}
+
+/// Checks whether the address is expected to be globally reachable
+///
+/// This excludes the addresses of the other classes, the reserved blocks, the
+/// SRv6 SIDs and the multicast addresses without the global scope.
+fn is_global(addr: &IpAddr) -> bool {
+    const LOCAL_CLASSES: [Class; 9] = [
+        Class::Unspecified,
+        Class::Broadcast,
+        Class::Loopback,
+        Class::Private,
+        Class::Shared,
+        Class::LinkLocal,
+        Class::Documentation,
+        Class::Benchmarking,
+        Class::Multicast,
+    ];
+
+    if addr.is_multicast() {
+        return scope(addr) == Some(Scope::Global);
+    }
+
+    !LOCAL_CLASSES.iter().any(|class| class.contains(addr))
+        && reserved_block(addr).is_none()
+        && !matches!(addr, IpAddr::V6(addr) if in_ipv6_prefix(addr, [0x5f00, 0, 0, 0, 0, 0, 0, 0], 16))
+        && scope(addr) == Some(Scope::Global)
+}
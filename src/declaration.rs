@@ -0,0 +1,732 @@
+//! Parsing of the items declaring addresses
+//!
+//! The declarations of `addresses!` have the form `<attributes> <visibility>
+//! NAME: Type = <arguments>;`, the arguments being the ones of the address
+//! macro matching the type. The enums given to `#[ip_table]` declare the
+//! address of their variants with `#[addr = "<address>"]` or
+//! `#[addr(<arguments>)]` attributes, and the fields of the structs deriving
+//! `IpDefaults` their default address with `#[ip(default = <arguments>)]`.
+//! The `const` and `static` items given to `#[address]` are initialized with
+//! the string representation of their address.
+
+use std::iter::Peekable;
+
+use proc_macro::{Delimiter, Group, Ident, Spacing, Span, TokenStream, TokenTree};
+
+pub struct Declaration {
+    /// Attributes and visibility of the constant
+    pub prefix: TokenStream,
+    pub name: Ident,
+    pub ty: Ident,
+    /// Tokens following the `=`
+    pub value: TokenStream,
+}
+
+pub struct Error {
+    pub span: Span,
+    pub message: String,
+}
+
+/// Parses the declarations separated by `;`
+///
+/// Malformed declarations are returned as errors, so that the following ones
+/// are still parsed.
+pub fn parse(stream: TokenStream) -> Vec<Result<Declaration, Error>> {
+    let mut ret = Vec::new();
+    let mut tokens = Vec::new();
+
+    for token in stream {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ';' => {
+                if !tokens.is_empty() {
+                    ret.push(parse_declaration(std::mem::take(&mut tokens), punct.span()));
+                }
+            }
+            _ => tokens.push(token),
+        }
+    }
+
+    if let Some(token) = tokens.last() {
+        let span = token.span();
+
+        ret.push(Err(Error {
+            span,
+            message: String::from("Expected `;` after the declaration"),
+        }));
+    }
+
+    ret
+}
+
+/// Parses a declaration, `end` being the span of the `;` ending it
+fn parse_declaration(tokens: Vec<TokenTree>, end: Span) -> Result<Declaration, Error> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut prefix = TokenStream::new();
+
+    while let Some((pound, group)) = next_attribute(&mut tokens)? {
+        prefix.extend([pound, TokenTree::Group(group)]);
+    }
+
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            prefix.extend(tokens.next());
+
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    prefix.extend(tokens.next());
+                }
+            }
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        token => return Err(expected(token, end, "the name of the constant")),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+        token => return Err(expected(token, end, "`:`")),
+    }
+
+    let ty = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        token => return Err(expected(token, end, "the type of the constant")),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {}
+        token => return Err(expected(token, end, "`=`")),
+    }
+
+    let value: TokenStream = tokens.collect();
+
+    if value.is_empty() {
+        return Err(Error {
+            span: end,
+            message: format!("Missing the address of `{name}`"),
+        });
+    }
+
+    Ok(Declaration {
+        prefix,
+        name,
+        ty,
+        value,
+    })
+}
+
+fn expected(token: Option<TokenTree>, end: Span, what: &str) -> Error {
+    match token {
+        Some(token) => Error {
+            span: token.span(),
+            message: format!("Unexpected token `{token}`: expected {what}"),
+        },
+        None => Error {
+            span: end,
+            message: format!("Expected {what}"),
+        },
+    }
+}
+
+/// Enum given to `#[ip_table]`
+pub struct Enum {
+    /// Tokens preceding the name of the enum, up to the `enum` keyword
+    pub prefix: TokenStream,
+    pub name: Ident,
+    pub variants: Vec<Variant>,
+}
+
+pub struct Variant {
+    /// Attributes of the variant, without its `#[addr]` attribute
+    pub attributes: TokenStream,
+    pub name: Ident,
+    /// Arguments of the `#[addr]` attribute
+    pub addr: TokenStream,
+    /// Discriminant of the variant, if any, including the `=`
+    pub discriminant: TokenStream,
+}
+
+/// Parses an enum made of unit variants
+///
+/// Malformed variants are returned as errors, so that the following ones are
+/// still parsed.
+pub fn parse_enum(stream: TokenStream) -> Result<(Enum, Vec<Error>), Error> {
+    let mut tokens = stream.into_iter().peekable();
+    let prefix = next_keyword(&mut tokens, &["enum"])?;
+    let name = next_name(&mut tokens, "enum")?;
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group,
+        token => {
+            return Err(expected(
+                token,
+                Span::call_site(),
+                "the variants of the enum",
+            ))
+        }
+    };
+
+    let mut variants = Vec::new();
+    let mut errors = Vec::new();
+
+    for (tokens, end) in split_list(&body) {
+        match parse_variant(tokens, end) {
+            Ok(v) => variants.push(v),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Ok((
+        Enum {
+            prefix,
+            name,
+            variants,
+        },
+        errors,
+    ))
+}
+
+/// Parses a variant, `end` being the span of the token following it
+fn parse_variant(tokens: Vec<TokenTree>, end: Span) -> Result<Variant, Error> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut attributes = TokenStream::new();
+    let mut addr = None;
+
+    while let Some((pound, group)) = next_attribute(&mut tokens)? {
+        let mut inner = group.stream().into_iter();
+
+        match inner.next() {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "addr" => {
+                let value: TokenStream = match inner.next() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => inner.collect(),
+                    Some(TokenTree::Group(value))
+                        if value.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        value.stream()
+                    }
+                    token => return Err(expected(token, group.span_close(), "`=` or `(`")),
+                };
+
+                if value.is_empty() {
+                    return Err(Error {
+                        span: group.span_close(),
+                        message: String::from("Missing the address of the variant"),
+                    });
+                }
+
+                addr = Some(value);
+            }
+            _ => attributes.extend([pound, TokenTree::Group(group)]),
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        token => return Err(expected(token, end, "the name of the variant")),
+    };
+
+    if let Some(TokenTree::Group(group)) = tokens.peek() {
+        return Err(Error {
+            span: group.span(),
+            message: format!("Only unit variants are supported, `{name}` has fields"),
+        });
+    }
+
+    let addr = match addr {
+        Some(addr) => addr,
+        None => {
+            return Err(Error {
+                span: name.span(),
+                message: format!("Missing the `#[addr = \"<address>\"]` attribute of `{name}`"),
+            })
+        }
+    };
+
+    Ok(Variant {
+        attributes,
+        name,
+        addr,
+        discriminant: tokens.collect(),
+    })
+}
+
+/// Returns the next outer attribute, as its `#` and its bracketed group
+fn next_attribute(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+) -> Result<Option<(TokenTree, Group)>, Error> {
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {}
+        _ => return Ok(None),
+    }
+
+    let pound = tokens.next().unwrap();
+
+    match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+            Ok(Some((pound, group)))
+        }
+        token => Err(expected(token, pound.span(), "an attribute")),
+    }
+}
+
+/// `const` or `static` item given to `#[address]`
+pub struct Item {
+    /// Tokens preceding the name of the item, up to its `const`, `static` or
+    /// `static mut` keywords
+    pub prefix: TokenStream,
+    pub name: Ident,
+    pub ty: Vec<TokenTree>,
+    /// Initializer of the item
+    pub value: TokenStream,
+}
+
+/// Parses a `const` or `static` item
+pub fn parse_item(stream: TokenStream) -> Result<Item, Error> {
+    let mut tokens = stream.into_iter().peekable();
+    let mut prefix = next_keyword(&mut tokens, &["const", "static"])?;
+
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "mut" {
+            prefix.extend(tokens.next());
+        }
+    }
+
+    let end = Span::call_site();
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        token => return Err(expected(token, end, "the name of the item")),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+        token => return Err(expected(token, end, "`:`")),
+    }
+
+    let mut ty = Vec::new();
+
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => break,
+            Some(token) => ty.push(token),
+            None => return Err(expected(None, end, "`=`")),
+        }
+    }
+
+    let mut value: Vec<TokenTree> = tokens.collect();
+
+    if matches!(value.last(), Some(TokenTree::Punct(punct)) if punct.as_char() == ';') {
+        value.pop();
+    }
+
+    if value.is_empty() {
+        return Err(Error {
+            span: end,
+            message: format!("Missing the address of `{name}`"),
+        });
+    }
+
+    Ok(Item {
+        prefix,
+        name,
+        ty,
+        value: value.into_iter().collect(),
+    })
+}
+
+/// Struct given to `#[derive(IpDefaults)]`
+pub struct Struct {
+    pub name: Ident,
+    pub generics: Generics,
+    pub fields: Fields,
+}
+
+/// Generic parameters and `where` clause of a struct
+#[derive(Default)]
+pub struct Generics {
+    /// Parameters of the struct, without their default value
+    pub params: Vec<TokenStream>,
+    /// Parameters given as arguments of the struct, such as `'a`, `T` or `N`
+    pub arguments: Vec<TokenStream>,
+    /// Names of the type parameters
+    pub types: Vec<Ident>,
+    /// Predicates of the `where` clause, without the `where` keyword
+    pub predicates: Vec<TokenStream>,
+}
+
+pub enum Fields {
+    Named(Vec<Field>),
+    Unnamed(Vec<Field>),
+    Unit,
+}
+
+pub struct Field {
+    /// Name of the field, `None` for the fields of tuple structs
+    pub name: Option<Ident>,
+    pub ty: Vec<TokenTree>,
+    /// Arguments following `default =` in the `#[ip(...)]` attribute
+    pub default: Option<TokenStream>,
+}
+
+/// Parses a struct
+///
+/// Malformed fields are returned as errors, so that the following ones are
+/// still parsed.
+pub fn parse_struct(stream: TokenStream) -> Result<(Struct, Vec<Error>), Error> {
+    let mut tokens = stream.into_iter().peekable();
+    next_keyword(&mut tokens, &["struct"])?;
+    let name = next_ident(&mut tokens, "struct")?;
+    let mut generics = parse_generics(&mut tokens)?;
+    parse_where_clause(&mut tokens, &mut generics);
+
+    let mut errors = Vec::new();
+    let mut parse_fields = |body: &Group, named| {
+        split_list(body)
+            .into_iter()
+            .filter_map(|(tokens, end)| match parse_field(tokens, end, named) {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    errors.push(e);
+
+                    None
+                }
+            })
+            .collect()
+    };
+
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            Fields::Named(parse_fields(&group, true))
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let fields = Fields::Unnamed(parse_fields(&group, false));
+            // The `where` clause of tuple structs follows their fields
+            parse_where_clause(&mut tokens, &mut generics);
+
+            fields
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => Fields::Unit,
+        token => {
+            return Err(expected(
+                token,
+                Span::call_site(),
+                "the fields of the struct",
+            ))
+        }
+    };
+
+    Ok((
+        Struct {
+            name,
+            generics,
+            fields,
+        },
+        errors,
+    ))
+}
+
+/// Parses the generic parameters following the name of a struct, if any
+fn parse_generics(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+) -> Result<Generics, Error> {
+    let mut generics = Generics::default();
+
+    let open = match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => punct.span(),
+        _ => return Ok(generics),
+    };
+    tokens.next();
+
+    let mut params = Vec::new();
+    let mut angles = Angles {
+        depth: 1,
+        arrow: false,
+    };
+
+    loop {
+        let Some(token) = tokens.next() else {
+            return Err(Error {
+                span: open,
+                message: String::from("Expected `>` after the generic parameters"),
+            });
+        };
+
+        angles.update(&token);
+        if angles.depth == 0 {
+            break;
+        }
+
+        params.push(token);
+    }
+
+    for (mut param, end) in split_tokens(params, open) {
+        // The parameters of an impl can't have a default value
+        let mut angles = Angles::default();
+        let default = param.iter().position(|token| {
+            angles.update(token);
+
+            angles.depth == 0 && matches!(token, TokenTree::Punct(punct) if punct.as_char() == '=')
+        });
+        if let Some(i) = default {
+            param.truncate(i);
+        }
+
+        let argument = match param.as_slice() {
+            [TokenTree::Punct(quote), lifetime, ..] if quote.as_char() == '\'' => {
+                vec![TokenTree::Punct(quote.clone()), lifetime.clone()]
+            }
+            [TokenTree::Ident(keyword), name, ..] if keyword.to_string() == "const" => {
+                vec![name.clone()]
+            }
+            [TokenTree::Ident(name), ..] => {
+                generics.types.push(name.clone());
+
+                vec![TokenTree::Ident(name.clone())]
+            }
+            _ => {
+                return Err(expected(
+                    param.first().cloned(),
+                    end,
+                    "a lifetime, type or const parameter",
+                ))
+            }
+        };
+
+        generics.params.push(param.into_iter().collect());
+        generics.arguments.push(argument.into_iter().collect());
+    }
+
+    Ok(generics)
+}
+
+/// Parses the `where` clause of a struct, if any, up to its fields or its `;`
+fn parse_where_clause(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    generics: &mut Generics,
+) {
+    if !matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == "where") {
+        return;
+    }
+    tokens.next();
+
+    let mut clause = Vec::new();
+    let mut angles = Angles::default();
+
+    while let Some(token) = tokens.peek() {
+        let end = match token {
+            TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            _ => false,
+        };
+
+        if end && angles.depth == 0 {
+            break;
+        }
+
+        angles.update(token);
+        clause.extend(tokens.next());
+    }
+
+    generics.predicates.extend(
+        split_tokens(clause, Span::call_site())
+            .into_iter()
+            .map(|(tokens, _)| tokens.into_iter().collect()),
+    );
+}
+
+/// Parses a field, `end` being the span of the token following it
+fn parse_field(tokens: Vec<TokenTree>, end: Span, named: bool) -> Result<Field, Error> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut default = None;
+
+    while let Some((_, group)) = next_attribute(&mut tokens)? {
+        let mut inner = group.stream().into_iter();
+
+        if !matches!(inner.next(), Some(TokenTree::Ident(ident)) if ident.to_string() == "ip") {
+            continue;
+        }
+
+        let malformed = |span| Error {
+            span,
+            message: String::from("Expected `#[ip(default = \"<address>\")]`"),
+        };
+
+        let args = match inner.next() {
+            Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis => args,
+            token => return Err(malformed(token.map_or(group.span_close(), |v| v.span()))),
+        };
+        let mut args = args.stream().into_iter();
+
+        match (args.next(), args.next()) {
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct)))
+                if ident.to_string() == "default" && punct.as_char() == '=' => {}
+            (token, _) => return Err(malformed(token.map_or(group.span_close(), |v| v.span()))),
+        }
+
+        let value: TokenStream = args.collect();
+
+        if value.is_empty() {
+            return Err(malformed(group.span_close()));
+        }
+
+        default = Some(value);
+    }
+
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            tokens.next();
+
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next();
+                }
+            }
+        }
+    }
+
+    let name = if named {
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            token => return Err(expected(token, end, "the name of the field")),
+        };
+
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+            token => return Err(expected(token, end, "`:`")),
+        }
+
+        Some(name)
+    } else {
+        None
+    };
+
+    let ty: Vec<TokenTree> = tokens.collect();
+
+    if ty.is_empty() {
+        return Err(expected(None, end, "the type of the field"));
+    }
+
+    Ok(Field { name, ty, default })
+}
+
+/// Consumes the tokens up to the first of the given keywords, returning them
+fn next_keyword(
+    tokens: &mut impl Iterator<Item = TokenTree>,
+    keywords: &[&str],
+) -> Result<TokenStream, Error> {
+    let mut ret = TokenStream::new();
+
+    for token in tokens {
+        let found = matches!(&token, TokenTree::Ident(ident) if keywords.contains(&ident.to_string().as_str()));
+        ret.extend([token]);
+
+        if found {
+            return Ok(ret);
+        }
+    }
+
+    let keywords: Vec<String> = keywords.iter().map(|v| format!("`{v}`")).collect();
+
+    Err(Error {
+        span: Span::call_site(),
+        message: format!("Expected a {}", keywords.join(" or ")),
+    })
+}
+
+/// Returns the name of the item following its keyword
+fn next_ident(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    kind: &str,
+) -> Result<Ident, Error> {
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) => Ok(ident),
+        token => Err(expected(
+            token,
+            Span::call_site(),
+            &format!("the name of the {kind}"),
+        )),
+    }
+}
+
+/// Returns the name of the item following its keyword, rejecting the generic
+/// items
+fn next_name(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    kind: &str,
+) -> Result<Ident, Error> {
+    let name = next_ident(tokens, kind)?;
+
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => Err(Error {
+            span: punct.span(),
+            message: format!("Generic {kind}s are not supported"),
+        }),
+        _ => Ok(name),
+    }
+}
+
+/// Depth of the angle brackets, such as the ones of `HashMap<K, V>`
+#[derive(Default)]
+struct Angles {
+    depth: usize,
+    /// Whether the previous token is the `-` of `->`
+    arrow: bool,
+}
+
+impl Angles {
+    /// Updates the depth with the token following the previous ones
+    fn update(&mut self, token: &TokenTree) {
+        if let TokenTree::Punct(punct) = token {
+            match punct.as_char() {
+                '<' => self.depth += 1,
+                // The `>` of `->` doesn't close an angle bracket
+                '>' if !self.arrow => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+
+            self.arrow = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            self.arrow = false;
+        }
+    }
+}
+
+/// Splits the content of a group at its commas, except the ones between angle
+/// brackets such as the ones of `HashMap<K, V>`
+///
+/// Each item is returned with the span of the token following it.
+fn split_list(group: &Group) -> Vec<(Vec<TokenTree>, Span)> {
+    split_tokens(group.stream(), group.span_close())
+}
+
+/// Splits tokens at their commas like [`split_list`], `end` being the span
+/// following the last one
+fn split_tokens(
+    tokens: impl IntoIterator<Item = TokenTree>,
+    end: Span,
+) -> Vec<(Vec<TokenTree>, Span)> {
+    let mut ret = Vec::new();
+    let mut item = Vec::new();
+    let mut angles = Angles::default();
+
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' && angles.depth == 0 => {
+                if !item.is_empty() {
+                    ret.push((std::mem::take(&mut item), punct.span()));
+                }
+
+                continue;
+            }
+            _ => angles.update(&token),
+        }
+
+        item.push(token);
+    }
+
+    if !item.is_empty() {
+        ret.push((item, end));
+    }
+
+    ret
+}
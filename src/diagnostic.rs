@@ -0,0 +1,209 @@
+//! Soft diagnostics emitted by the macros
+//!
+//! Stable Rust gives no way for a procedural macro to emit a warning. Warnings
+//! are thus generated as the use of a deprecated constant whose deprecation
+//! note is the warning message, spanned on the offending argument. In strict
+//! mode, warnings are reported as errors instead.
+//!
+//! The entries of the address inventory are recorded along with the
+//! diagnostics, so that they are only written by the invocations emitting no
+//! error. The expansions consulting the address policy make the compiler
+//! track its file as well, so that they are expanded again when it changes.
+
+use std::path::Path;
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+use proc_macro_error::{abort_if_dirty, emit_error};
+
+use crate::{include, inventory, policy::Policy, tokens};
+
+#[derive(Clone)]
+pub struct Diagnostics {
+    strict: bool,
+    allow_outside_ranges: bool,
+    /// Warnings along with the attributes of the entry they are reported on
+    warnings: Vec<(Span, String, TokenStream)>,
+    /// Attributes of the entry being checked
+    attributes: TokenStream,
+    /// JSON lines of the inventory entries
+    inventory: Vec<String>,
+    /// Path of the policy file consulted by the checks
+    policy: Option<String>,
+}
+
+fn respan(stream: TokenStream, span: Span) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut inner = Group::new(group.delimiter(), respan(group.stream(), span));
+                inner.set_span(span);
+                token = TokenTree::Group(inner);
+            } else {
+                token.set_span(span);
+            }
+
+            token
+        })
+        .collect()
+}
+
+/// Builds the block using a deprecated constant whose note is `message`
+fn warning_stream(message: &str) -> TokenStream {
+    let mut note: TokenStream = [tokens::ident("note"), tokens::punct('=')]
+        .into_iter()
+        .collect();
+    note.extend([tokens::literal(Literal::string(message))]);
+
+    let mut deprecated: TokenStream = [tokens::ident("deprecated")].into_iter().collect();
+    deprecated.extend([tokens::group(Delimiter::Parenthesis, note)]);
+
+    let mut allow: TokenStream = [tokens::ident("allow")].into_iter().collect();
+    allow.extend([tokens::group(
+        Delimiter::Parenthesis,
+        tokens::path("non_upper_case_globals"),
+    )]);
+
+    let mut body: TokenStream = [
+        tokens::punct('#'),
+        tokens::group(Delimiter::Bracket, deprecated),
+        tokens::punct('#'),
+        tokens::group(Delimiter::Bracket, allow),
+        tokens::ident("const"),
+        tokens::ident("fancy_ip_warning"),
+        tokens::punct(':'),
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+        tokens::punct('='),
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+        tokens::punct(';'),
+    ]
+    .into_iter()
+    .collect();
+    body.extend(tokens::let_statement("_", tokens::path("fancy_ip_warning")));
+
+    tokens::group(Delimiter::Brace, body).into()
+}
+
+impl Diagnostics {
+    pub fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            allow_outside_ranges: false,
+            warnings: Vec::new(),
+            attributes: TokenStream::new(),
+            inventory: Vec::new(),
+            policy: None,
+        }
+    }
+
+    /// Makes the wrapped expression track the policy file, if the crate has
+    /// one
+    pub fn track_policy(&mut self) {
+        if self.policy.is_none() {
+            self.policy = Policy::file();
+        }
+    }
+
+    /// Reports the warnings as errors, for the patterns which can't hold them
+    pub fn strictly(mut self) -> Self {
+        self.strict = true;
+
+        self
+    }
+
+    /// Records in the inventory entries that the call opted out of the allowed
+    /// ranges of the address policy
+    pub fn allowing_outside_ranges(mut self, value: bool) -> Self {
+        self.allow_outside_ranges = value;
+
+        self
+    }
+
+    /// Records an address generated by the macro `name` in the inventory, if
+    /// enabled
+    pub fn record(&mut self, name: &str, literal: &str, kind: &str, value: String, span: Span) {
+        if !inventory::enabled() {
+            return;
+        }
+
+        let entry = inventory::Entry {
+            name,
+            literal,
+            kind,
+            value,
+            span,
+            allow_outside_ranges: self.allow_outside_ranges,
+        };
+
+        self.inventory.push(entry.to_json());
+    }
+
+    /// Takes the recorded inventory entries, for the invocations generating
+    /// several expressions which write them with [`inventory::write`] once
+    /// all of them are generated
+    pub fn take_inventory(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.inventory)
+    }
+
+    /// Gives the attributes of the list entry being checked to the next
+    /// warnings, so that a `cfg` attribute only emits them along with the
+    /// entry and a lint attribute allows them
+    pub fn set_attributes(&mut self, attributes: TokenStream) {
+        self.attributes = attributes;
+    }
+
+    /// Reports a warning, or an error in strict mode
+    pub fn warn(&mut self, span: Span, message: String) {
+        if self.strict {
+            emit_error!(span, "{}", message);
+        } else {
+            self.warnings.push((span, message, self.attributes.clone()));
+        }
+    }
+
+    /// Reports an error, whatever the mode
+    pub fn error(&mut self, span: Span, message: String) {
+        emit_error!(span, "{}", message);
+    }
+
+    /// Attaches the reported warnings to the generated expression, and writes
+    /// the recorded inventory entries unless an error was emitted
+    pub fn wrap(self, expr: TokenStream) -> TokenStream {
+        if !self.inventory.is_empty() {
+            abort_if_dirty();
+            inventory::write(&self.inventory);
+        }
+
+        if self.warnings.is_empty() && self.policy.is_none() {
+            return expr;
+        }
+
+        let mut body: TokenStream = self
+            .policy
+            .iter()
+            .map(|path| include::track_file(Path::new(path)))
+            .collect();
+
+        for (span, message, attributes) in &self.warnings {
+            body.extend(attributes.clone());
+            body.extend(respan(warning_stream(message), *span));
+        }
+
+        body.extend(expr);
+
+        TokenTree::Group(Group::new(Delimiter::Brace, body)).into()
+    }
+
+    /// Writes the recorded inventory entries of a pattern unless an error was
+    /// emitted
+    ///
+    /// Patterns hold neither the warnings, which must be reported as errors
+    /// with [`Self::strictly`], nor the item making the compiler track the
+    /// policy file.
+    pub fn finish_pattern(self, pattern: TokenStream) -> TokenStream {
+        abort_if_dirty();
+        inventory::write(&self.inventory);
+
+        pattern
+    }
+}
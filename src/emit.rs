@@ -0,0 +1,214 @@
+//! Options controlling how the generated expression is emitted
+
+use std::path::Path;
+
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+
+use proc_macro_error::{abort, abort_if_dirty};
+
+use crate::arg_parser::Opt;
+use crate::diagnostic::Diagnostics;
+use crate::policy::Policy;
+use crate::{emit_parse_error, include, tokens, OBJECT_PREFIX};
+
+/// Environment variable enabling the `debug_expand` option for every call
+const DEBUG_EXPAND_VAR: &str = "FANCY_IP_DEBUG_EXPAND";
+
+/// Environment variable giving the prefix of the generated types, read with
+/// the `net-path` feature
+#[cfg(feature = "net-path")]
+const NET_PATH_VAR: &str = "FANCY_IP_NET_PATH";
+
+#[derive(Default)]
+pub struct Emit {
+    const_block: Option<Span>,
+    debug_expand: Option<Span>,
+    /// Path replacing the `std::net` or `core::net` prefix of the types
+    path: Option<TokenStream>,
+}
+
+impl Emit {
+    /// Parses an emission option, returning `false` if the option is unknown
+    pub fn parse_option(&mut self, opt: &Opt) -> bool {
+        match opt.name() {
+            "const" => self.const_block = Some(opt.span()),
+            "debug_expand" => self.debug_expand = Some(opt.span()),
+            "path" => {
+                self.path = emit_parse_error(opt.value_path());
+
+                return true;
+            }
+            _ => return false,
+        }
+
+        emit_parse_error(opt.flag());
+
+        true
+    }
+
+    /// Returns the span of the `const` option, if given
+    pub fn const_block(&self) -> Option<Span> {
+        self.const_block
+    }
+
+    /// Returns the path of the address type `ty` according to the `path`
+    /// option, for the items generated outside of [`Emit::finish`]
+    pub fn type_path(&self, ty: &str) -> TokenStream {
+        match &self.path {
+            Some(path) => {
+                let mut path = path.clone();
+                path.extend(tokens::path(&format!("::{ty}")));
+
+                path
+            }
+            None => type_path(ty),
+        }
+    }
+
+    /// Emits the generated expression according to the options
+    pub fn finish(&self, expr: TokenStream) -> TokenStream {
+        let expr = match &self.path {
+            Some(path) => with_prefix(expr, path),
+            None => match env_path() {
+                Some(path) => with_prefix(expr, &path),
+                None => match policy_path() {
+                    Some(path) => track_policy(with_prefix(expr, &path)),
+                    None => expr,
+                },
+            },
+        };
+
+        let expr = if self.const_block.is_some() {
+            let mut ret: TokenStream =
+                TokenTree::Ident(Ident::new("const", Span::call_site())).into();
+            ret.extend([TokenTree::Group(Group::new(Delimiter::Brace, expr))]);
+
+            ret
+        } else {
+            expr
+        };
+
+        let debug_expand = self.debug_expand.or_else(|| {
+            std::env::var_os(DEBUG_EXPAND_VAR)
+                .filter(|v| !v.is_empty() && v != "0")
+                .map(|_| Span::call_site())
+        });
+
+        if let Some(span) = debug_expand {
+            let mut diagnostics = Diagnostics::new(false);
+            diagnostics.warn(
+                span,
+                format!("fancy-ip expanded to `{}`", tokens::to_string(&expr)),
+            );
+
+            diagnostics.wrap(expr)
+        } else {
+            expr
+        }
+    }
+}
+
+/// Replaces the `std::net` or `core::net` prefix of the generated types by
+/// `path`
+pub fn with_prefix(stream: TokenStream, path: &TokenStream) -> TokenStream {
+    let prefix: Vec<TokenTree> = tokens::path(OBJECT_PREFIX).into_iter().collect();
+
+    replace_prefix(stream, &prefix, path)
+}
+
+/// Replaces the paths starting with `prefix` in `stream` by `path`
+fn replace_prefix(stream: TokenStream, prefix: &[TokenTree], path: &TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut ret = TokenStream::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let matches = tokens.len() - i >= prefix.len()
+            && prefix
+                .iter()
+                .zip(&tokens[i..])
+                .all(|(a, b)| a.to_string() == b.to_string());
+
+        if matches {
+            ret.extend(path.clone());
+            i += prefix.len();
+
+            continue;
+        }
+
+        ret.extend([match &tokens[i] {
+            TokenTree::Group(group) => {
+                let mut ret = Group::new(
+                    group.delimiter(),
+                    replace_prefix(group.stream(), prefix, path),
+                );
+                ret.set_span(group.span());
+
+                TokenTree::Group(ret)
+            }
+            token => token.clone(),
+        }]);
+        i += 1;
+    }
+
+    ret
+}
+
+/// Returns the path given by [`NET_PATH_VAR`], if any
+#[cfg(feature = "net-path")]
+fn env_path() -> Option<TokenStream> {
+    let value = std::env::var(NET_PATH_VAR).ok().filter(|v| !v.is_empty())?;
+
+    match value.parse() {
+        Ok(path) if crate::policy::is_path(&value) => Some(path),
+        _ => abort!(
+            Span::call_site(),
+            "The `{}` environment variable must hold a path such as `my_net`, found `{}`",
+            NET_PATH_VAR,
+            value
+        ),
+    }
+}
+
+#[cfg(not(feature = "net-path"))]
+fn env_path() -> Option<TokenStream> {
+    None
+}
+
+/// Returns the path given by the `net_path` key of the address policy, if any
+fn policy_path() -> Option<TokenStream> {
+    match &*Policy::get() {
+        Ok(policy) => policy.net_path().and_then(|path| path.parse().ok()),
+        Err(e) => {
+            // Already reported by the checks of the address, if any
+            abort_if_dirty();
+            abort!(Span::call_site(), "{}", e)
+        }
+    }
+}
+
+/// Makes the compiler track the policy file giving the prefix of the types of
+/// the expression
+fn track_policy(expr: TokenStream) -> TokenStream {
+    let Some(file) = Policy::file() else {
+        return expr;
+    };
+
+    let mut body = include::track_file(Path::new(&file));
+    body.extend(expr);
+
+    TokenTree::Group(Group::new(Delimiter::Brace, body)).into()
+}
+
+/// Returns the path of the address type `ty`, such as `std::net::IpAddr`,
+/// for the items generated outside of [`Emit::finish`]
+pub fn type_path(ty: &str) -> TokenStream {
+    match env_path().or_else(policy_path) {
+        Some(mut path) => {
+            path.extend(tokens::path(&format!("::{ty}")));
+
+            path
+        }
+        None => tokens::path(&format!("{OBJECT_PREFIX}::{ty}")),
+    }
+}
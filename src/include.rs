@@ -0,0 +1,139 @@
+//! Support of `include_str!` in argument position
+//!
+//! Procedural macros can't expand the macros given as arguments, so the
+//! included file is read directly, relative to the file containing the macro
+//! call like `include_str!` does.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use proc_macro::{Delimiter, Literal, Span, TokenStream};
+
+use crate::tokens;
+
+/// File included in place of a string argument
+pub struct Included {
+    /// Path of the file as displayed in the diagnostics
+    path: String,
+    /// Path of the file, used to track it as a dependency
+    absolute: PathBuf,
+    /// Line of the file where the argument starts
+    line: usize,
+}
+
+impl Included {
+    /// Reads the file at `path`, relative to the file containing `span`
+    ///
+    /// The content is trimmed so that files holding a single address can be
+    /// used as is.
+    pub fn read(path: &str, span: Span) -> Result<(String, Self), String> {
+        let base = span
+            .local_file()
+            .and_then(|file| file.parent().map(Path::to_path_buf))
+            .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+            .unwrap_or_default();
+
+        let display = Path::new(&span.file())
+            .parent()
+            .map(|parent| parent.join(path).display().to_string())
+            .unwrap_or_else(|| path.to_string());
+
+        Self::read_from(base.join(path), display)
+    }
+
+    /// Reads the file at `path`, relative to the directory of the manifest of
+    /// the crate being compiled
+    pub fn read_from_manifest(path: &str) -> Result<(String, Self), String> {
+        let base = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        Self::read_from(base.join(path), path.to_string())
+    }
+
+    fn read_from(absolute: PathBuf, display: String) -> Result<(String, Self), String> {
+        let content = std::fs::read_to_string(&absolute)
+            .map_err(|e| format!("Couldn't read `{display}`: {e}"))?;
+        // The compiler resolves the relative paths of `include_str!` against
+        // the file containing it, not the working directory
+        let absolute = std::fs::canonicalize(&absolute).unwrap_or(absolute);
+
+        let value = content.trim_start();
+        let line = content[..content.len() - value.len()].matches('\n').count() + 1;
+
+        Ok((
+            value.trim_end().to_string(),
+            Self {
+                path: display,
+                absolute,
+                line,
+            },
+        ))
+    }
+
+    /// Returns the location of the `offset`-th line of the read content
+    pub fn at_line(&self, offset: usize) -> Self {
+        Self {
+            path: self.path.clone(),
+            absolute: self.absolute.clone(),
+            line: self.line + offset,
+        }
+    }
+
+    /// Builds an item making the compiler track the file as a dependency, so
+    /// that the crate is rebuilt when it changes
+    pub fn track_stream(&self) -> TokenStream {
+        track_file(&self.absolute)
+    }
+}
+
+/// Builds an item making the compiler track the file at `path`
+///
+/// The files of the crate are named relative to the directory of its manifest
+/// with `env!("CARGO_MANIFEST_DIR")`, so that the expansion doesn't depend on
+/// the directory the crate is built in.
+pub fn track_file(path: &Path) -> TokenStream {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let relative = std::env::var_os("CARGO_MANIFEST_DIR").and_then(|manifest| {
+        let manifest = PathBuf::from(manifest);
+        let manifest = std::fs::canonicalize(&manifest).unwrap_or(manifest);
+
+        path.strip_prefix(manifest).ok().map(|relative| {
+            relative
+                .components()
+                .map(|v| format!("/{}", v.as_os_str().to_string_lossy()))
+                .collect::<String>()
+        })
+    });
+
+    let Some(relative) = relative else {
+        return tokens::tracking_const("include_str", &path.to_string_lossy());
+    };
+
+    let invocation = |name, argument| -> TokenStream {
+        [
+            tokens::ident(name),
+            tokens::punct('!'),
+            tokens::group(Delimiter::Parenthesis, argument),
+        ]
+        .into_iter()
+        .collect()
+    };
+
+    let manifest = invocation(
+        "env",
+        tokens::literal(Literal::string("CARGO_MANIFEST_DIR")).into(),
+    );
+    let relative = tokens::literal(Literal::string(&relative)).into();
+
+    tokens::tracking_item(
+        "include_str",
+        invocation("concat", tokens::list([manifest, relative])),
+    )
+}
+
+impl Display for Included {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`, line {}", self.path, self.line)
+    }
+}
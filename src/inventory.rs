@@ -0,0 +1,221 @@
+//! Export of the addresses generated by the macros
+//!
+//! When the `FANCY_IP_INVENTORY` environment variable is set at build time to
+//! a directory, each successful macro invocation appends a JSON object
+//! describing the generated address to `<directory>/<crate name>.jsonl`. The
+//! entries are recorded along with the diagnostics of the invocation, and only
+//! written once the invocation is known to emit no error.
+//!
+//! The file is truncated by the first invocation of each crate expanded by the
+//! process, so it only lists the addresses of the last build of the crate. A
+//! persistent process, such as the proc-macro server of an IDE, only appends
+//! the lines it didn't write yet.
+
+use std::collections::BTreeSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use proc_macro::Span;
+use proc_macro_error::emit_error;
+
+const INVENTORY_VAR: &str = "FANCY_IP_INVENTORY";
+
+/// Lines written by this process to each inventory file
+static WRITTEN: Mutex<Vec<(PathBuf, BTreeSet<String>)>> = Mutex::new(Vec::new());
+
+/// Socket address declared with the `bind` option
+struct Bind {
+    /// Name of the crate declaring it
+    crate_name: String,
+    socket: SocketAddr,
+    /// Location of the literal, as reported in the warnings
+    location: String,
+    /// File of the literal on the local file system, if any
+    file: Option<PathBuf>,
+    /// Source text of the literal
+    literal: Option<String>,
+}
+
+impl Bind {
+    /// Checks whether the literal is still found in its file
+    ///
+    /// A persistent process, such as the proc-macro server of an IDE, keeps
+    /// the socket addresses of the invocations deleted or edited since they
+    /// were expanded, which must not be reported anymore.
+    fn is_current(&self) -> bool {
+        let (Some(file), Some(literal)) = (&self.file, &self.literal) else {
+            return true;
+        };
+
+        std::fs::read_to_string(file).map_or(true, |content| content.contains(literal.as_str()))
+    }
+}
+
+/// Socket addresses declared with the `bind` option by the invocations
+/// expanded by this process
+static BINDS: Mutex<Vec<Bind>> = Mutex::new(Vec::new());
+
+fn location(span: Span) -> String {
+    format!("{}:{}:{}", span.file(), span.line(), span.column())
+}
+
+/// Checks whether two bound socket addresses prevent each other from being
+/// bound, the unspecified address colliding with every address of its family
+fn collides(a: &SocketAddr, b: &SocketAddr) -> bool {
+    a.port() == b.port()
+        && a.is_ipv4() == b.is_ipv4()
+        && (a.ip() == b.ip() || a.ip().is_unspecified() || b.ip().is_unspecified())
+}
+
+/// Registers a socket address bound by the crate being compiled
+///
+/// Returns the location of a previously registered socket address colliding
+/// with this one, if any. Only the invocations expanded by the same process
+/// are compared, and the registered socket addresses whose literal is no
+/// longer found in their file are dropped. This check is thus best-effort:
+/// an incremental compilation only expanding some of the invocations doesn't
+/// compare them with the others.
+pub fn register_bind(socket: &SocketAddr, span: Span) -> Option<String> {
+    let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+    let location = location(span);
+    let mut binds = BINDS.lock().unwrap_or_else(|e| e.into_inner());
+
+    binds.retain(|bind| bind.crate_name != crate_name || bind.location != location);
+
+    let mut ret = None;
+
+    binds.retain(|bind| {
+        if ret.is_some() || bind.crate_name != crate_name || !collides(socket, &bind.socket) {
+            return true;
+        }
+
+        if !bind.is_current() {
+            return false;
+        }
+
+        ret = Some(bind.location.clone());
+
+        true
+    });
+
+    binds.push(Bind {
+        crate_name,
+        socket: *socket,
+        location,
+        file: span.local_file(),
+        literal: span.source_text(),
+    });
+
+    ret
+}
+
+/// An address generated by a macro invocation
+pub struct Entry<'a> {
+    /// Name of the macro
+    pub name: &'a str,
+    /// Literal given to the macro
+    pub literal: &'a str,
+    /// Type of the generated expression
+    pub kind: &'a str,
+    /// Canonical representation of the generated address
+    pub value: String,
+    /// Span of the literal
+    pub span: Span,
+    /// Whether the call opted out of the allowed ranges of the address policy
+    pub allow_outside_ranges: bool,
+}
+
+fn escape(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len() + 2);
+    ret.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ret.push(c),
+        }
+    }
+
+    ret.push('"');
+    ret
+}
+
+fn path() -> Option<PathBuf> {
+    let directory = std::env::var_os(INVENTORY_VAR).filter(|v| !v.is_empty())?;
+    let name = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| String::from("unknown"));
+
+    Some(PathBuf::from(directory).join(format!("{name}.jsonl")))
+}
+
+/// Checks whether the invocations record their addresses
+pub fn enabled() -> bool {
+    path().is_some()
+}
+
+/// Writes the JSON lines of the entries recorded by an invocation, which must
+/// be known to emit no error
+pub fn write(lines: &[String]) {
+    let Some(path) = path() else {
+        return;
+    };
+
+    let mut written = WRITTEN.lock().unwrap_or_else(|e| e.into_inner());
+
+    let (truncate, index) = match written.iter().position(|(v, _)| *v == path) {
+        Some(index) => (false, index),
+        None => {
+            written.push((path.clone(), BTreeSet::new()));
+
+            (true, written.len() - 1)
+        }
+    };
+
+    let lines: Vec<&String> = lines
+        .iter()
+        .filter(|line| written[index].1.insert(line.to_string()))
+        .collect();
+
+    if lines.is_empty() && !truncate {
+        return;
+    }
+
+    let result = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(!truncate)
+        .truncate(truncate)
+        .open(&path)
+        .and_then(|mut file| lines.iter().try_for_each(|line| writeln!(file, "{line}")));
+
+    if let Err(e) = result {
+        emit_error!(
+            Span::call_site(),
+            "Unable to write the address inventory `{}`: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+impl Entry<'_> {
+    /// Returns the JSON line of the entry
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"crate\":{},\"file\":{},\"line\":{},\"column\":{},\"macro\":{},\"literal\":{},\"type\":{},\"value\":{},\"allow_outside_ranges\":{}}}",
+            escape(&std::env::var("CARGO_PKG_NAME").unwrap_or_default()),
+            escape(&self.span.file()),
+            self.span.line(),
+            self.span.column(),
+            escape(self.name),
+            escape(self.literal),
+            escape(self.kind),
+            escape(&self.value),
+            self.allow_outside_ranges,
+        )
+    }
+}
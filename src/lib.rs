@@ -1,407 +1,8859 @@
-//! Adds a fancy way to generate IP addresses and socket addresses from its
-//! string representation
-//!
-//! This library aims to replace the use of `parse()` or `new()` functions for
-//! initializing an IP address using a macro call. This approach allows the
-//! emission of compile-time errors when an address is malformed and the use of
-//! human-readable addresses in const contexts.
-//!
-//! # Using in `#[no_std]` contexts
-//!
-//! This library can be used in `#[no_std]` contexts by using the `core`
-//! implementation of addresses instead of the `std` implementation.
-//!
-//! > ⚠️ Address in `core` is currently an unstable feature.
-//! >
-//! > In order to use this feature, you must use the nightly toolchain and
-//! > enable the `ip_in_core` in `main.rs` or `lib.rs` as is:
-//! > ```ignore
-//! > #![feature(ip_in_core)]
-//! > ```
-//! >
-//! > No external IP address provider is planned to be supported. If you want to
-//! > use `fancy-ip` in `#[no_std]` context with the stable or beta toolchain:
-//! > be patient.
-//!
-//! In order to use fancy-ip in `no_std` contexts, you must add this library in
-//! your `Cargo.toml` disabling the default features:
-//! ```toml
-//! fancy-ip = { version = "0.1", default_features = false }
-//! ```
-
-#![crate_type = "proc-macro"]
-extern crate proc_macro;
-
-mod arg_parser;
-
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
-use std::str::FromStr;
-
-use proc_macro::{Span, TokenStream};
-
-use arg_parser::ArgParser;
-use proc_macro_error::{abort, proc_macro_error};
-
-#[cfg(feature = "std")]
-const OBJECT_PREFIX: &'static str = "std::net";
-#[cfg(not(feature = "std"))]
-const OBJECT_PREFIX: &'static str = "core::net";
-
-fn generate_ipv4_stream(addr: &Ipv4Addr) -> TokenStream {
-    let [a, b, c, d] = addr.octets();
-
-    format!("{OBJECT_PREFIX}::Ipv4Addr::new({a}, {b}, {c}, {d})")
-        .parse()
-        .unwrap()
-}
-
-fn generate_ipv4_socket_stream(socket: &SocketAddrV4) -> TokenStream {
-    let addr = socket.ip();
-    let port = socket.port();
-
-    let ip_stream = generate_ipv4_stream(addr);
-
-    format!("{OBJECT_PREFIX}::SocketAddrV4::new({ip_stream},{port})")
-        .parse()
-        .unwrap()
-}
-
-fn generate_ipv6_stream(addr: &Ipv6Addr) -> TokenStream {
-    let [a, b, c, d, e, f, g, h] = addr.segments();
-
-    format!("{OBJECT_PREFIX}::Ipv6Addr::new({a}, {b}, {c}, {d}, {e}, {f}, {g}, {h})")
-        .parse()
-        .unwrap()
-}
-
-fn generate_ipv6_socket_stream(socket: &SocketAddrV6) -> TokenStream {
-    let addr = socket.ip();
-    let port = socket.port();
-    let flow_info = socket.flowinfo();
-    let scope_id = socket.scope_id();
-
-    let ip_stream = generate_ipv6_stream(addr);
-
-    format!("{OBJECT_PREFIX}::SocketAddrV6::new({ip_stream},{port},{flow_info},{scope_id})")
-        .parse()
-        .unwrap()
-}
-
-fn generate_ip_stream(addr: &IpAddr) -> TokenStream {
-    match addr {
-        IpAddr::V4(ip) => {
-            let ip_stream = generate_ipv4_stream(ip);
-
-            format!("{OBJECT_PREFIX}::IpAddr::V4({ip_stream})")
-                .parse()
-                .unwrap()
-        }
-        IpAddr::V6(ip) => {
-            let ip_stream = generate_ipv6_stream(ip);
-
-            format!("{OBJECT_PREFIX}::IpAddr::V6({ip_stream})")
-                .parse()
-                .unwrap()
-        }
-    }
-}
-
-fn generate_ip_socket_stream(socket: &SocketAddr) -> TokenStream {
-    match socket {
-        SocketAddr::V4(socket) => {
-            let socket_stream = generate_ipv4_socket_stream(socket);
-
-            format!("{OBJECT_PREFIX}::SocketAddr::V4({socket_stream})")
-                .parse()
-                .unwrap()
-        }
-        SocketAddr::V6(socket) => {
-            let socket_stream = generate_ipv6_socket_stream(socket);
-
-            format!("{OBJECT_PREFIX}::SocketAddr::V6({socket_stream})")
-                .parse()
-                .unwrap()
-        }
-    }
-}
-
-fn report_error<T>(value: Result<T, arg_parser::Error>) -> T {
-    match value {
-        Ok(v) => v,
-        Err(e) => {
-            abort!(e.span(), "{}", e);
-        }
-    }
-}
-
-fn report_too_few_arguments_error(given: usize, expected: usize) -> ! {
-    abort!(
-        Span::call_site(),
-        "Too few argument: Given {}, expected {}",
-        given,
-        expected
-    );
-}
-
-fn report_too_many_arguments_error(span: Span, given: usize, expected: usize) -> ! {
-    abort!(
-        span,
-        "Too many arguments: Given {}, expected {}",
-        given,
-        expected
-    );
-}
-
-/// Generate an IPv4 address from the standard textual representation
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of an IP address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::ipv4;
-///
-/// assert_eq!(ipv4!("192.168.1.5"), std::net::Ipv4Addr::new(192, 168, 1, 5));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn ipv4(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let ip = if let Some((v, span)) = report_error(parser.next_string()) {
-        match Ipv4Addr::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(
-                    span,
-                    "The given address `{}` is not a valid IPv4 address",
-                    v
-                );
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 1);
-    }
-
-    generate_ipv4_stream(&ip)
-}
-
-/// Generate an IPv6 address from the standard textual representation
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of an IP address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::ipv6;
-///
-/// assert_eq!(ipv6!("::1"), std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn ipv6(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let ip = if let Some((v, span)) = report_error(parser.next_string()) {
-        match Ipv6Addr::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(
-                    span,
-                    "The given address `{}` is not a valid IPv6 address",
-                    v
-                );
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 1);
-    }
-
-    generate_ipv6_stream(&ip)
-}
-
-/// Generate an IP address from the standard textual representation (both
-/// support IPv4 and IPv6)
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of an IP address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::ip;
-///
-/// assert_eq!(ip!("::1"), std::net::IpAddr::V6(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)));
-/// assert_eq!(ip!("192.168.1.5"), std::net::IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 5)));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn ip(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let ip = if let Some((v, span)) = report_error(parser.next_string()) {
-        match IpAddr::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(span, "The given address `{}` is not a valid IP address", v);
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 1);
-    }
-
-    generate_ip_stream(&ip)
-}
-
-/// Generates a socket address from its string representation
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of a socket address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::socketv4;
-///
-/// assert_eq!(socketv4!("192.168.1.5:3000"), std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 3000));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn socketv4(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let socket = if let Some((v, span)) = report_error(parser.next_string()) {
-        match SocketAddrV4::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(
-                    span,
-                    "The given address `{}` is not a valid IPv4 socket address",
-                    v
-                );
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 1);
-    }
-
-    generate_ipv4_socket_stream(&socket)
-}
-
-/// Generates a socket address from its string representation
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of a socket address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::socketv6;
-///
-/// assert_eq!(socketv6!("[::1]:3000"), std::net::SocketAddrV6::new(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 3000, 0, 0));
-/// assert_eq!(socketv6!("[::]:8080", 58, 30), std::net::SocketAddrV6::new(std::net::Ipv6Addr::UNSPECIFIED, 8080, 58, 30));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn socketv6(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let mut socket = if let Some((v, span)) = report_error(parser.next_string()) {
-        match SocketAddrV6::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(
-                    span,
-                    "The given address `{}` is not a valid IPv6 socket address",
-                    v
-                );
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some((flow_info, _)) = report_error(parser.next_integer()) {
-        socket.set_flowinfo(flow_info);
-    }
-
-    if let Some((scope_id, _)) = report_error(parser.next_integer()) {
-        socket.set_scope_id(scope_id)
-    }
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 3);
-    }
-
-    generate_ipv6_socket_stream(&socket)
-}
-
-/// Generates a socket address from its string representation
-///
-/// # Syntax
-///
-/// This macro works as a function which take only one argument: the string
-/// representation of a socket address
-///
-/// # Example
-///
-/// ```
-/// # use fancy_ip::socket;
-///
-/// assert_eq!(socket!("[::1]:3000"), std::net::SocketAddr::V6(std::net::SocketAddrV6::new(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 3000, 0, 0)));
-/// assert_eq!(socket!("192.168.1.5:3000"), std::net::SocketAddr::V4(std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 3000)));
-/// ```
-#[proc_macro_error]
-#[proc_macro]
-pub fn socket(item: TokenStream) -> TokenStream {
-    let mut parser = ArgParser::from(item);
-
-    let socket = if let Some((v, span)) = report_error(parser.next_string()) {
-        match SocketAddr::from_str(v.as_str()) {
-            Ok(v) => v,
-            Err(_) => {
-                abort!(
-                    span,
-                    "The given address `{}` is not a valid socket address",
-                    v
-                );
-            }
-        }
-    } else {
-        report_too_few_arguments_error(0, 1);
-    };
-
-    if let Some(span) = report_error(parser.ignore_next()) {
-        report_too_many_arguments_error(span, parser.count_arguments(), 1);
-    }
-
-    generate_ip_socket_stream(&socket)
-}
+//! Adds a fancy way to generate IP addresses and socket addresses from its
+//! string representation
+//!
+//! This library aims to replace the use of `parse()` or `new()` functions for
+//! initializing an IP address using a macro call. This approach allows the
+//! emission of compile-time errors when an address is malformed and the use of
+//! human-readable addresses in const contexts.
+//!
+//! # Using in `#[no_std]` contexts
+//!
+//! This library can be used in `#[no_std]` contexts by using the `core`
+//! implementation of addresses instead of the `std` implementation, which is
+//! stable since Rust 1.77 (fancy-ip itself requires Rust 1.88).
+//!
+//! In order to use fancy-ip in `no_std` contexts, you must add this library in
+//! your `Cargo.toml` disabling the default features:
+//! ```toml
+//! fancy-ip = { version = "0.1", default-features = false }
+//! ```
+//!
+//! The `no-std-net` feature generates the types of the
+//! [no-std-net](https://crates.io/crates/no-std-net) crate instead of the
+//! `core` ones, which the crate using fancy-ip must depend on:
+//! ```toml
+//! fancy-ip = { version = "0.1", default-features = false, features = ["no-std-net"] }
+//! no-std-net = "0.6"
+//! ```
+//!
+//! As the features of a crate are unified across the dependency graph, this
+//! feature only applies when the `std` feature is disabled, so that it never
+//! changes the types generated for the other crates using fancy-ip. The
+//! `path = no_std_net` option generates the no-std-net types for a single
+//! call whatever the enabled features (see the [type paths](#type-paths)).
+//!
+//! # Literals
+//!
+//! Addresses are given as string literals, which can be raw strings
+//! (`r"10.0.0.1"`, `r#"10.0.0.1"#`) or contain escape sequences. The address is
+//! parsed from the unescaped value of the literal. When an address is
+//! malformed, the error points at the whole literal.
+//!
+//! Adjacent string literals are concatenated before parsing, which allows
+//! long addresses to be split across lines:
+//! ```
+//! # use fancy_ip::socket;
+//! let server = socket!(
+//!     "[2001:db8:85a3:8d3:1319:8a2e:370:7348]"
+//!     ":8443"
+//! );
+//! # assert_eq!(server.port(), 8443);
+//! ```
+//!
+//! A string argument can also be given as `include_str!("<path>")`, the path
+//! being relative to the file containing the macro call. The content of the
+//! file is trimmed, so that files holding a single address can be used as is,
+//! and the errors mention the file and line the address comes from:
+//! ```ignore
+//! # use fancy_ip::ip;
+//! let upstream = ip!(include_str!("upstream.txt"));
+//! ```
+//!
+//! The `concat!` and `env!` macros are evaluated the same way, so that
+//! addresses can be composed from several literals or read from the build
+//! environment. The compiler tracks the environment variables read, so the
+//! crate is rebuilt when they change:
+//! ```
+//! # use fancy_ip::ipv4;
+//! const HOST: std::net::Ipv4Addr = ipv4!(concat!("192.168.", "1.5"));
+//! # assert_eq!(HOST, std::net::Ipv4Addr::new(192, 168, 1, 5));
+//! ```
+//!
+//! # Compile-time checks
+//!
+//! Besides the address syntax, the address macros check the given addresses
+//! and emit a warning for the ones that are most likely typos:
+//!
+//! - addresses in a reserved block (`0.0.0.0/8`, `240.0.0.0/4` and the IPv6
+//!   blocks reserved by the IETF), silenced by the `allow_reserved` option;
+//! - deprecated IPv6 site-local addresses (`fec0::/10`), which should be
+//!   replaced by unique local addresses (`fc00::/7`), silenced by the
+//!   `allow_site_local` option.
+//!
+//! ```
+//! # use fancy_ip::{ipv4, ipv6};
+//! const ADDR: std::net::Ipv4Addr = ipv4!("250.0.0.1", allow_reserved);
+//! const LEGACY: std::net::Ipv6Addr = ipv6!("fec0::1", allow_site_local);
+//! ```
+//!
+//! The `strict` option turns these warnings into errors:
+//! ```compile_fail
+//! # use fancy_ip::ipv4;
+//! const ADDR: std::net::Ipv4Addr = ipv4!("250.0.0.1", strict);
+//! ```
+//!
+//! The IPv6 blocks of the special-purpose registry assigned out of a reserved
+//! block, such as the SRv6 SIDs of `5f00::/16`, are not reported:
+//! ```
+//! # use fancy_ip::ipv6;
+//! const SID: std::net::Ipv6Addr = ipv6!("5f00::1", strict);
+//! ```
+//!
+//! Strict mode can also be enabled for every macro call without editing them,
+//! for CI builds for instance, by setting the `FANCY_IP_STRICT` environment
+//! variable or by building with `RUSTFLAGS="--cfg fancy_ip_strict"`. As the
+//! macros run on the host, the cfg is only seen when cargo applies `RUSTFLAGS`
+//! to host artifacts, which isn't the case when cross compiling with
+//! `--target`.
+//!
+//! Strict mode only applies to the warnings the macros already emit: the
+//! reserved and site-local addresses above, the classes given to the `warn`
+//! option and the socket addresses colliding with a `bind` one. It doesn't add
+//! checks of its own, so non-canonical spellings of an address, privileged
+//! ports and unspecified bind addresses are not reported. The unspecified and
+//! non-documentation addresses can be rejected with the `deny = unspecified`
+//! and `expect = documentation` options described below.
+//!
+//! Some checks are only performed when asked for, and fail the compilation
+//! when the address doesn't satisfy them:
+//!
+//! - `target`: the address is a valid destination, which excludes the
+//!   unspecified and broadcast addresses, the port 0 for socket addresses and
+//!   multicast addresses unless the `allow_multicast` option is given.
+//!
+//! ```compile_fail
+//! # use fancy_ip::socket;
+//! let server = socket!("0.0.0.0:9000", target);
+//! ```
+//!
+//! - `deny_special_purpose`: the address belongs to no block of the IANA
+//!   special-purpose address registries (`0.0.0.0/8`, `192.0.0.0/24`,
+//!   `240.0.0.0/4`, `100::/64`, among others), the error naming the most
+//!   specific block. This suits the generated code which must only contain
+//!   globally routable addresses.
+//!
+//! ```compile_fail
+//! # use fancy_ip::ipv4;
+//! const ADDR: std::net::Ipv4Addr = ipv4!("192.0.0.8", deny_special_purpose);
+//! ```
+//!
+//! The `scope = <scope>` option checks the scope of the address, which is one
+//! of `interface_local`, `link_local`, `realm_local`, `admin_local`,
+//! `site_local`, `organization_local` or `global`. The scope of multicast
+//! addresses is given by their scope field (or RFC 2365 for IPv4), loopback
+//! addresses are interface-local and link-local unicast addresses are
+//! link-local. Other unicast addresses are global.
+//! ```
+//! # use fancy_ip::{ipv6, socketv6};
+//! const ALL_NODES: std::net::Ipv6Addr = ipv6!("ff02::1", scope = link_local);
+//! const MDNS: std::net::SocketAddrV6 = socketv6!("[ff02::fb]:5353", scope = link_local);
+//! ```
+//!
+//! ```compile_fail
+//! # use fancy_ip::ipv6;
+//! const ALL_NODES: std::net::Ipv6Addr = ipv6!("ff0e::1", scope = link_local);
+//! ```
+//!
+//! The `expect = <class>` option checks that the address belongs to a class,
+//! which is one of:
+//!
+//! - `unspecified`, `broadcast`, `loopback` and `multicast`;
+//! - `private`: `10.0.0.0/8`, `172.16.0.0/12`, `192.168.0.0/16` and the
+//!   `fc00::/7` unique local addresses;
+//! - `unique_local`: `fc00::/7` only;
+//! - `shared`: the `100.64.0.0/10` carrier-grade NAT addresses;
+//! - `link_local`: `169.254.0.0/16` and `fe80::/10`;
+//! - `documentation`: `192.0.2.0/24`, `198.51.100.0/24`, `203.0.113.0/24`,
+//!   `2001:db8::/32` and `3fff::/20`;
+//! - `benchmarking`: `198.18.0.0/15` and `2001:2::/48`;
+//! - `global`: the addresses belonging to none of the above classes nor to a
+//!   reserved block or `5f00::/16`, the multicast ones having the global
+//!   scope.
+//!
+//! ```
+//! # use fancy_ip::{ip, ipv4};
+//! const GATEWAY: std::net::Ipv4Addr = ipv4!("10.1.2.3", expect = private);
+//! const EXAMPLE: std::net::IpAddr = ip!("2001:db8::1", expect = documentation);
+//! ```
+//!
+//! ```compile_fail
+//! # use fancy_ip::ipv4;
+//! const GATEWAY: std::net::Ipv4Addr = ipv4!("8.8.8.8", expect = private);
+//! ```
+//!
+//! The `deny = <class>` option does the opposite, failing the compilation when
+//! the address belongs to the class, and the `warn = <class>` option only
+//! emits a warning. Both can be given several times. This catches the
+//! wildcard address pasted into a constant used to connect to a server:
+//! ```compile_fail
+//! # use fancy_ip::socket;
+//! const SERVER: std::net::SocketAddr = socket!("0.0.0.0:80", deny = unspecified, deny = broadcast);
+//! ```
+//!
+//! The `bind` option declares a socket address the program binds to. A warning
+//! is emitted when two invocations of the same crate declare colliding bound
+//! socket addresses: the same port on the same address, or on the unspecified
+//! address of the same family. This check is best-effort, as it only compares
+//! the invocations expanded by the same compiler process: an incremental
+//! build may only expand the invocations of the modified code, and an IDE
+//! may keep reporting a collision until the file of the other invocation is
+//! saved.
+//! ```compile_fail
+//! # use fancy_ip::socket;
+//! const HTTP: std::net::SocketAddr = socket!("0.0.0.0:8080", bind, strict);
+//! const METRICS: std::net::SocketAddr = socket!("127.0.0.1:8080", bind, strict);
+//! ```
+//!
+//! # Address policy
+//!
+//! A `fancy-ip.toml` file next to the `Cargo.toml` of a crate defines an
+//! address policy enforced on every address macro of the crate. Addresses
+//! belonging to a `deny` prefix fail the compilation with the message of the
+//! entry:
+//! ```toml
+//! [[deny]]
+//! prefix = "10.20.0.0/16"
+//! message = "The Paris datacenter has been decommissioned"
+//!
+//! [[deny]]
+//! prefix = "192.168.1.17"
+//! ```
+//!
+//! The `allowed_ranges` key restricts the addresses of the crate to the given
+//! prefixes, such as the documentation ranges for library crates. Calls using
+//! an address outside of these ranges must opt out with the
+//! `allow_outside_ranges` option, which is recorded in the address inventory:
+//! ```toml
+//! allowed_ranges = ["192.0.2.0/24", "198.51.100.0/24", "2001:db8::/32"]
+//! ```
+//!
+//! The policy file is written in a subset of TOML: arrays of tables,
+//! `key = value` pairs with string or string array values and comments. The
+//! expansions checking an address against the policy make the compiler track
+//! the file, so that they are expanded again when it changes.
+//!
+//! # Const evaluation
+//!
+//! The generated expressions can be used in const contexts. The `const` option
+//! wraps the generated expression in an inline `const { ... }` block, which
+//! guarantees the value is computed at compile time even when it is used in a
+//! non-const position (inline const blocks require Rust 1.79):
+//! ```
+//! # use fancy_ip::ipv4;
+//! let gateway = ipv4!("192.168.1.1", const);
+//! # assert_eq!(gateway, std::net::Ipv4Addr::new(192, 168, 1, 1));
+//! ```
+//!
+//! # Debugging expansions
+//!
+//! The `debug_expand` option makes the macro emit a warning showing the exact
+//! expression it generated, which helps diagnosing type path or feature
+//! selection issues:
+//! ```
+//! # use fancy_ip::ipv6;
+//! let localhost = ipv6!("::1", debug_expand);
+//! # assert!(localhost.is_loopback());
+//! ```
+//!
+//! Setting the `FANCY_IP_DEBUG_EXPAND` environment variable at build time
+//! enables this option for every macro call.
+//!
+//! # Reproducible output
+//!
+//! The expansion of a macro call is the same on every platform and for every
+//! compilation, so that expansion snapshots and reproducible build audits
+//! don't change between builds. It only depends on:
+//!
+//! - the arguments of the call and the enabled features of fancy-ip;
+//! - the [policy file](#address-policy) of the crate, including its `net_path`
+//!   key, which is tracked through its path relative to the directory of the
+//!   manifest, like the files read by `include_str!` arguments and the file
+//!   macros;
+//! - the strict mode, enabled by the `FANCY_IP_STRICT` environment variable
+//!   or by passing `--cfg fancy_ip_strict` to the compiler, which turns the
+//!   warnings into errors;
+//! - the `FANCY_IP_NET_PATH` environment variable, with the `net-path`
+//!   feature;
+//! - the `FANCY_IP_DEBUG_EXPAND` environment variable, which adds the
+//!   warnings of the `debug_expand` option.
+//!
+//! Each integer is generated in the fixed format of the value it holds:
+//! address octets and segments, ports and prefix lengths as decimal literals,
+//! hardware address bytes as two-digit hexadecimal literals, and addresses
+//! and masks handled as integers as zero-padded hexadecimal literals, each
+//! suffixed with its type where the generated expression doesn't give it.
+//! The tokens are generated with a fixed spacing, which the `debug_expand`
+//! warnings print as is rather than through the pretty-printer of the
+//! compiler.
+//!
+//! The lists keep the order of their input, except the ones of `aggregate!`,
+//! `ip_set!` and `lpm_table!`, which are sorted by address, the ones given
+//! the `sort` option, and the maps of `ip_map!`, whose order is the one of
+//! their perfect hash function, computed with fixed keys. The only exceptions
+//! are the warnings, as the `bind` check depends on the order in which the
+//! compiler expands the macros.
+//!
+//! # Address inventory
+//!
+//! Setting the `FANCY_IP_INVENTORY` environment variable at build time to a
+//! directory makes every macro invocation record the address it generated in
+//! `<directory>/<crate name>.jsonl`, one JSON object per line:
+//! ```json
+//! {"crate":"my-service","file":"src/main.rs","line":12,"column":24,"macro":"socket","literal":"10.0.0.5:443","type":"SocketAddr","value":"10.0.0.5:443","allow_outside_ranges":false}
+//! ```
+//!
+//! This allows auditing every hardcoded address of a binary from its build
+//! artifacts. The file is rewritten by each compilation of the crate, and
+//! changing the variable makes cargo expand the macros of the crates using
+//! fancy-ip again.
+//!
+//! # Type paths
+//!
+//! The generated expressions name the address types through `std::net` (or
+//! `core::net`). The `path = <path>` option replaces this prefix, for crates
+//! re-exporting the address types through a facade module or crate:
+//! ```
+//! # use fancy_ip::ipv4;
+//! mod net {
+//!     pub use std::net::*;
+//! }
+//!
+//! const ADDR: net::Ipv4Addr = ipv4!("10.0.0.1", path = net);
+//! # assert_eq!(ADDR, std::net::Ipv4Addr::new(10, 0, 0, 1));
+//! ```
+//!
+//! The `net_path` key of the [address policy](#address-policy) file of a
+//! crate gives the prefix of its macro calls not given the `path` option,
+//! including the types of the items declared by `addresses!` and
+//! `#[ip_table]`. As the file is read from the directory of the crate, the
+//! prefix doesn't apply to the other crates of the build:
+//! ```toml
+//! net_path = "my_shim::net"
+//! ```
+//!
+//! With the `net-path` feature, the `FANCY_IP_NET_PATH` environment variable
+//! gives the prefix of every macro call of the build instead, such as
+//! `FANCY_IP_NET_PATH=my_shim::net`, for the workspaces funnelling all their
+//! crates through the same shim. The `path` option of a call takes precedence
+//! over the variable, which takes precedence over the `net_path` key of the
+//! policy file.
+//!
+//! # Output targets
+//!
+//! By default, macros generate the `std::net` (or `core::net`) types. Some
+//! macros accept an `as = <target>` option to generate the equivalent type of
+//! another crate instead. Each target is enabled by the feature of the same
+//! name:
+//!
+//! | Target         | Feature        | Generated types                                                           |
+//! |----------------|----------------|---------------------------------------------------------------------------|
+//! | `nix`          | `nix`          | `nix::sys::socket::SockaddrIn` and `SockaddrIn6`                          |
+//! | `pnet`         | `pnet`         | `pnet::util::MacAddr`                                                     |
+//! | `smoltcp`      | `smoltcp`      | `smoltcp::wire::Ipv4Address`, `Ipv6Address`, `IpAddress` and `IpEndpoint` |
+//! | `embedded_nal` | `embedded-nal` | `embedded_nal::SocketAddr`, `SocketAddrV4` and `SocketAddrV6`             |
+//! | `socket2`      | `socket2`      | `socket2::SockAddr`                                                       |
+//!
+//! The `embedded_nal` target is meant for the versions of embedded-nal before
+//! 0.8, which re-export the address types of no-std-net. The later versions
+//! use the `core::net` types, generated without the `std` feature.
+//!
+//! The `ipv4!` and `ipv6!` macros also accept the `u32`, `u128`, `octets` and
+//! `segments` targets, generating the numeric representation of the address
+//! without any runtime conversion. The `be_bytes`, `le_bytes` and `ne_bytes`
+//! targets generate the bytes in a given order, and the `order = network`
+//! option stores the integers in network byte order.
+//!
+//! # Optional macros
+//!
+//! Some macros generate the configuration of another crate and are only
+//! available with the feature of the same name:
+//!
+//! - `resolver_config!` (feature `hickory`): generates a
+//!   `hickory_resolver::config::ResolverConfig` from name server addresses.
+//! - `cidr!` (features `ipnet`, `ipnetwork` or `cidr`): generates an `ipnet::Ipv4Net`
+//!   or `ipnet::Ipv6Net` from a network in CIDR notation, or the
+//!   `ipnetwork::Ipv4Network` or `ipnetwork::Ipv6Network` with the
+//!   `as = ipnetwork` option, or the `cidr::Ipv4Cidr` or `cidr::Ipv6Cidr` with
+//!   the `as = cidr` option (feature `cidr`).
+//! - `sockaddr_in!` and `sockaddr_in6!` (feature `libc`): generate a
+//!   `libc::sockaddr_in` or a `libc::sockaddr_in6` from a socket address, for
+//!   the code calling the socket functions of the C library.
+//! - `winsock_sockaddr_in!` and `winsock_sockaddr_in6!` (feature `windows`):
+//!   generate a `SOCKADDR_IN` or a `SOCKADDR_IN6` of the
+//!   `windows_sys::Win32::Networking::WinSock` module from a socket address.
+//! - `ip_map!` (feature `phf`): generates a `phf::Map` whose keys are
+//!   addresses, for the static lookups by address.
+
+#![crate_type = "proc-macro"]
+extern crate proc_macro;
+
+mod arg_parser;
+mod checks;
+mod classify;
+mod declaration;
+mod diagnostic;
+mod emit;
+mod include;
+mod inventory;
+mod mac;
+mod network;
+#[cfg(feature = "phf")]
+mod phf;
+mod policy;
+mod sha1;
+#[cfg(feature = "phf")]
+mod siphash;
+mod sort;
+mod target;
+mod tokens;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::str::FromStr;
+
+use proc_macro::{Delimiter, Literal, Span, TokenStream, TokenTree};
+
+use arg_parser::{ArgParser, Attribute, Opt};
+use checks::Checks;
+use classify::Class;
+use diagnostic::Diagnostics;
+use emit::Emit;
+use include::Included;
+use network::Network;
+use proc_macro_error::{abort, abort_if_dirty, emit_error, proc_macro_error};
+use sort::Sort;
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+use target::NetTarget;
+use target::{IpTarget, MacTarget, NetArrayTarget, SocketTarget};
+
+#[cfg(feature = "std")]
+const OBJECT_PREFIX: &str = "std::net";
+#[cfg(not(any(feature = "std", feature = "no-std-net")))]
+const OBJECT_PREFIX: &str = "core::net";
+#[cfg(all(not(feature = "std"), feature = "no-std-net"))]
+const OBJECT_PREFIX: &str = "no_std_net";
+
+fn generate_ipv4_stream(addr: &Ipv4Addr) -> TokenStream {
+    tokens::call(
+        &format!("{OBJECT_PREFIX}::Ipv4Addr::new"),
+        addr.octets()
+            .map(|v| tokens::literal(Literal::u8_unsuffixed(v)).into()),
+    )
+}
+
+/// Generates the integer or array representation of `addr` selected by
+/// `target`, which is known to be supported for the family of `addr`
+///
+/// With `network_order`, the integers are converted with `to_be` so that they
+/// are stored in network byte order.
+fn generate_representation_stream(
+    target: &IpTarget,
+    addr: &IpAddr,
+    network_order: bool,
+) -> TokenStream {
+    let integer = |value: u128, digits: usize, ty: &str| -> TokenStream {
+        let value = tokens::hex_suffixed(value, digits, ty).into();
+
+        if network_order {
+            tokens::call(&format!("{ty}::to_be"), [value])
+        } else {
+            value
+        }
+    };
+
+    let bytes = |octets: Vec<u8>| -> TokenStream {
+        tokens::group(
+            Delimiter::Bracket,
+            tokens::list(
+                octets
+                    .into_iter()
+                    .map(|v| tokens::literal(Literal::u8_suffixed(v)).into()),
+            ),
+        )
+        .into()
+    };
+
+    let (value, digits, ty, octets) = match addr {
+        IpAddr::V4(addr) => (u32::from(*addr).into(), 8, "u32", addr.octets().to_vec()),
+        IpAddr::V6(addr) => (u128::from(*addr), 32, "u128", addr.octets().to_vec()),
+    };
+
+    match (target, addr) {
+        (IpTarget::U32 | IpTarget::U128, _) => integer(value, digits, ty),
+        (IpTarget::Segments, IpAddr::V6(addr)) => tokens::group(
+            Delimiter::Bracket,
+            tokens::list(addr.segments().map(|v| integer(v.into(), 4, "u16"))),
+        )
+        .into(),
+        (IpTarget::Octets, _) => bytes(octets),
+        (IpTarget::LeBytes, _) => bytes(octets.into_iter().rev().collect()),
+        // The byte order of the target platform is only known by the compiler
+        (IpTarget::NeBytes, _) => tokens::call(
+            &format!("{ty}::to_ne_bytes"),
+            [tokens::hex_suffixed(value, digits, ty).into()],
+        ),
+        _ => unreachable!(),
+    }
+}
+
+fn generate_ipv4_socket_stream(socket: &SocketAddrV4) -> TokenStream {
+    tokens::call(
+        &format!("{OBJECT_PREFIX}::SocketAddrV4::new"),
+        [
+            generate_ipv4_stream(socket.ip()),
+            tokens::literal(Literal::u16_unsuffixed(socket.port())).into(),
+        ],
+    )
+}
+
+fn generate_ipv6_stream(addr: &Ipv6Addr) -> TokenStream {
+    tokens::call(
+        &format!("{OBJECT_PREFIX}::Ipv6Addr::new"),
+        addr.segments()
+            .map(|v| tokens::literal(Literal::u16_unsuffixed(v)).into()),
+    )
+}
+
+fn generate_ipv6_socket_stream(socket: &SocketAddrV6) -> TokenStream {
+    tokens::call(
+        &format!("{OBJECT_PREFIX}::SocketAddrV6::new"),
+        [
+            generate_ipv6_stream(socket.ip()),
+            tokens::literal(Literal::u16_unsuffixed(socket.port())).into(),
+            tokens::literal(Literal::u32_unsuffixed(socket.flowinfo())).into(),
+            tokens::literal(Literal::u32_unsuffixed(socket.scope_id())).into(),
+        ],
+    )
+}
+
+/// Generates a const expression building a non-zero integer of type `ty`
+/// from `value`, which is known not to be zero
+fn generate_nonzero_stream(ty: &str, value: TokenStream) -> TokenStream {
+    generate_const_unwrap_stream(
+        tokens::call(&format!("{ty}::new"), [value]),
+        "Some",
+        tokens::path("None"),
+    )
+}
+
+/// Generates a const expression unwrapping `value`, which is known to match
+/// `success(v)` rather than `failure`
+fn generate_const_unwrap_stream(
+    value: TokenStream,
+    success: &str,
+    failure: TokenStream,
+) -> TokenStream {
+    let mut ret: TokenStream = [tokens::ident("match")].into_iter().collect();
+    ret.extend(value);
+
+    let mut arms = tokens::call(success, [tokens::path("v")]);
+    arms.extend(tokens::op("=>"));
+    arms.extend([tokens::ident("v"), tokens::punct(',')]);
+    arms.extend(failure);
+    arms.extend(tokens::op("=>"));
+    arms.extend([
+        tokens::ident("panic"),
+        tokens::punct('!'),
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+    ]);
+    ret.extend([tokens::group(Delimiter::Brace, arms)]);
+
+    ret
+}
+
+fn generate_mac_bytes(addr: &[u8]) -> TokenStream {
+    tokens::list(addr.iter().map(|v| tokens::hex((*v).into(), 2).into()))
+}
+
+fn generate_mac_stream(addr: &[u8]) -> TokenStream {
+    tokens::group(Delimiter::Bracket, generate_mac_bytes(addr)).into()
+}
+
+#[cfg(feature = "pnet")]
+fn generate_pnet_mac_stream(addr: &[u8; 6]) -> TokenStream {
+    let mut ret = tokens::path("pnet::util::MacAddr");
+    ret.extend([tokens::group(
+        Delimiter::Parenthesis,
+        generate_mac_bytes(addr),
+    )]);
+
+    ret
+}
+
+#[cfg(feature = "hickory")]
+fn generate_resolver_config_stream(
+    name_servers: &[SocketAddr],
+    protocols: &[&str],
+    trust_nx: bool,
+) -> TokenStream {
+    let mut body = tokens::let_statement(
+        "mut config",
+        tokens::call("hickory_resolver::config::ResolverConfig::new", []),
+    );
+
+    for socket in name_servers {
+        for protocol in protocols {
+            let mut name_server = tokens::let_statement(
+                "mut name_server",
+                tokens::call(
+                    "hickory_resolver::config::NameServerConfig::new",
+                    [
+                        generate_ip_socket_stream(socket),
+                        tokens::path(&format!("hickory_resolver::config::Protocol::{protocol}")),
+                    ],
+                ),
+            );
+            name_server.extend(tokens::path("name_server"));
+            name_server.extend([
+                tokens::punct('.'),
+                tokens::ident("trust_negative_responses"),
+                tokens::punct('='),
+                tokens::ident(if trust_nx { "true" } else { "false" }),
+                tokens::punct(';'),
+            ]);
+            name_server.extend([tokens::ident("config"), tokens::punct('.')]);
+            name_server.extend(tokens::call(
+                "add_name_server",
+                [tokens::path("name_server")],
+            ));
+            name_server.extend([tokens::punct(';')]);
+
+            body.extend([tokens::group(Delimiter::Brace, name_server)]);
+        }
+    }
+
+    body.extend(tokens::path("config"));
+
+    tokens::group(Delimiter::Brace, body).into()
+}
+
+fn generate_net_tuple_stream(network: &Network) -> TokenStream {
+    let addr = match network.addr() {
+        IpAddr::V4(addr) => generate_ipv4_stream(&addr),
+        IpAddr::V6(addr) => generate_ipv6_stream(&addr),
+    };
+
+    tokens::group(
+        Delimiter::Parenthesis,
+        tokens::list([
+            addr,
+            tokens::literal(Literal::u8_unsuffixed(network.prefix_len())).into(),
+        ]),
+    )
+    .into()
+}
+
+fn generate_net_array_stream(
+    target: &NetArrayTarget,
+    networks: impl IntoIterator<Item = Network>,
+) -> TokenStream {
+    let generate = match target {
+        NetArrayTarget::Tuple => generate_net_tuple_stream,
+        #[cfg(feature = "ipnet")]
+        NetArrayTarget::Net(NetTarget::Ipnet) => generate_ipnet_stream,
+        #[cfg(feature = "ipnetwork")]
+        NetArrayTarget::Net(NetTarget::Ipnetwork) => generate_ipnetwork_stream,
+        #[cfg(feature = "cidr")]
+        NetArrayTarget::Net(NetTarget::Cidr) => generate_cidr_stream,
+    };
+
+    tokens::group(
+        Delimiter::Bracket,
+        tokens::list(networks.into_iter().map(|v| generate(&v))),
+    )
+    .into()
+}
+
+#[cfg(feature = "ipnet")]
+fn generate_ipnet_stream(network: &Network) -> TokenStream {
+    let len = tokens::literal(Literal::u8_unsuffixed(network.prefix_len())).into();
+
+    match network.addr() {
+        IpAddr::V4(addr) => tokens::call(
+            "ipnet::Ipv4Net::new_assert",
+            [generate_ipv4_stream(&addr), len],
+        ),
+        IpAddr::V6(addr) => tokens::call(
+            "ipnet::Ipv6Net::new_assert",
+            [generate_ipv6_stream(&addr), len],
+        ),
+    }
+}
+
+#[cfg(feature = "libc")]
+fn generate_libc_ipv4_socket_stream(socket: &SocketAddrV4) -> TokenStream {
+    let mut addr = tokens::path("libc::in_addr");
+    addr.extend([tokens::group(Delimiter::Brace, {
+        let mut field: TokenStream = [tokens::ident("s_addr"), tokens::punct(':')]
+            .into_iter()
+            .collect();
+        field.extend(tokens::call(
+            "u32::from_ne_bytes",
+            [generate_mac_stream(&socket.ip().octets())],
+        ));
+
+        field
+    })]);
+
+    generate_libc_sockaddr_stream(
+        "sockaddr_in",
+        [
+            ("sin_family", generate_libc_family_stream("AF_INET")),
+            ("sin_port", generate_network_port_stream(socket.port())),
+            ("sin_addr", addr),
+        ],
+    )
+}
+
+#[cfg(feature = "libc")]
+fn generate_libc_ipv6_socket_stream(socket: &SocketAddrV6) -> TokenStream {
+    let mut addr = tokens::path("libc::in6_addr");
+    addr.extend([tokens::group(Delimiter::Brace, {
+        let mut field: TokenStream = [tokens::ident("s6_addr"), tokens::punct(':')]
+            .into_iter()
+            .collect();
+        field.extend(generate_mac_stream(&socket.ip().octets()));
+
+        field
+    })]);
+
+    generate_libc_sockaddr_stream(
+        "sockaddr_in6",
+        [
+            ("sin6_family", generate_libc_family_stream("AF_INET6")),
+            ("sin6_port", generate_network_port_stream(socket.port())),
+            // Kept in host byte order, like the standard library does
+            (
+                "sin6_flowinfo",
+                tokens::literal(Literal::u32_unsuffixed(socket.flowinfo())).into(),
+            ),
+            ("sin6_addr", addr),
+            (
+                "sin6_scope_id",
+                tokens::literal(Literal::u32_unsuffixed(socket.scope_id())).into(),
+            ),
+        ],
+    )
+}
+
+#[cfg(feature = "libc")]
+fn generate_libc_family_stream(family: &str) -> TokenStream {
+    let mut ret = tokens::path(&format!("libc::{family}"));
+    ret.extend([tokens::ident("as")]);
+    ret.extend(tokens::path("libc::sa_family_t"));
+
+    ret
+}
+
+/// Generates the port in network byte order, which depends on the endianness
+/// of the target
+#[cfg(any(feature = "libc", feature = "windows"))]
+fn generate_network_port_stream(port: u16) -> TokenStream {
+    tokens::call(
+        "u16::to_be",
+        [tokens::literal(Literal::u16_unsuffixed(port)).into()],
+    )
+}
+
+/// Generates the block building the `libc::<ty>` structure from its zeroed
+/// value then assigning `fields`
+///
+/// The layout of the socket address structures depends on the platform, some
+/// of them having a `sin_len` field or padding fields, which are left zeroed.
+#[cfg(feature = "libc")]
+fn generate_libc_sockaddr_stream<const N: usize>(
+    ty: &str,
+    fields: [(&str, TokenStream); N],
+) -> TokenStream {
+    let mut zeroed = tokens::path("core::mem::zeroed");
+    zeroed.extend(tokens::op("::"));
+    zeroed.extend([tokens::punct('<')]);
+    zeroed.extend(tokens::path(&format!("libc::{ty}")));
+    zeroed.extend([
+        tokens::punct('>'),
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+    ]);
+
+    let mut body = tokens::let_statement(
+        "mut addr",
+        [
+            tokens::ident("unsafe"),
+            tokens::group(Delimiter::Brace, zeroed),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    for (field, value) in fields {
+        body.extend([
+            tokens::ident("addr"),
+            tokens::punct('.'),
+            tokens::ident(field),
+            tokens::punct('='),
+        ]);
+        body.extend(value);
+        body.extend([tokens::punct(';')]);
+    }
+
+    body.extend([tokens::ident("addr")]);
+
+    tokens::group(Delimiter::Brace, body).into()
+}
+
+/// Generates the `<path> { <fields> }` structure expression
+#[cfg(feature = "windows")]
+fn generate_struct_stream<const N: usize>(
+    path: &str,
+    fields: [(&str, TokenStream); N],
+) -> TokenStream {
+    let mut ret = tokens::path(path);
+    ret.extend([tokens::group(
+        Delimiter::Brace,
+        tokens::list(fields.into_iter().map(|(name, value)| {
+            let mut field: TokenStream = [tokens::ident(name), tokens::punct(':')]
+                .into_iter()
+                .collect();
+            field.extend(value);
+
+            field
+        })),
+    )]);
+
+    ret
+}
+
+#[cfg(feature = "windows")]
+const WINSOCK_PREFIX: &str = "windows_sys::Win32::Networking::WinSock";
+
+#[cfg(feature = "windows")]
+fn generate_winsock_ipv4_socket_stream(socket: &SocketAddrV4) -> TokenStream {
+    let addr = generate_struct_stream(
+        &format!("{WINSOCK_PREFIX}::IN_ADDR_0"),
+        [(
+            "S_addr",
+            tokens::call(
+                "u32::from_ne_bytes",
+                [generate_mac_stream(&socket.ip().octets())],
+            ),
+        )],
+    );
+
+    generate_struct_stream(
+        &format!("{WINSOCK_PREFIX}::SOCKADDR_IN"),
+        [
+            (
+                "sin_family",
+                tokens::path(&format!("{WINSOCK_PREFIX}::AF_INET")),
+            ),
+            ("sin_port", generate_network_port_stream(socket.port())),
+            (
+                "sin_addr",
+                generate_struct_stream(&format!("{WINSOCK_PREFIX}::IN_ADDR"), [("S_un", addr)]),
+            ),
+            ("sin_zero", generate_zeroed_array_stream(8)),
+        ],
+    )
+}
+
+#[cfg(feature = "windows")]
+fn generate_winsock_ipv6_socket_stream(socket: &SocketAddrV6) -> TokenStream {
+    let addr = generate_struct_stream(
+        &format!("{WINSOCK_PREFIX}::IN6_ADDR_0"),
+        [("Byte", generate_mac_stream(&socket.ip().octets()))],
+    );
+
+    let scope_id = generate_struct_stream(
+        &format!("{WINSOCK_PREFIX}::SOCKADDR_IN6_0"),
+        [(
+            "sin6_scope_id",
+            tokens::literal(Literal::u32_unsuffixed(socket.scope_id())).into(),
+        )],
+    );
+
+    generate_struct_stream(
+        &format!("{WINSOCK_PREFIX}::SOCKADDR_IN6"),
+        [
+            (
+                "sin6_family",
+                tokens::path(&format!("{WINSOCK_PREFIX}::AF_INET6")),
+            ),
+            ("sin6_port", generate_network_port_stream(socket.port())),
+            // Kept in host byte order, like the standard library does
+            (
+                "sin6_flowinfo",
+                tokens::literal(Literal::u32_unsuffixed(socket.flowinfo())).into(),
+            ),
+            (
+                "sin6_addr",
+                generate_struct_stream(&format!("{WINSOCK_PREFIX}::IN6_ADDR"), [("u", addr)]),
+            ),
+            ("Anonymous", scope_id),
+        ],
+    )
+}
+
+/// Generates the `[0; <len>]` array
+#[cfg(feature = "windows")]
+fn generate_zeroed_array_stream(len: usize) -> TokenStream {
+    tokens::group(
+        Delimiter::Bracket,
+        [
+            tokens::literal(Literal::u8_unsuffixed(0)),
+            tokens::punct(';'),
+            tokens::literal(Literal::usize_unsuffixed(len)),
+        ]
+        .into_iter()
+        .collect(),
+    )
+    .into()
+}
+
+#[cfg(feature = "socket2")]
+fn generate_socket2_stream(socket: TokenStream) -> TokenStream {
+    tokens::call("socket2::SockAddr::from", [socket])
+}
+
+#[cfg(feature = "ipnetwork")]
+fn generate_ipnetwork_stream(network: &Network) -> TokenStream {
+    let len = tokens::literal(Literal::u8_unsuffixed(network.prefix_len())).into();
+
+    // The error of `Ipv4Network::new` holds a `String`, which can't be dropped
+    // in const contexts
+    let network = match network.addr() {
+        IpAddr::V4(addr) => tokens::call(
+            "ipnetwork::Ipv4Network::new_checked",
+            [generate_ipv4_stream(&addr), len],
+        ),
+        IpAddr::V6(addr) => tokens::call(
+            "ipnetwork::Ipv6Network::new_checked",
+            [generate_ipv6_stream(&addr), len],
+        ),
+    };
+
+    generate_const_unwrap_stream(network, "Some", tokens::path("None"))
+}
+
+/// Generates a `cidr` network, which is known to have no bit set outside of
+/// its prefix
+#[cfg(feature = "cidr")]
+fn generate_cidr_stream(network: &Network) -> TokenStream {
+    let len = tokens::literal(Literal::u8_unsuffixed(network.prefix_len())).into();
+
+    let network = match network.addr() {
+        IpAddr::V4(addr) => tokens::call("cidr::Ipv4Cidr::new", [generate_ipv4_stream(&addr), len]),
+        IpAddr::V6(addr) => tokens::call("cidr::Ipv6Cidr::new", [generate_ipv6_stream(&addr), len]),
+    };
+
+    generate_const_unwrap_stream(network, "Ok", tokens::call("Err", [tokens::path("_")]))
+}
+
+#[cfg(feature = "nix")]
+fn generate_nix_socket_stream(socket: &SocketAddr) -> TokenStream {
+    match socket {
+        SocketAddr::V4(socket) => tokens::call(
+            "nix::sys::socket::SockaddrIn::from",
+            [generate_ipv4_socket_stream(socket)],
+        ),
+        SocketAddr::V6(socket) => tokens::call(
+            "nix::sys::socket::SockaddrIn6::from",
+            [generate_ipv6_socket_stream(socket)],
+        ),
+    }
+}
+
+/// Generates the `embedded_nal` equivalent of a `std::net` expression, as
+/// embedded-nal re-exports the address types of either `core::net` or
+/// no-std-net
+#[cfg(feature = "embedded-nal")]
+fn generate_embedded_nal_stream(stream: TokenStream) -> TokenStream {
+    emit::with_prefix(stream, &tokens::path("embedded_nal"))
+}
+
+#[cfg(feature = "smoltcp")]
+fn generate_smoltcp_address_stream(addr: &IpAddr) -> TokenStream {
+    match addr {
+        IpAddr::V4(ip) => tokens::call(
+            "smoltcp::wire::Ipv4Address::new",
+            ip.octets()
+                .map(|v| tokens::literal(Literal::u8_unsuffixed(v)).into()),
+        ),
+        IpAddr::V6(ip) => tokens::call(
+            "smoltcp::wire::Ipv6Address::new",
+            ip.segments()
+                .map(|v| tokens::literal(Literal::u16_unsuffixed(v)).into()),
+        ),
+    }
+}
+
+#[cfg(feature = "smoltcp")]
+fn generate_smoltcp_ip_stream(addr: &IpAddr) -> TokenStream {
+    let variant = match addr {
+        IpAddr::V4(_) => "smoltcp::wire::IpAddress::Ipv4",
+        IpAddr::V6(_) => "smoltcp::wire::IpAddress::Ipv6",
+    };
+
+    tokens::call(variant, [generate_smoltcp_address_stream(addr)])
+}
+
+#[cfg(feature = "smoltcp")]
+fn generate_smoltcp_endpoint_stream(socket: &SocketAddr) -> TokenStream {
+    tokens::call(
+        "smoltcp::wire::IpEndpoint::new",
+        [
+            generate_smoltcp_ip_stream(&socket.ip()),
+            tokens::literal(Literal::u16_unsuffixed(socket.port())).into(),
+        ],
+    )
+}
+
+fn generate_ip_stream(addr: &IpAddr) -> TokenStream {
+    match addr {
+        IpAddr::V4(ip) => tokens::call(
+            &format!("{OBJECT_PREFIX}::IpAddr::V4"),
+            [generate_ipv4_stream(ip)],
+        ),
+        IpAddr::V6(ip) => tokens::call(
+            &format!("{OBJECT_PREFIX}::IpAddr::V6"),
+            [generate_ipv6_stream(ip)],
+        ),
+    }
+}
+
+fn generate_ip_socket_stream(socket: &SocketAddr) -> TokenStream {
+    match socket {
+        SocketAddr::V4(socket) => tokens::call(
+            &format!("{OBJECT_PREFIX}::SocketAddr::V4"),
+            [generate_ipv4_socket_stream(socket)],
+        ),
+        SocketAddr::V6(socket) => tokens::call(
+            &format!("{OBJECT_PREFIX}::SocketAddr::V6"),
+            [generate_ipv6_socket_stream(socket)],
+        ),
+    }
+}
+
+/// Generates the test of whether the address given by `expr` belongs to
+/// `network`, comparing the network part of its bits
+///
+/// The expression can be of any type converted to an `IpAddr` by `From`.
+fn generate_subnet_test_stream(expr: TokenStream, network: &Network) -> TokenStream {
+    let (family, ty, digits, bits) = match network.truncated().addr() {
+        IpAddr::V4(addr) => ("V4", "u32", 8, u32::from(addr) as u128),
+        IpAddr::V6(addr) => ("V6", "u128", 32, u128::from(addr)),
+    };
+
+    let mut octets = tokens::path("addr");
+    octets.extend([tokens::punct('.')]);
+    octets.extend(tokens::call("octets", []));
+
+    let mut test = tokens::call(&format!("{ty}::from_be_bytes"), [octets]);
+    test.extend([
+        tokens::punct('&'),
+        tokens::hex_suffixed(network.mask(), digits, ty),
+    ]);
+
+    let mut arms = tokens::path(&format!("{OBJECT_PREFIX}::IpAddr::{family}"));
+    arms.extend([tokens::group(Delimiter::Parenthesis, tokens::path("addr"))]);
+    arms.extend(tokens::op("=>"));
+    arms.extend([tokens::group(Delimiter::Parenthesis, test)]);
+    arms.extend(tokens::op("=="));
+    arms.extend([
+        tokens::hex_suffixed(bits, digits, ty),
+        tokens::punct(','),
+        tokens::ident("_"),
+    ]);
+    arms.extend(tokens::op("=>"));
+    arms.extend([tokens::ident("false")]);
+
+    let mut ret: TokenStream = [tokens::ident("match")].into_iter().collect();
+    ret.extend(tokens::call(
+        &format!("{OBJECT_PREFIX}::IpAddr::from"),
+        [expr],
+    ));
+    ret.extend([tokens::group(Delimiter::Brace, arms)]);
+
+    ret
+}
+
+/// Generates the range pattern matching the bits of the addresses of
+/// `network`
+fn generate_subnet_pattern_stream(network: &Network) -> TokenStream {
+    let (ty, digits, first) = match network.addr() {
+        IpAddr::V4(addr) => ("u32", 8, u32::from(addr) as u128),
+        IpAddr::V6(addr) => ("u128", 32, u128::from(addr)),
+    };
+
+    let last = first | (network.mask() ^ (u128::MAX >> (128 - digits * 4)));
+
+    let mut ret: TokenStream = [tokens::hex_suffixed(first, digits, ty)]
+        .into_iter()
+        .collect();
+    ret.extend(tokens::op("..="));
+    ret.extend([tokens::hex_suffixed(last, digits, ty)]);
+
+    ret
+}
+
+/// Generates the array pattern matching the octets of the addresses of
+/// `network`, each octet being matched by a value, a range or a wildcard
+fn generate_subnet_octets_pattern_stream(network: &Network) -> TokenStream {
+    let octets = match network.addr() {
+        IpAddr::V4(addr) => addr.octets().to_vec(),
+        IpAddr::V6(addr) => addr.octets().to_vec(),
+    };
+
+    let patterns = octets.iter().enumerate().map(|(i, octet)| {
+        let prefix_bits = (network.prefix_len() as usize).saturating_sub(i * 8).min(8);
+
+        match prefix_bits {
+            0 => tokens::ident("_").into(),
+            8 => tokens::literal(Literal::u8_unsuffixed(*octet)).into(),
+            _ => {
+                let mut range: TokenStream = [tokens::literal(Literal::u8_unsuffixed(*octet))]
+                    .into_iter()
+                    .collect();
+                range.extend(tokens::op("..="));
+                range.extend([tokens::literal(Literal::u8_unsuffixed(
+                    octet | (0xff >> prefix_bits),
+                ))]);
+
+                range
+            }
+        }
+    });
+
+    tokens::group(Delimiter::Bracket, tokens::list(patterns)).into()
+}
+
+/// Generates a `&'static [<ty>]` slice through a constant named `name`
+fn generate_slice_stream(
+    name: &str,
+    ty: TokenStream,
+    items: impl IntoIterator<Item = TokenStream>,
+) -> TokenStream {
+    let mut body: TokenStream = [
+        tokens::ident("const"),
+        tokens::ident(name),
+        tokens::punct(':'),
+        tokens::punct('&'),
+    ]
+    .into_iter()
+    .collect();
+    body.extend([tokens::group(Delimiter::Bracket, ty)]);
+    body.extend([tokens::punct('='), tokens::punct('&')]);
+    body.extend([tokens::group(Delimiter::Bracket, tokens::list(items))]);
+    body.extend([tokens::punct(';'), tokens::ident(name)]);
+
+    tokens::group(Delimiter::Brace, body).into()
+}
+
+fn generate_socket_slice_stream(sockets: &[SocketAddr]) -> TokenStream {
+    generate_slice_stream(
+        "SOCKETS",
+        tokens::path(&format!("{OBJECT_PREFIX}::SocketAddr")),
+        sockets.iter().map(generate_ip_socket_stream),
+    )
+}
+
+/// Wraps `expr` in a block reading the environment variable `name` with
+/// `env!`, so that the compiler rebuilds the crate when it changes
+fn generate_env_tracking_stream(name: &str, expr: TokenStream) -> TokenStream {
+    let mut body = tokens::tracking_const("env", name);
+    body.extend(expr);
+
+    tokens::group(Delimiter::Brace, body).into()
+}
+
+/// Returns the lines of an address file along with their index, without
+/// their `#` comments and skipping the empty ones
+fn data_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn report_error<T>(value: Result<T, arg_parser::Error>) -> T {
+    match value {
+        Ok(v) => v,
+        Err(e) => {
+            abort!(e.span(), "{}", e);
+        }
+    }
+}
+
+/// Reports a parsing error without aborting, so that the following arguments
+/// are still examined
+fn emit_parse_error<T>(value: Result<T, arg_parser::Error>) -> Option<T> {
+    match value {
+        Ok(v) => Some(v),
+        Err(e) => {
+            emit_error!(e.span(), "{}", e);
+
+            None
+        }
+    }
+}
+
+/// Returns the next option, reporting the malformed ones without aborting
+fn next_option(parser: &mut ArgParser) -> Option<Opt> {
+    loop {
+        if let Some(opt) = emit_parse_error(parser.next_option()) {
+            return opt;
+        }
+    }
+}
+
+/// Returns the host name given in place of an address, if any
+fn host_name(value: &str) -> Option<&str> {
+    let host = match value.rsplit_once(':') {
+        Some((host, port)) if port.bytes().all(|c| c.is_ascii_digit()) => host,
+        _ => value,
+    };
+
+    let is_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'-')
+    };
+
+    if host.len() <= 253
+        && host.bytes().any(|c| c.is_ascii_alphabetic())
+        && host.trim_end_matches('.').split('.').all(is_label)
+    {
+        Some(host)
+    } else {
+        None
+    }
+}
+
+fn report_invalid_address_error(
+    span: Span,
+    value: &str,
+    kind: &str,
+    origin: Option<&Included>,
+) -> ! {
+    emit_invalid_address_error(span, value, kind, origin);
+    abort_if_dirty();
+
+    unreachable!()
+}
+
+/// Reports an invalid address without aborting, for the macros taking several
+/// addresses
+fn emit_invalid_address_error(span: Span, value: &str, kind: &str, origin: Option<&Included>) {
+    let origin = origin.map(|v| format!("The address is included from {v}"));
+
+    if let Some(host) = host_name(value) {
+        emit_error!(
+            span,
+            "The given address `{}` is not a valid {}: `{}` is a host name, which can't be resolved at compile time",
+            value,
+            kind,
+            host;
+            help = "Resolve the host name at runtime, using `std::net::ToSocketAddrs` for instance";
+            note =? origin
+        );
+    } else if let Some(port) =
+        out_of_bounds_port(value).filter(|_| kind.ends_with("socket address"))
+    {
+        emit_error!(
+            span,
+            "The given address `{}` is not a valid {}: the port `{}` is out of bounds, expected a `u16` between 0 and {}",
+            value,
+            kind,
+            port,
+            u16::MAX;
+            note =? origin
+        );
+    } else if Network::parse(value, false).is_some() {
+        emit_error!(
+            span,
+            "The given address `{}` is not a valid {}: it is a network",
+            value,
+            kind;
+            help = "List the networks with `ip_set!` or `aggregate!`, or generate them with `ipv4_net!` or `ipv6_net!`";
+            note =? origin
+        );
+    } else {
+        emit_error!(
+            span,
+            "The given address `{}` is not a valid {}",
+            value,
+            kind;
+            note =? origin
+        );
+    }
+}
+
+/// Reports an invalid address read from an environment variable, `note`
+/// naming the variable
+fn report_invalid_env_address_error(span: Span, value: &str, kind: &str, note: &str) -> ! {
+    abort!(
+        span,
+        "The given address `{}` is not a valid {}",
+        value,
+        kind;
+        note = note
+    );
+}
+
+fn report_invalid_network_error(
+    span: Span,
+    value: &str,
+    kind: &str,
+    origin: Option<&Included>,
+) -> ! {
+    emit_invalid_network_error(span, value, kind, origin);
+    abort_if_dirty();
+
+    unreachable!()
+}
+
+/// Reports an invalid network without aborting, for the macros taking several
+/// networks
+fn emit_invalid_network_error(span: Span, value: &str, kind: &str, origin: Option<&Included>) {
+    let origin = origin.map(|v| format!("The network is included from {v}"));
+
+    let family_matches = |max: u8| match max {
+        32 => !kind.starts_with("IPv6"),
+        _ => !kind.starts_with("IPv4"),
+    };
+
+    if let Some((len, max)) =
+        Network::out_of_bounds_len(value).filter(|(_, max)| family_matches(*max))
+    {
+        emit_error!(
+            span,
+            "The given network `{}` is not a valid {}: the prefix length `{}` is out of bounds, expected a length between 0 and {}",
+            value,
+            kind,
+            len,
+            max;
+            note =? origin
+        );
+    } else {
+        emit_error!(
+            span,
+            "The given network `{}` is not a valid {} in CIDR notation",
+            value,
+            kind;
+            note =? origin
+        );
+    }
+}
+
+/// Returns the port of a socket address when it doesn't fit in a `u16`
+fn out_of_bounds_port(value: &str) -> Option<&str> {
+    let (host, port) = value.rsplit_once(':')?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(host);
+
+    if port.is_empty()
+        || !port.bytes().all(|c| c.is_ascii_digit())
+        || port.parse::<u16>().is_ok()
+        || IpAddr::from_str(host.split('%').next().unwrap_or(host)).is_err()
+    {
+        return None;
+    }
+
+    Some(port)
+}
+
+fn report_too_few_arguments_error(given: usize, expected: usize) -> ! {
+    abort!(
+        Span::call_site(),
+        "Too few argument: Given {}, expected {}",
+        given,
+        expected
+    );
+}
+
+fn report_unknown_option_error(opt: &Opt) {
+    emit_error!(opt.span(), "Unknown option `{}`", opt.name());
+}
+
+/// Options accepted by the IP address macros
+#[derive(Default)]
+struct IpOptions {
+    checks: Checks,
+    emit: Emit,
+}
+
+/// Address parsed by the macros accepting both IP and socket addresses
+enum IpOrSocket {
+    Ip(IpAddr),
+    Socket(SocketAddr),
+}
+
+/// Address given to the macros accepting either a string representation or
+/// an expression evaluated at runtime
+enum IpOrExpr {
+    Ip(String, Span, IpAddr),
+    Expr(TokenStream),
+}
+
+/// Options accepted by the socket macros
+struct SocketOptions {
+    target: SocketTarget,
+    checks: Checks,
+    emit: Emit,
+}
+
+/// Options accepted by the hardware address macros
+struct MacOptions {
+    target: MacTarget,
+    emit: Emit,
+}
+
+/// Parses the options accepted by the IP address macros
+///
+/// `extra` parses the options specific to the macro, returning `false` if the
+/// option is unknown.
+fn parse_ip_options(parser: &mut ArgParser, mut extra: impl FnMut(&Opt) -> bool) -> IpOptions {
+    let mut options = IpOptions::default();
+
+    while let Some(opt) = next_option(parser) {
+        if !options.checks.parse_option(&opt) && !options.emit.parse_option(&opt) && !extra(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    options
+}
+
+/// Parses the options accepted by the socket address macros
+///
+/// `extra` parses the options specific to the macro, returning `false` if the
+/// option is unknown.
+fn parse_socket_options(
+    parser: &mut ArgParser,
+    mut extra: impl FnMut(&Opt) -> bool,
+) -> SocketOptions {
+    let mut options = SocketOptions {
+        target: SocketTarget::Net,
+        checks: Checks::default(),
+        emit: Emit::default(),
+    };
+
+    while let Some(opt) = next_option(parser) {
+        match opt.name() {
+            "as" => options.target = SocketTarget::from_option(&opt),
+            _ if options.checks.parse_option(&opt) => {}
+            _ if options.emit.parse_option(&opt) => {}
+            _ if extra(&opt) => {}
+            _ => report_unknown_option_error(&opt),
+        }
+    }
+
+    if let Some(span) = options.emit.const_block() {
+        if !options.target.is_const() {
+            abort!(
+                span,
+                "The selected output target can't be evaluated in const contexts"
+            );
+        }
+    }
+
+    options
+}
+
+fn parse_mac_options(parser: &mut ArgParser) -> MacOptions {
+    let mut options = MacOptions {
+        target: MacTarget::Array,
+        emit: Emit::default(),
+    };
+
+    while let Some(opt) = next_option(parser) {
+        match opt.name() {
+            "as" => options.target = MacTarget::from_option(&opt),
+            _ if options.emit.parse_option(&opt) => {}
+            _ => report_unknown_option_error(&opt),
+        }
+    }
+
+    options
+}
+
+/// Parses the `order` option of the integer output targets, `order` being set
+/// to whether the integers are stored in network byte order and to the span of
+/// the option
+fn parse_order_option(opt: &Opt, order: &mut Option<(bool, Span)>) -> bool {
+    if opt.name() != "order" {
+        return false;
+    }
+
+    match report_error(opt.value_ident()).as_str() {
+        "network" => *order = Some((true, opt.span())),
+        "host" => *order = Some((false, opt.span())),
+        name => emit_error!(
+            opt.span(),
+            "Unknown byte order `{}`, expected `network` or `host`",
+            name
+        ),
+    }
+
+    true
+}
+
+/// Parses the `wrapped` option of the family-specific macros
+fn parse_wrapped_option(opt: &Opt, wrapped: &mut Option<Span>) -> bool {
+    if opt.name() == "wrapped" {
+        emit_parse_error(opt.flag());
+        *wrapped = Some(opt.span());
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Returns the type of a value given as a suffixed integer or float literal,
+/// or as a `bool` or `char` literal
+fn literal_type(value: &TokenStream) -> Option<TokenStream> {
+    let mut tokens = value.clone().into_iter();
+
+    let (Some(token), None) = (tokens.next(), tokens.next()) else {
+        return None;
+    };
+
+    let ty = match token {
+        TokenTree::Ident(ident) if matches!(ident.to_string().as_str(), "true" | "false") => {
+            "bool".to_owned()
+        }
+        TokenTree::Literal(literal) => match litrs::Literal::from(literal) {
+            litrs::Literal::Integer(v) if !v.suffix().is_empty() => v.suffix().to_owned(),
+            litrs::Literal::Float(v) if !v.suffix().is_empty() => v.suffix().to_owned(),
+            litrs::Literal::Char(_) => "char".to_owned(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(tokens::path(&ty))
+}
+
+/// Parses the `sort` option of the list macros
+fn parse_sort_option(opt: &Opt, sort: &mut Option<Sort>) -> bool {
+    if opt.name() != "sort" {
+        return false;
+    }
+
+    if sort.is_some() {
+        emit_error!(opt.span(), "The `sort` value is given twice");
+    } else {
+        *sort = Some(Sort::from_option(opt));
+    }
+
+    true
+}
+
+/// Parses the `deny_overlaps` option of the network list macros
+fn parse_overlaps_option(opt: &Opt, deny_overlaps: &mut bool) -> bool {
+    if opt.name() == "deny_overlaps" {
+        emit_parse_error(opt.flag());
+        *deny_overlaps = true;
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Reports the networks overlapping a previous network of the list, as
+/// rejected by the `deny_overlaps` option
+fn emit_overlap_errors(networks: &[(Network, String, Span)]) {
+    for (i, (network, literal, span)) in networks.iter().enumerate() {
+        let previous = networks[..i].iter().find(|(v, _, _)| v.overlaps(network));
+
+        if let Some((_, other, other_span)) = previous {
+            emit_error!(
+                *span,
+                "The network `{}` overlaps the network `{}`, which the `deny_overlaps` option rejects",
+                literal,
+                other
+            );
+
+            // Span notes are only rendered by nightly compilers, so the
+            // other network is pointed at by its own error
+            emit_error!(
+                *other_span,
+                "The network `{}` is overlapped by the network `{}` given later",
+                other,
+                literal
+            );
+        }
+    }
+}
+
+/// Address of a list along with the attributes of its entry, its literal and
+/// its span
+type AttributedAddress<T> = (Vec<Attribute>, T, String, Span);
+
+/// Returns the next string argument of a list along with the attributes given
+/// before it
+fn next_attributed_string(parser: &mut ArgParser) -> Option<(Vec<Attribute>, String, Span)> {
+    let attributes = report_error(parser.next_attributes());
+
+    match report_error(parser.next_string()) {
+        Some((literal, span)) => Some((attributes, literal, span)),
+        None => match attributes.first() {
+            Some(attribute) => abort!(attribute.span, "Expected an entry after the attribute"),
+            None => None,
+        },
+    }
+}
+
+/// Collects the tokens of the attributes of an entry
+fn attribute_stream(attributes: &[Attribute]) -> TokenStream {
+    attributes.iter().map(|v| v.tokens.clone()).collect()
+}
+
+/// Parses the addresses given as positional arguments, each one possibly
+/// preceded by attributes, returning them along with the number of arguments
+/// given
+///
+/// With `groups`, the addresses can also be grouped as `<name>: [<entries>]`,
+/// the entries of a group being flattened in place and given the attributes
+/// of the group. Invalid addresses are reported without aborting.
+fn parse_attributed_address_list<T: FromStr>(
+    parser: &mut ArgParser,
+    kind: &str,
+    groups: bool,
+) -> (Vec<AttributedAddress<T>>, usize) {
+    let mut addrs = Vec::new();
+    let given = parse_address_entries(parser, kind, groups, &[], &mut addrs);
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    (addrs, given)
+}
+
+/// Parses the entries of [`parse_attributed_address_list`] into `addrs`, each
+/// one being given the attributes `inherited` from its groups, and returns
+/// the number of arguments given
+fn parse_address_entries<T: FromStr>(
+    parser: &mut ArgParser,
+    kind: &str,
+    groups: bool,
+    inherited: &[Attribute],
+    addrs: &mut Vec<AttributedAddress<T>>,
+) -> usize {
+    let mut given = 0;
+    let mut names: Vec<proc_macro::Ident> = Vec::new();
+
+    loop {
+        let mut attributes = inherited.to_vec();
+        attributes.extend(report_error(parser.next_attributes()));
+
+        if groups {
+            if let Some((name, mut group)) = report_error(parser.next_group()) {
+                given += 1;
+
+                if let Some(other) = names.iter().find(|v| v.to_string() == name.to_string()) {
+                    emit_error!(name.span(), "The group `{}` is given twice", name);
+                    emit_error!(other.span(), "The group `{}` is given again later", other);
+                }
+
+                if parse_address_entries(&mut group, kind, groups, &attributes, addrs) == 0 {
+                    emit_error!(name.span(), "The group `{}` holds no entry", name);
+                }
+
+                if let Some(span) = report_error(group.ignore_next()) {
+                    emit_error!(span, "Unexpected argument in the group `{}`", name);
+                }
+
+                parser.absorb(group);
+                names.push(name);
+                continue;
+            }
+        }
+
+        let Some((literal, span)) = report_error(parser.next_string()) else {
+            if let Some(attribute) = attributes.get(inherited.len()) {
+                abort!(attribute.span, "Expected an entry after the attribute");
+            }
+
+            return given;
+        };
+
+        given += 1;
+
+        match T::from_str(literal.as_str()) {
+            Ok(v) => addrs.push((attributes, v, literal, span)),
+            Err(_) => emit_invalid_address_error(span, &literal, kind, parser.origin()),
+        }
+    }
+}
+
+/// Parses the addresses given as positional arguments, returning them along
+/// with the number of arguments given
+///
+/// Invalid addresses are reported without aborting, as well as the
+/// attributes, which only some lists accept.
+fn parse_address_list<T: FromStr>(
+    parser: &mut ArgParser,
+    kind: &str,
+) -> (Vec<(T, String, Span)>, usize) {
+    let (addrs, given) = parse_attributed_address_list(parser, kind, false);
+
+    addrs
+        .iter()
+        .flat_map(|(attributes, _, _, _)| attributes)
+        .for_each(|v| {
+            emit_error!(
+                v.span,
+                "The entries of this macro can't be given attributes"
+            )
+        });
+
+    let addrs = addrs
+        .into_iter()
+        .map(|(_, addr, literal, span)| (addr, literal, span))
+        .collect();
+
+    (addrs, given)
+}
+
+/// Parses the networks given as positional arguments, returning them along
+/// with the number of arguments given
+///
+/// Invalid networks are reported without aborting, as well as the networks of
+/// another family than the first one.
+fn parse_network_list(parser: &mut ArgParser) -> (Vec<(Network, String, Span)>, usize) {
+    let mut networks: Vec<(Network, String, Span)> = Vec::new();
+    let mut given = 0;
+
+    while let Some((literal, span)) = report_error(parser.next_string()) {
+        given += 1;
+
+        let Some(network) = Network::parse(literal.as_str(), false) else {
+            emit_invalid_network_error(span, &literal, "network", parser.origin());
+            continue;
+        };
+
+        match networks.first() {
+            Some((first, _, _)) if first.addr().is_ipv4() != network.addr().is_ipv4() => {
+                emit_error!(
+                    span,
+                    "The network `{}` is not of the address family of the first network",
+                    literal;
+                    help = "Use two distinct lists for the IPv4 and IPv6 networks"
+                );
+            }
+            _ => networks.push((network, literal, span)),
+        }
+    }
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    (networks, given)
+}
+
+/// Parses an IPv4 socket address given either as its string representation or
+/// as an address followed by the port, then the options parsed by
+/// `parse_options`
+fn next_socketv4<O>(
+    parser: &mut ArgParser,
+    parse_options: impl FnOnce(&mut ArgParser) -> O,
+) -> (String, Span, SocketAddrV4, O) {
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let port = emit_parse_error(parser.next_integer("port")).flatten();
+
+    let options = parse_options(parser);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let socket = match port {
+        Some((port, _)) => match Ipv4Addr::from_str(literal.as_str()) {
+            Ok(v) => SocketAddrV4::new(v, port),
+            Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+        },
+        None => match SocketAddrV4::from_str(literal.as_str()) {
+            Ok(v) => v,
+            Err(_) => {
+                report_invalid_address_error(span, &literal, "IPv4 socket address", parser.origin())
+            }
+        },
+    };
+
+    abort_if_dirty();
+
+    (literal, span, socket, options)
+}
+
+/// Parses an IPv6 socket address given either as its string representation or
+/// as an address followed by the port, then its optional flow information and
+/// scope id, then the options parsed by `parse_options`
+///
+/// `parse_options` receives the parser of the `flow_info` and `scope_id`
+/// options, returning `false` if the option is unknown.
+fn next_socketv6<O>(
+    parser: &mut ArgParser,
+    parse_options: impl FnOnce(&mut ArgParser, &mut dyn FnMut(&Opt) -> bool) -> O,
+) -> (String, Span, SocketAddrV6, O) {
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    // A bare address is followed by the port, then by the optional fields
+    let bare = Ipv6Addr::from_str(literal.as_str()).ok();
+    let port = if bare.is_some() {
+        emit_parse_error(parser.next_integer("port")).flatten()
+    } else {
+        None
+    };
+
+    let mut flow_info = emit_parse_error(parser.next_integer("flow_info")).flatten();
+    let mut scope_id = emit_parse_error(parser.next_integer("scope_id")).flatten();
+
+    let options = parse_options(parser, &mut |opt| {
+        let value = match opt.name() {
+            "flow_info" => &mut flow_info,
+            "scope_id" => &mut scope_id,
+            _ => return false,
+        };
+
+        if value.is_some() {
+            emit_error!(opt.span(), "The `{}` value is given twice", opt.name());
+        } else if let Some(v) = emit_parse_error(opt.value_integer()) {
+            *value = Some((v, opt.span()));
+        }
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        let expected = if port.is_some() { 4 } else { 3 };
+        report_too_many_arguments_error(span, parser.count_arguments(), expected);
+    }
+
+    let mut socket = match (bare, port) {
+        (Some(addr), Some((port, _))) => SocketAddrV6::new(addr, port, 0, 0),
+        _ => match SocketAddrV6::from_str(literal.as_str()) {
+            Ok(v) => v,
+            Err(_) => {
+                report_invalid_address_error(span, &literal, "IPv6 socket address", parser.origin())
+            }
+        },
+    };
+
+    abort_if_dirty();
+
+    if let Some((flow_info, _)) = flow_info {
+        socket.set_flowinfo(flow_info);
+    }
+
+    if let Some((scope_id, _)) = scope_id {
+        socket.set_scope_id(scope_id)
+    }
+
+    (literal, span, socket, options)
+}
+
+/// Parses an IP or socket address given as a string, then the options of the
+/// IP address macros
+fn next_ip_or_socket(parser: &mut ArgParser) -> (String, Span, IpOrSocket, IpOptions) {
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let addr = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => IpOrSocket::Ip(v),
+        Err(_) => match SocketAddr::from_str(literal.as_str()) {
+            Ok(v) => IpOrSocket::Socket(v),
+            Err(_) => report_invalid_address_error(
+                span,
+                &literal,
+                "IP or socket address",
+                parser.origin(),
+            ),
+        },
+    };
+
+    abort_if_dirty();
+
+    (literal, span, addr, options)
+}
+
+/// Checks an IP or socket address, returning its canonical representation
+fn check_ip_or_socket(
+    checks: &Checks,
+    diagnostics: &mut Diagnostics,
+    addr: &IpOrSocket,
+    span: Span,
+) -> String {
+    match addr {
+        IpOrSocket::Ip(v) => {
+            checks.check(diagnostics, v, span);
+            v.to_string()
+        }
+        IpOrSocket::Socket(v) => {
+            checks.check_socket(diagnostics, v, span);
+            v.to_string()
+        }
+    }
+}
+
+/// Returns the name of the PTR record of `addr`
+fn reverse_dns_name(addr: &IpAddr) -> String {
+    let mut labels: Vec<String> = match addr {
+        IpAddr::V4(addr) => addr.octets().iter().rev().map(u8::to_string).collect(),
+        // Each nibble is a label, starting with the least significant one
+        IpAddr::V6(addr) => addr
+            .octets()
+            .iter()
+            .rev()
+            .flat_map(|byte| [byte & 0xf, byte >> 4])
+            .map(|nibble| format!("{nibble:x}"))
+            .collect(),
+    };
+
+    labels.push(match addr {
+        IpAddr::V4(_) => "in-addr.arpa".to_string(),
+        IpAddr::V6(_) => "ip6.arpa".to_string(),
+    });
+
+    labels.join(".")
+}
+
+/// Prefix lengths allowed by RFC 6052 for the IPv4-embedded IPv6 addresses
+const NAT64_PREFIX_LENS: [u8; 6] = [32, 40, 48, 56, 64, 96];
+
+/// Returns the IPv4-embedded IPv6 address of `addr` in the NAT64 prefix
+/// `prefix`, as defined by RFC 6052
+///
+/// The bits 64 to 71 of the address are reserved, the IPv4 address being
+/// split around them when the prefix is shorter than 64 bits.
+fn nat64_address(prefix: &Network, addr: &Ipv4Addr) -> Ipv6Addr {
+    let mut octets = match prefix.addr() {
+        IpAddr::V6(v) => v.octets(),
+        IpAddr::V4(_) => unreachable!(),
+    };
+
+    let mut pos = prefix.prefix_len() as usize / 8;
+
+    for octet in addr.octets() {
+        if pos == 8 {
+            pos += 1;
+        }
+
+        octets[pos] = octet;
+        pos += 1;
+    }
+
+    Ipv6Addr::from(octets)
+}
+
+/// Parses the only positional argument of the macros computing an address
+/// of a network, `kind` being the expected kind of network
+///
+/// The address may be any address of the network, as in the configuration of
+/// an interface.
+fn next_network(parser: &mut ArgParser, kind: &str) -> (String, Span, Network, IpOptions) {
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) if kind != "IPv4 network" || v.addr().is_ipv4() => v,
+        _ => report_invalid_network_error(span, &literal, kind, parser.origin()),
+    };
+
+    (literal, span, network, options)
+}
+
+/// Generates an address computed from the network given to the macro `name`
+fn finish_network_address(
+    parser: &ArgParser,
+    options: IpOptions,
+    name: &str,
+    literal: &str,
+    span: Span,
+    addr: IpAddr,
+) -> TokenStream {
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &addr, span);
+
+    let (kind, stream) = match addr {
+        IpAddr::V4(v) => ("Ipv4Addr", generate_ipv4_stream(&v)),
+        IpAddr::V6(v) => ("Ipv6Addr", generate_ipv6_stream(&v)),
+    };
+
+    diagnostics.record(name, literal, kind, addr.to_string(), span);
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Parses a port given either as an integer or as a string
+fn next_port(parser: &mut ArgParser) -> (u16, Span) {
+    if !parser.next_is_string() {
+        return match report_error(parser.next_integer("port")) {
+            Some(v) => v,
+            None => report_too_few_arguments_error(0, 1),
+        };
+    }
+
+    let (literal, span) = match report_error(parser.next_string()) {
+        Some(v) => v,
+        None => report_too_few_arguments_error(0, 1),
+    };
+
+    match literal.parse::<u16>() {
+        Ok(v) => (v, span),
+        Err(_) if !literal.is_empty() && literal.bytes().all(|c| c.is_ascii_digit()) => {
+            abort!(
+                span,
+                "The port `{}` is out of bounds, expected a `u16` between 0 and {}",
+                literal,
+                u16::MAX
+            );
+        }
+        Err(_) => abort!(
+            span,
+            "The given port `{}` is not a valid port number",
+            literal
+        ),
+    }
+}
+
+/// Parses the `as` option of the IP address macros
+fn parse_ip_target_option(opt: &Opt, target: &mut IpTarget, default: &str) -> bool {
+    if opt.name() == "as" {
+        *target = IpTarget::from_option(opt, default);
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Ensures the socket address doesn't hold a flow information or a scope id
+/// when generating a smoltcp endpoint, which can't hold them
+#[cfg_attr(not(feature = "smoltcp"), allow(unused_variables))]
+fn check_endpoint_target(target: &SocketTarget, socket: &SocketAddr, span: Span) {
+    #[cfg(feature = "smoltcp")]
+    if let (SocketTarget::Smoltcp, SocketAddr::V6(socket)) = (target, socket) {
+        if socket.flowinfo() != 0 || socket.scope_id() != 0 {
+            abort!(
+                span,
+                "The `smoltcp` output target can't hold the flow information and the scope id of an IPv6 socket address"
+            );
+        }
+    }
+}
+
+/// Ensures the `any` option of `cidr!` is only used with the targets having a
+/// type for any network, and without the `wrapped` option
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+fn check_any_cidr_option(target: &NetTarget, span: Span, wrapped: Option<Span>) {
+    if !target.has_any_type() {
+        abort!(
+            span,
+            "The `any` option is only supported by the `cidr` output target"
+        );
+    }
+
+    if wrapped.is_some() {
+        abort!(
+            span,
+            "The `any` and `wrapped` options can't be used together"
+        );
+    }
+}
+
+/// Reports a network whose address isn't the network address, for the macros
+/// computing addresses of the network
+fn emit_host_bits_error(network: &Network, span: Span) {
+    emit_error!(
+        span,
+        "The network `{}` has bits set outside of its prefix",
+        network;
+        help = "Use `{}` instead", network.truncated()
+    );
+}
+
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+fn report_host_bits_error(network: &Network, span: Span) -> ! {
+    abort!(
+        span,
+        "The network `{}` has bits set outside of its prefix, which the selected output target doesn't allow",
+        network;
+        help = "Use `{}` instead", network.truncated()
+    );
+}
+
+/// Ensures the `order` option is only used with the targets generating
+/// integers
+fn check_order_target(target: &IpTarget, span: Span) {
+    if !target.has_byte_order() {
+        abort!(
+            span,
+            "The `order` option is only supported by the `u32`, `u128` and `segments` output targets"
+        );
+    }
+}
+
+/// Ensures the `wrapped` option of the IP address macros isn't used with the
+/// integer and array representations
+fn check_wrapped_ip_target(target: &IpTarget, span: Span) {
+    if target.is_representation() {
+        abort!(
+            span,
+            "The `wrapped` option can't be used with the integer and array output targets"
+        );
+    }
+}
+
+/// Ensures the `wrapped` option is only used with targets having a wrapping
+/// type
+fn check_wrapped_target(target: &SocketTarget, span: Span) {
+    if !target.has_wrapping_type() {
+        abort!(
+            span,
+            "The `wrapped` option is only supported by the `std::net` and `embedded_nal` output targets"
+        );
+    }
+}
+
+fn report_too_many_arguments_error(span: Span, given: usize, expected: usize) {
+    emit_error!(
+        span,
+        "Too many arguments: Given {}, expected {}",
+        given,
+        expected
+    );
+}
+
+/// Generate an IPv4 address from the standard textual representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address
+///
+/// The `wrapped` option generates the address wrapped in an `IpAddr`, while
+/// still guaranteeing it is an IPv4 address.
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::Ipv4Address` instead,
+/// or a `smoltcp::wire::IpAddress` with the `wrapped` option (requires the
+/// `smoltcp` feature).
+///
+/// The `as = u32` option generates the address as an `u32` literal, and the
+/// `as = octets` option as a `[u8; 4]` array in network byte order.
+///
+/// The byte arrays can also be generated in a given byte order with the
+/// `be_bytes` (same as `octets`), `le_bytes` and `ne_bytes` targets, the
+/// latter using the byte order of the target platform. The `order = network`
+/// option generates the `u32` in network byte order, as stored in the
+/// protocol headers, instead of its numeric value (`order = host`).
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv4;
+///
+/// assert_eq!(ipv4!("192.168.1.5"), std::net::Ipv4Addr::new(192, 168, 1, 5));
+/// assert_eq!(ipv4!(r#"192.168.1.5"#), std::net::Ipv4Addr::new(192, 168, 1, 5));
+/// assert_eq!(ipv4!("192.168.1.5", wrapped), std::net::IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 5)));
+/// assert_eq!(ipv4!("192.168.1.\x35"), std::net::Ipv4Addr::new(192, 168, 1, 5));
+/// assert_eq!(ipv4!("10.0.0.1", as = u32), 0x0a000001);
+/// assert_eq!(ipv4!("10.0.0.1", as = octets), [10, 0, 0, 1]);
+/// assert_eq!(ipv4!("10.0.0.1", as = le_bytes), [1, 0, 0, 10]);
+/// assert_eq!(ipv4!("10.0.0.1", as = u32, order = network), 0x0a000001u32.to_be());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv4(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut wrapped = None;
+    let mut order = None;
+    let mut target = IpTarget::Net;
+    let options = parse_ip_options(&mut parser, |opt| {
+        parse_wrapped_option(opt, &mut wrapped)
+            || parse_order_option(opt, &mut order)
+            || parse_ip_target_option(opt, &mut target, "Ipv4Addr")
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv4Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    if let Some(span) = wrapped {
+        check_wrapped_ip_target(&target, span);
+    }
+
+    if let Some((_, span)) = order {
+        check_order_target(&target, span);
+    }
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V4(ip), span);
+
+    diagnostics.record(
+        "ipv4",
+        &literal,
+        target.type_name(if wrapped.is_some() {
+            "IpAddr"
+        } else {
+            "Ipv4Addr"
+        }),
+        ip.to_string(),
+        span,
+    );
+
+    let stream = match target {
+        IpTarget::Net if wrapped.is_some() => generate_ip_stream(&IpAddr::V4(ip)),
+        IpTarget::Net => generate_ipv4_stream(&ip),
+        #[cfg(feature = "smoltcp")]
+        IpTarget::Smoltcp if wrapped.is_some() => generate_smoltcp_ip_stream(&IpAddr::V4(ip)),
+        #[cfg(feature = "smoltcp")]
+        IpTarget::Smoltcp => generate_smoltcp_address_stream(&IpAddr::V4(ip)),
+        _ => generate_representation_stream(
+            &target,
+            &IpAddr::V4(ip),
+            matches!(order, Some((true, _))),
+        ),
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generate an IPv6 address from the standard textual representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address
+///
+/// The `wrapped` option generates the address wrapped in an `IpAddr`, while
+/// still guaranteeing it is an IPv6 address.
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::Ipv6Address` instead,
+/// or a `smoltcp::wire::IpAddress` with the `wrapped` option (requires the
+/// `smoltcp` feature).
+///
+/// The `as = u128` option generates the address as an `u128` literal, the
+/// `as = octets` option as a `[u8; 16]` array in network byte order and the
+/// `as = segments` option as a `[u16; 8]` array.
+///
+/// Like `ipv4!`, the byte arrays can be generated in a given byte order with
+/// the `be_bytes`, `le_bytes` and `ne_bytes` targets, and the `order = network`
+/// option generates the integers and the segments in network byte order.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv6;
+///
+/// assert_eq!(ipv6!("::1"), std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+/// assert_eq!(ipv6!("::1", wrapped), std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST));
+/// assert_eq!(ipv6!("::1", as = u128), 1);
+/// assert_eq!(ipv6!("2001:db8::1", as = segments), [0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv6(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut wrapped = None;
+    let mut order = None;
+    let mut target = IpTarget::Net;
+    let options = parse_ip_options(&mut parser, |opt| {
+        parse_wrapped_option(opt, &mut wrapped)
+            || parse_order_option(opt, &mut order)
+            || parse_ip_target_option(opt, &mut target, "Ipv6Addr")
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv6Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv6 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    if let Some(span) = wrapped {
+        check_wrapped_ip_target(&target, span);
+    }
+
+    if let Some((_, span)) = order {
+        check_order_target(&target, span);
+    }
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V6(ip), span);
+
+    diagnostics.record(
+        "ipv6",
+        &literal,
+        target.type_name(if wrapped.is_some() {
+            "IpAddr"
+        } else {
+            "Ipv6Addr"
+        }),
+        ip.to_string(),
+        span,
+    );
+
+    let stream = match target {
+        IpTarget::Net if wrapped.is_some() => generate_ip_stream(&IpAddr::V6(ip)),
+        IpTarget::Net => generate_ipv6_stream(&ip),
+        #[cfg(feature = "smoltcp")]
+        IpTarget::Smoltcp if wrapped.is_some() => generate_smoltcp_ip_stream(&IpAddr::V6(ip)),
+        #[cfg(feature = "smoltcp")]
+        IpTarget::Smoltcp => generate_smoltcp_address_stream(&IpAddr::V6(ip)),
+        _ => generate_representation_stream(
+            &target,
+            &IpAddr::V6(ip),
+            matches!(order, Some((true, _))),
+        ),
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generate an IP address from the standard textual representation (both
+/// support IPv4 and IPv6)
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address
+///
+/// The `map_v6` option generates IPv4 addresses in their IPv4-mapped IPv6
+/// form (`::ffff:a.b.c.d`), so that every address is stored as an IPv6 one.
+/// IPv6 addresses are left untouched.
+///
+/// The `canonical` option does the opposite, generating IPv4-mapped IPv6
+/// addresses as `IpAddr::V4`, like `IpAddr::to_canonical`. Other addresses
+/// are left untouched. This option can't be used together with `map_v6`.
+///
+/// With both options, the checks are done on the address as written.
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::IpAddress` instead
+/// (requires the `smoltcp` feature).
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip;
+///
+/// assert_eq!(ip!("::1"), std::net::IpAddr::V6(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)));
+/// assert_eq!(ip!("192.168.1.5"), std::net::IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 5)));
+/// assert_eq!(ip!("192.0.2.1", map_v6), std::net::IpAddr::V6(std::net::Ipv4Addr::new(192, 0, 2, 1).to_ipv6_mapped()));
+/// assert_eq!(ip!("::ffff:192.0.2.1", canonical), std::net::IpAddr::V4(std::net::Ipv4Addr::new(192, 0, 2, 1)));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut map_v6 = None;
+    let mut canonical = None;
+    let mut target = IpTarget::Net;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "map_v6" => {
+            emit_parse_error(opt.flag());
+            map_v6 = Some(opt.span());
+
+            true
+        }
+        "canonical" => {
+            emit_parse_error(opt.flag());
+            canonical = Some(opt.span());
+
+            true
+        }
+        _ => parse_ip_target_option(opt, &mut target, "IpAddr"),
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+    };
+
+    if let (Some(_), Some(span)) = (map_v6, canonical) {
+        emit_error!(
+            span,
+            "The `canonical` and `map_v6` options can't be used together"
+        );
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    let ip = match ip {
+        IpAddr::V4(v) if map_v6.is_some() => IpAddr::V6(v.to_ipv6_mapped()),
+        IpAddr::V6(v) if canonical.is_some() => v.to_canonical(),
+        ip => ip,
+    };
+
+    diagnostics.record(
+        "ip",
+        &literal,
+        target.type_name("IpAddr"),
+        ip.to_string(),
+        span,
+    );
+
+    let stream = match target {
+        IpTarget::Net => generate_ip_stream(&ip),
+        #[cfg(feature = "smoltcp")]
+        IpTarget::Smoltcp => generate_smoltcp_ip_stream(&ip),
+        _ => generate_representation_stream(&target, &ip, false),
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the octets of an IPv4 address from its textual representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 address. The address is generated as a `[u8; 4]`
+/// array in network byte order, like `ipv4!` with the `as = octets` option.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv4_octets;
+///
+/// const GATEWAY: [u8; 4] = ipv4_octets!("192.168.1.254");
+/// assert_eq!(GATEWAY, [192, 168, 1, 254]);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv4_octets(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv4Addr::from_str(literal.as_str()) {
+        Ok(v) => IpAddr::V4(v),
+        Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    diagnostics.record("ipv4_octets", &literal, "[u8; 4]", ip.to_string(), span);
+
+    options.emit.finish(
+        parser.track(diagnostics.wrap(generate_representation_stream(
+            &IpTarget::Octets,
+            &ip,
+            false,
+        ))),
+    )
+}
+
+/// Generates the segments of an IPv6 address from its textual representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv6 address. The address is generated as a
+/// `[u16; 8]` array, like `ipv6!` with the `as = segments` option. The
+/// `order = network` option stores the segments in network byte order.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv6_segments;
+///
+/// const DOCUMENTATION: [u16; 8] = ipv6_segments!("2001:db8::1");
+/// assert_eq!(DOCUMENTATION, [0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv6_segments(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut order = None;
+    let options = parse_ip_options(&mut parser, |opt| parse_order_option(opt, &mut order));
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv6Addr::from_str(literal.as_str()) {
+        Ok(v) => IpAddr::V6(v),
+        Err(_) => report_invalid_address_error(span, &literal, "IPv6 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    diagnostics.record("ipv6_segments", &literal, "[u16; 8]", ip.to_string(), span);
+
+    options.emit.finish(
+        parser.track(diagnostics.wrap(generate_representation_stream(
+            &IpTarget::Segments,
+            &ip,
+            matches!(order, Some((true, _))),
+        ))),
+    )
+}
+
+/// Generates the canonical representation of an IP or socket address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address or of a socket address. The address is
+/// validated then generated as a `&'static str` literal holding its canonical
+/// representation, as displayed by the standard library: the longest run of
+/// zero segments of IPv6 addresses is compressed and their hexadecimal digits
+/// are lowercase.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_str;
+///
+/// const LOCALHOST: &str = ip_str!("0:0:0:0:0:0:0:1");
+/// assert_eq!(LOCALHOST, "::1");
+/// assert_eq!(ip_str!("2001:DB8:0:0:1::1"), "2001:db8::1:0:0:1");
+/// assert_eq!(ip_str!("[2001:db8:0::1]:80"), "[2001:db8::1]:80");
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_str(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, addr, options) = next_ip_or_socket(&mut parser);
+
+    let mut diagnostics = options.checks.diagnostics();
+    let value = check_ip_or_socket(&options.checks, &mut diagnostics, &addr, span);
+
+    diagnostics.record("ip_str", &literal, "&str", value.clone(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(tokens::literal(Literal::string(&value)).into())))
+}
+
+/// Generates an IP or socket address along with its canonical representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address or of a socket address. The address is
+/// generated as an `(IpAddr, &'static str)` or a `(SocketAddr, &'static str)`
+/// tuple, the string being the canonical representation generated by
+/// `ip_str!`. This avoids formatting the address at runtime.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_with_str;
+///
+/// const SERVER: (std::net::IpAddr, &str) = ip_with_str!("2001:db8:0::1");
+/// assert_eq!(SERVER.0, "2001:db8::1".parse::<std::net::IpAddr>().unwrap());
+/// assert_eq!(SERVER.1, "2001:db8::1");
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_with_str(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, addr, options) = next_ip_or_socket(&mut parser);
+
+    let mut diagnostics = options.checks.diagnostics();
+    let value = check_ip_or_socket(&options.checks, &mut diagnostics, &addr, span);
+
+    let (kind, stream) = match &addr {
+        IpOrSocket::Ip(v) => ("(IpAddr, &str)", generate_ip_stream(v)),
+        IpOrSocket::Socket(v) => ("(SocketAddr, &str)", generate_ip_socket_stream(v)),
+    };
+
+    diagnostics.record("ip_with_str", &literal, kind, value.clone(), span);
+
+    let stream = tokens::group(
+        Delimiter::Parenthesis,
+        tokens::list([stream, tokens::literal(Literal::string(&value)).into()]),
+    )
+    .into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates an IP or socket address restricted to the documentation ranges
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address or of a socket address. The address is
+/// generated as an `IpAddr` or a `SocketAddr`, like `ip!` or `socket!`, but
+/// the compilation fails unless it belongs to a range reserved for the
+/// documentation: `192.0.2.0/24`, `198.51.100.0/24` and `203.0.113.0/24`
+/// (RFC 5737), `2001:db8::/32` (RFC 3849) or `3fff::/20` (RFC 9637). This
+/// guarantees that examples never ship a routable address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::doc_ip;
+///
+/// const SERVER: std::net::IpAddr = doc_ip!("192.0.2.1");
+/// const ENDPOINT: std::net::SocketAddr = doc_ip!("[2001:db8::1]:443");
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::doc_ip;
+///
+/// const SERVER: std::net::IpAddr = doc_ip!("8.8.8.8");
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn doc_ip(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, addr, options) = next_ip_or_socket(&mut parser);
+
+    let ip = match &addr {
+        IpOrSocket::Ip(v) => *v,
+        IpOrSocket::Socket(v) => v.ip(),
+    };
+
+    if !Class::Documentation.contains(&ip) {
+        abort!(
+            span,
+            "The address `{}` is not a documentation address",
+            ip;
+            help = "Use an address of `192.0.2.0/24`, `198.51.100.0/24`, `203.0.113.0/24`, `2001:db8::/32` or `3fff::/20`"
+        );
+    }
+
+    let mut diagnostics = options.checks.diagnostics();
+    let value = check_ip_or_socket(&options.checks, &mut diagnostics, &addr, span);
+
+    let (kind, stream) = match &addr {
+        IpOrSocket::Ip(v) => ("IpAddr", generate_ip_stream(v)),
+        IpOrSocket::Socket(v) => ("SocketAddr", generate_ip_socket_stream(v)),
+    };
+
+    diagnostics.record("doc_ip", &literal, kind, value, span);
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Checks whether an IP address belongs to an address class
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of an IP address and the name of an address class, among
+/// the ones of the `expect` option. The result of the classification is
+/// generated as a `true` or `false` literal, so that it can be used in const
+/// contexts.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_is;
+///
+/// const PRIVATE: bool = ip_is!("10.0.0.1", private);
+/// assert!(PRIVATE);
+/// assert!(!ip_is!("2001:db8::1", global));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_is(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let mut class = None;
+    let options = parse_ip_options(&mut parser, |opt| {
+        let Some(value) = Class::from_name(opt.name()) else {
+            return false;
+        };
+
+        emit_parse_error(opt.flag());
+
+        if class.is_some() {
+            emit_error!(opt.span(), "The address class is given twice");
+        } else {
+            class = Some(value);
+        }
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let ip = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+    };
+
+    let Some(class) = class else {
+        report_too_few_arguments_error(1, 2);
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    let value = class.contains(&ip);
+
+    diagnostics.record("ip_is", &literal, "bool", value.to_string(), span);
+
+    options.emit.finish(
+        parser.track(diagnostics.wrap(tokens::ident(if value { "true" } else { "false" }).into())),
+    )
+}
+
+/// Checks whether an IP address belongs to a network
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: an IP address
+/// and the string representation of a network in CIDR notation.
+///
+/// When the address is given by its string representation, the membership is
+/// checked at compile time and generated as a `true` or `false` literal, so
+/// that it can be used in const contexts.
+///
+/// The address can also be given as an expression of type `IpAddr`,
+/// `Ipv4Addr` or `Ipv6Addr`. The membership is then checked at runtime by
+/// masking the bits of the address, which is all it takes. Addresses of the
+/// other family never belong to the network. The expression ends at the first
+/// comma outside of parentheses, brackets or braces.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::in_subnet;
+///
+/// const INTERNAL: bool = in_subnet!("10.1.2.3", "10.0.0.0/8");
+/// assert!(INTERNAL);
+///
+/// let addr: std::net::IpAddr = "192.168.1.5".parse().unwrap();
+/// assert!(in_subnet!(addr, "192.168.0.0/16"));
+/// assert!(!in_subnet!(addr, "2001:db8::/32"));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn in_subnet(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let addr = if parser.next_is_string() {
+        let (literal, span) = report_error(parser.next_string()).unwrap();
+
+        match IpAddr::from_str(literal.as_str()) {
+            Ok(v) => IpOrExpr::Ip(literal, span, v),
+            Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+        }
+    } else {
+        match parser.next_expression() {
+            Some((expr, _)) => IpOrExpr::Expr(expr),
+            None => report_too_few_arguments_error(0, 2),
+        }
+    };
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(1, 2);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    let stream = match addr {
+        IpOrExpr::Ip(addr_literal, addr_span, ip) => {
+            abort_if_dirty();
+
+            options.checks.check(&mut diagnostics, &ip, addr_span);
+
+            let value = network.contains(&ip);
+
+            diagnostics.record(
+                "in_subnet",
+                &addr_literal,
+                "bool",
+                value.to_string(),
+                addr_span,
+            );
+
+            tokens::ident(if value { "true" } else { "false" }).into()
+        }
+        IpOrExpr::Expr(expr) => {
+            abort_if_dirty();
+
+            generate_subnet_test_stream(expr, &network)
+        }
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the pattern matching the addresses of a network
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of a network in CIDR notation, the address having no bit
+/// set outside of the prefix. It is used in pattern position, and generates
+/// the range pattern matching the addresses of the network, as `u32` for an
+/// IPv4 network and as `u128` for an IPv6 network. This lets the compiler
+/// optimize the dispatch on the address, without any conversion to `IpAddr`.
+///
+/// The `as = octets` option generates an array pattern matching the octets of
+/// the addresses instead, as returned by `Ipv4Addr::octets` or
+/// `Ipv6Addr::octets`.
+///
+/// The address of the network is checked as the address macros do, and the
+/// same options are accepted to relax or tighten the checks. As patterns
+/// can't hold warnings, the warnings of the checks are reported as errors.
+/// For the same reason, the compiler doesn't track the files and environment
+/// variables its argument is read from, nor the address policy file.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::subnet_pat;
+///
+/// fn zone(addr: std::net::Ipv4Addr) -> &'static str {
+///     match u32::from(addr) {
+///         subnet_pat!("10.0.0.0/8") => "internal",
+///         subnet_pat!("192.168.0.0/16") => "office",
+///         subnet_pat!("240.0.0.0/4", allow_reserved) => "lab",
+///         _ => "external",
+///     }
+/// }
+///
+/// assert_eq!(zone(std::net::Ipv4Addr::new(192, 168, 1, 5)), "office");
+///
+/// assert!(matches!(
+///     std::net::Ipv4Addr::new(172, 20, 1, 1).octets(),
+///     subnet_pat!("172.16.0.0/12", as = octets)
+/// ));
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn subnet_pat(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut octets = false;
+    let mut checks = Checks::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if checks.parse_option(&opt) {
+            continue;
+        }
+
+        if opt.name() != "as" {
+            report_unknown_option_error(&opt);
+            continue;
+        }
+
+        match report_error(opt.value_ident()).as_str() {
+            "octets" => octets = true,
+            name => emit_error!(
+                opt.span(),
+                "Unknown or unsupported output target `{}` for this macro",
+                name
+            ),
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    // Each error is expanded to a macro call, in place of the pattern
+    abort_if_dirty();
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    if network.has_host_bits() {
+        emit_host_bits_error(&network, span);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = checks.diagnostics().strictly();
+    checks.check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record("subnet_pat", &literal, "pattern", network.to_string(), span);
+
+    diagnostics.finish_pattern(if octets {
+        generate_subnet_octets_pattern_stream(&network)
+    } else {
+        generate_subnet_pattern_stream(&network)
+    })
+}
+
+/// Generates the name of the reverse DNS record of an IP address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IP address. The name of its PTR record is generated
+/// as a `&'static str` literal, in the `in-addr.arpa` domain for an IPv4
+/// address and in the `ip6.arpa` domain for an IPv6 address. The latter uses
+/// the nibble format, with one label for each of the 32 hexadecimal digits of
+/// the address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::reverse_dns;
+///
+/// const PTR: &str = reverse_dns!("192.168.1.5");
+/// assert_eq!(PTR, "5.1.168.192.in-addr.arpa");
+///
+/// const PTR6: &str = reverse_dns!("2001:db8::1");
+/// assert_eq!(
+///     PTR6,
+///     "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+/// );
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn reverse_dns(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    let name = reverse_dns_name(&ip);
+
+    diagnostics.record("reverse_dns", &literal, "&str", name.clone(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(tokens::literal(Literal::string(&name)).into())))
+}
+
+/// Generates the IPv4-mapped IPv6 address of an IPv4 address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 address. The `::ffff:a.b.c.d` address mapping it
+/// is generated as an `Ipv6Addr`, the checks being done on the IPv4 address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::to_ipv6_mapped;
+///
+/// const MAPPED: std::net::Ipv6Addr = to_ipv6_mapped!("192.168.1.5");
+/// assert_eq!(MAPPED, "::ffff:192.168.1.5".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn to_ipv6_mapped(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv4Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V4(ip), span);
+
+    let mapped = ip.to_ipv6_mapped();
+
+    diagnostics.record(
+        "to_ipv6_mapped",
+        &literal,
+        "Ipv6Addr",
+        mapped.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_ipv6_stream(&mapped))))
+}
+
+/// Generates an IPv4-embedded IPv6 address of a NAT64 prefix
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of an IPv6 network in CIDR notation, being the NAT64
+/// prefix, and the string representation of an IPv4 address. The address
+/// synthesized by embedding the IPv4 address into the prefix as defined by
+/// RFC 6052 is generated as an `Ipv6Addr`.
+///
+/// The prefix length must be 32, 40, 48, 56, 64 or 96 bits, and the prefix
+/// must have no bit set outside of its length nor in the bits 64 to 71 of the
+/// address, which are reserved. The checks are done on the IPv4 address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::nat64;
+///
+/// const SYNTHESIZED: std::net::Ipv6Addr = nat64!("64:ff9b::/96", "203.0.113.7");
+/// assert_eq!(SYNTHESIZED, "64:ff9b::cb00:7107".parse::<std::net::Ipv6Addr>().unwrap());
+///
+/// const SPLIT: std::net::Ipv6Addr = nat64!("2001:db8:100::/40", "192.0.2.33");
+/// assert_eq!(SPLIT, "2001:db8:1c0:2:21::".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn nat64(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (prefix_literal, prefix_span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(1, 2);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let prefix = match Network::parse(prefix_literal.as_str(), false) {
+        Some(v) if v.addr().is_ipv6() => v,
+        _ => report_invalid_network_error(
+            prefix_span,
+            &prefix_literal,
+            "IPv6 network",
+            parser.origin(),
+        ),
+    };
+
+    if !NAT64_PREFIX_LENS.contains(&prefix.prefix_len()) {
+        emit_error!(
+            prefix_span,
+            "The prefix length `{}` is not allowed for a NAT64 prefix",
+            prefix.prefix_len();
+            help = "Use one of the lengths defined by RFC 6052: 32, 40, 48, 56, 64 or 96"
+        );
+    } else if prefix.has_host_bits() {
+        emit_error!(
+            prefix_span,
+            "The NAT64 prefix `{}` has bits set outside of its length",
+            prefix;
+            help = "Use `{}` instead", prefix.truncated()
+        );
+    } else if matches!(prefix.addr(), IpAddr::V6(v) if v.octets()[8] != 0) {
+        emit_error!(
+            prefix_span,
+            "The NAT64 prefix `{}` has bits set in the bits 64 to 71 of the address, which are reserved by RFC 6052",
+            prefix
+        );
+    }
+
+    let ip = match Ipv4Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V4(ip), span);
+
+    let addr = nat64_address(&prefix, &ip);
+
+    diagnostics.record("nat64", &literal, "Ipv6Addr", addr.to_string(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_ipv6_stream(&addr))))
+}
+
+/// Generates the 6to4 address of an IPv4 address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 address. The address of the `2002::/16` 6to4
+/// prefix embedding it, as defined by RFC 3056, is generated as an
+/// `Ipv6Addr`.
+///
+/// The `subnet = <id>` option sets the 16-bit subnet ID following the
+/// `2002:V4ADDR::/48` prefix of the site, and the `interface_id = <id>` option
+/// sets the 64-bit interface ID. Both are zero by default. The checks are
+/// done on the IPv4 address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::sixtofour;
+///
+/// const SITE: std::net::Ipv6Addr = sixtofour!("203.0.113.7");
+/// assert_eq!(SITE, "2002:cb00:7107::".parse::<std::net::Ipv6Addr>().unwrap());
+///
+/// const HOST: std::net::Ipv6Addr = sixtofour!("203.0.113.7", subnet = 1, interface_id = 0x10);
+/// assert_eq!(HOST, "2002:cb00:7107:1::10".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn sixtofour(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut subnet = None;
+    let mut interface_id = None;
+    let options = parse_ip_options(&mut parser, |opt| {
+        let given = match opt.name() {
+            "subnet" => subnet.is_some(),
+            "interface_id" => interface_id.is_some(),
+            _ => return false,
+        };
+
+        if given {
+            emit_error!(opt.span(), "The `{}` value is given twice", opt.name());
+        } else if opt.name() == "subnet" {
+            subnet = emit_parse_error(opt.value_integer::<u16>());
+        } else {
+            interface_id = emit_parse_error(opt.value_integer::<u64>());
+        }
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv4Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv4 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V4(ip), span);
+
+    let addr = Ipv6Addr::from(
+        0x2002 << 112
+            | (u32::from(ip) as u128) << 80
+            | (subnet.unwrap_or(0) as u128) << 64
+            | interface_id.unwrap_or(0) as u128,
+    );
+
+    diagnostics.record("sixtofour", &literal, "Ipv6Addr", addr.to_string(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_ipv6_stream(&addr))))
+}
+
+/// Decodes a Teredo address into its server address, client address, port
+/// and flags
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv6 address of the `2001::/32` Teredo prefix. The
+/// fields it embeds, as defined by RFC 4380, are generated as an
+/// `(Ipv4Addr, Ipv4Addr, u16, u16)` tuple holding the address of the Teredo
+/// server, the external address and UDP port of the client, and the flags.
+/// The client address and port are stored obfuscated in the Teredo address,
+/// and are generated as they are seen on the network.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::teredo;
+///
+/// const TEREDO: (std::net::Ipv4Addr, std::net::Ipv4Addr, u16, u16) =
+///     teredo!("2001:0:4136:e378:8000:63bf:3fff:fdd2");
+///
+/// assert_eq!(TEREDO.0, std::net::Ipv4Addr::new(65, 54, 227, 120));
+/// assert_eq!(TEREDO.1, std::net::Ipv4Addr::new(192, 0, 2, 45));
+/// assert_eq!(TEREDO.2, 40000);
+/// assert_eq!(TEREDO.3, 0x8000);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn teredo(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv6Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv6 address", parser.origin()),
+    };
+
+    let segments = ip.segments();
+
+    if segments[..2] != [0x2001, 0] {
+        emit_error!(
+            span,
+            "The given address `{}` is not a Teredo address",
+            literal;
+            help = "Teredo addresses belong to the `2001::/32` prefix"
+        );
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V6(ip), span);
+
+    let bits = u128::from(ip);
+    let server = Ipv4Addr::from((bits >> 64) as u32);
+    let client = Ipv4Addr::from(!(bits as u32));
+    let port = !segments[5];
+    let flags = segments[4];
+
+    diagnostics.record(
+        "teredo",
+        &literal,
+        "(Ipv4Addr, Ipv4Addr, u16, u16)",
+        format!("({server}, {client}, {port}, {flags:#06x})"),
+        span,
+    );
+
+    let stream = tokens::group(
+        Delimiter::Parenthesis,
+        tokens::list([
+            generate_ipv4_stream(&server),
+            generate_ipv4_stream(&client),
+            tokens::literal(Literal::u16_suffixed(port)).into(),
+            tokens::hex_suffixed(flags as u128, 4, "u16").into(),
+        ]),
+    )
+    .into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates an RFC 4193 unique local IPv6 prefix
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of a MAC address and a 64-bit timestamp, given as an
+/// integer in the NTP format. The 40-bit global ID is derived from them with
+/// the algorithm of RFC 4193: it's the least significant 40 bits of the SHA-1
+/// digest of the timestamp followed by the modified EUI-64 of the MAC
+/// address. The `fd00::/8` prefix followed by the global ID is generated as
+/// an `(Ipv6Addr, u8)` tuple holding the `/48` prefix of the site.
+///
+/// The same arguments always give the same prefix, so any value can be used
+/// as a seed in place of the timestamp.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ula;
+///
+/// const SITE: (std::net::Ipv6Addr, u8) = ula!("00:11:22:33:44:55", 0xe7b2_1a4d_0000_0000);
+/// assert_eq!(SITE.0, "fd65:5d5b:4900::".parse::<std::net::Ipv6Addr>().unwrap());
+/// assert_eq!(SITE.1, 48);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ula(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let timestamp = match emit_parse_error(parser.next_integer::<u64>("timestamp")) {
+        Some(Some((v, _))) => v,
+        Some(None) => report_too_few_arguments_error(1, 2),
+        None => 0,
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let addr = match mac::parse(literal.as_str()) {
+        Some(v) => v,
+        None => {
+            abort!(
+                span,
+                "The given address `{}` is not a valid MAC address",
+                literal;
+                note =? parser.origin().map(|v| format!("The address is included from {v}"))
+            );
+        }
+    };
+
+    abort_if_dirty();
+
+    let mut key = timestamp.to_be_bytes().to_vec();
+    key.extend_from_slice(&mac::modified_eui64(&addr));
+
+    let digest = sha1::digest(&key);
+
+    let mut octets = [0u8; 16];
+    octets[0] = 0xfd;
+    octets[1..6].copy_from_slice(&digest[15..]);
+
+    let network = Network::new(IpAddr::V6(octets.into()), 48);
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record("ula", &literal, "(Ipv6Addr, u8)", network.to_string(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_net_tuple_stream(&network))))
+}
+
+/// Generates the IPv6 link-local address derived from a MAC address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of a MAC address. The `fe80::/64` address whose interface
+/// identifier is the modified EUI-64 of the MAC address, as defined by
+/// RFC 4291, is generated as an `Ipv6Addr`. The universal/local bit of the
+/// MAC address is inverted in the identifier.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::link_local_from_mac;
+///
+/// const LINK_LOCAL: std::net::Ipv6Addr = link_local_from_mac!("00:11:22:33:44:55");
+/// assert_eq!(LINK_LOCAL, "fe80::211:22ff:fe33:4455".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn link_local_from_mac(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let addr = match mac::parse(literal.as_str()) {
+        Some(v) => v,
+        None => {
+            abort!(
+                span,
+                "The given address `{}` is not a valid MAC address",
+                literal;
+                note =? parser.origin().map(|v| format!("The address is included from {v}"))
+            );
+        }
+    };
+
+    abort_if_dirty();
+
+    let mut octets = [0u8; 16];
+    octets[..2].copy_from_slice(&[0xfe, 0x80]);
+    octets[8..].copy_from_slice(&mac::modified_eui64(&addr));
+
+    let ip = Ipv6Addr::from(octets);
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V6(ip), span);
+
+    diagnostics.record(
+        "link_local_from_mac",
+        &literal,
+        "Ipv6Addr",
+        ip.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_ipv6_stream(&ip))))
+}
+
+/// Generates the solicited-node multicast address of an IPv6 address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv6 address. The `ff02::1:ff00:0/104` multicast
+/// address ending with the low-order 24 bits of this address, as defined by
+/// RFC 4291, is generated as an `Ipv6Addr`. The checks are done on the given
+/// address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::solicited_node;
+///
+/// const SOLICITED: std::net::Ipv6Addr = solicited_node!("fe80::1234:5678:9abc:def0");
+/// assert_eq!(SOLICITED, "ff02::1:ffbc:def0".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn solicited_node(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match Ipv6Addr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IPv6 address", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &IpAddr::V6(ip), span);
+
+    let addr =
+        Ipv6Addr::from(0xff02_0000_0000_0000_0000_0001_ff00_0000 | u128::from(ip) & 0xff_ffff);
+
+    diagnostics.record(
+        "solicited_node",
+        &literal,
+        "Ipv6Addr",
+        addr.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_ipv6_stream(&addr))))
+}
+
+/// Generates the Ethernet multicast MAC address of a multicast IP address
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 or IPv6 multicast address. The MAC address it is
+/// mapped to is generated as a `[u8; 6]`: `01:00:5e` followed by the
+/// low-order 23 bits of an IPv4 address, as defined by RFC 1112, or `33:33`
+/// followed by the low-order 32 bits of an IPv6 address, as defined by
+/// RFC 2464.
+///
+/// The `as = pnet` option generates a `pnet::util::MacAddr` instead (requires
+/// the `pnet` feature).
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::multicast_mac;
+///
+/// const MDNS: [u8; 6] = multicast_mac!("224.0.0.251");
+/// assert_eq!(MDNS, [0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb]);
+///
+/// const ALL_NODES: [u8; 6] = multicast_mac!("ff02::1");
+/// assert_eq!(ALL_NODES, [0x33, 0x33, 0x00, 0x00, 0x00, 0x01]);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn multicast_mac(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut target = MacTarget::Array;
+    let options = parse_ip_options(&mut parser, |opt| {
+        if opt.name() == "as" {
+            target = MacTarget::from_option(opt);
+
+            true
+        } else {
+            false
+        }
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let ip = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+    };
+
+    if !ip.is_multicast() {
+        emit_error!(
+            span,
+            "The given address `{}` is not a multicast address",
+            literal;
+            help = "Multicast addresses belong to the `224.0.0.0/4` and `ff00::/8` prefixes"
+        );
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &ip, span);
+
+    let addr = match ip {
+        IpAddr::V4(v) => {
+            let [_, b, c, d] = v.octets();
+            [0x01, 0x00, 0x5e, b & 0x7f, c, d]
+        }
+        IpAddr::V6(v) => {
+            let [.., c, d, e, f] = v.octets();
+            [0x33, 0x33, c, d, e, f]
+        }
+    };
+
+    diagnostics.record(
+        "multicast_mac",
+        &literal,
+        target.type_name(),
+        mac::to_string(&addr),
+        span,
+    );
+
+    let stream = match target {
+        MacTarget::Array => generate_mac_stream(&addr),
+        #[cfg(feature = "pnet")]
+        MacTarget::Pnet => generate_pnet_mac_stream(&addr),
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates a socket address from its string representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take the string representation of a
+/// socket address, or the string representation of an address followed by
+/// the port as an integer
+///
+/// The `as = nix` option generates a `nix::sys::socket::SockaddrIn` instead
+/// (requires the `nix` feature). As nix provides no const constructor for
+/// this type, the generated expression can't be used in const contexts.
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::IpEndpoint` instead
+/// (requires the `smoltcp` feature).
+///
+/// The `as = embedded_nal` option generates an `embedded_nal::SocketAddrV4`
+/// instead (requires the `embedded-nal` feature), for the versions of
+/// embedded-nal re-exporting the address types.
+///
+/// The `as = socket2` option generates a `socket2::SockAddr` instead
+/// (requires the `socket2` feature), converted from the `std::net` socket
+/// address. As this conversion isn't const, the generated expression can't be
+/// used in const contexts.
+///
+/// The `wrapped` option generates the socket address wrapped in a
+/// `SocketAddr`, while still guaranteeing it is an IPv4 socket address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::socketv4;
+///
+/// assert_eq!(socketv4!("192.168.1.5:3000"), std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 3000));
+/// assert_eq!(socketv4!("192.168.1.5:3000", wrapped), std::net::SocketAddr::V4(std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 3000)));
+/// assert_eq!(socketv4!("192.168.1.5", 8080), std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 8080));
+/// ```
+///
+/// ```ignore
+/// # use fancy_ip::socketv4;
+///
+/// let local: nix::sys::socket::SockaddrIn = socketv4!("127.0.0.1:3000", as = nix);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn socketv4(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut wrapped = None;
+    let (literal, span, socket, options) = next_socketv4(&mut parser, |parser| {
+        parse_socket_options(parser, |opt| parse_wrapped_option(opt, &mut wrapped))
+    });
+
+    if let Some(span) = wrapped {
+        check_wrapped_target(&options.target, span);
+    }
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V4(socket), span);
+
+    diagnostics.record(
+        "socketv4",
+        &literal,
+        options.target.type_name(
+            &SocketAddr::V4(socket),
+            if wrapped.is_some() {
+                "SocketAddr"
+            } else {
+                "SocketAddrV4"
+            },
+        ),
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(match options.target {
+            SocketTarget::Net if wrapped.is_some() => {
+                generate_ip_socket_stream(&SocketAddr::V4(socket))
+            }
+            SocketTarget::Net => generate_ipv4_socket_stream(&socket),
+            #[cfg(feature = "nix")]
+            SocketTarget::Nix => generate_nix_socket_stream(&SocketAddr::V4(socket)),
+            #[cfg(feature = "smoltcp")]
+            SocketTarget::Smoltcp => generate_smoltcp_endpoint_stream(&SocketAddr::V4(socket)),
+            #[cfg(feature = "embedded-nal")]
+            SocketTarget::EmbeddedNal if wrapped.is_some() => {
+                generate_embedded_nal_stream(generate_ip_socket_stream(&SocketAddr::V4(socket)))
+            }
+            #[cfg(feature = "embedded-nal")]
+            SocketTarget::EmbeddedNal => {
+                generate_embedded_nal_stream(generate_ipv4_socket_stream(&socket))
+            }
+            #[cfg(feature = "socket2")]
+            SocketTarget::Socket2 => generate_socket2_stream(generate_ipv4_socket_stream(&socket)),
+        })))
+}
+
+/// Generates a socket address from its string representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take the string representation of a
+/// socket address, or the string representation of an address followed by
+/// the port as an integer. Either can be followed by the flow information and
+/// the scope identifier of the socket address. These can also be given as the
+/// `flow_info = <value>` and `scope_id = <value>` options, which makes the call
+/// more readable and allows giving the scope identifier alone.
+///
+/// The `wrapped` option generates the socket address wrapped in a
+/// `SocketAddr`, while still guaranteeing it is an IPv6 socket address.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::socketv6;
+///
+/// assert_eq!(socketv6!("[::1]:3000"), std::net::SocketAddrV6::new(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 3000, 0, 0));
+/// assert_eq!(socketv6!("[::]:8080", 58, 30), std::net::SocketAddrV6::new(std::net::Ipv6Addr::UNSPECIFIED, 8080, 58, 30));
+/// assert_eq!(socketv6!("[::1]:3000", 0, 2, wrapped), std::net::SocketAddr::V6(std::net::SocketAddrV6::new(std::net::Ipv6Addr::LOCALHOST, 3000, 0, 2)));
+/// assert_eq!(socketv6!("[fe80::1]:80", scope_id = 3), std::net::SocketAddrV6::new(std::net::Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 80, 0, 3));
+/// assert_eq!(socketv6!("[::1]:80", flow_info = 0x12345).flowinfo(), 0x12345);
+/// assert_eq!(socketv6!("::1", 443), std::net::SocketAddrV6::new(std::net::Ipv6Addr::LOCALHOST, 443, 0, 0));
+/// ```
+///
+/// The `as = nix` option generates a `nix::sys::socket::SockaddrIn6` instead
+/// (requires the `nix` feature). As nix provides no const constructor for
+/// this type, the generated expression can't be used in const contexts.
+///
+/// ```ignore
+/// # use fancy_ip::socketv6;
+///
+/// let local: nix::sys::socket::SockaddrIn6 = socketv6!("[::1]:3000", as = nix);
+/// ```
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::IpEndpoint` instead
+/// (requires the `smoltcp` feature). As endpoints hold neither a flow
+/// information nor a scope id, both must be zero.
+///
+/// ```ignore
+/// # use fancy_ip::socketv6;
+///
+/// const SERVER: smoltcp::wire::IpEndpoint = socketv6!("[2001:db8::1]:80", as = smoltcp);
+/// ```
+///
+/// The `as = embedded_nal` option generates an `embedded_nal::SocketAddrV6`
+/// instead (requires the `embedded-nal` feature), for the versions of
+/// embedded-nal re-exporting the address types.
+///
+/// The `as = socket2` option generates a `socket2::SockAddr` instead
+/// (requires the `socket2` feature), converted from the `std::net` socket
+/// address. As this conversion isn't const, the generated expression can't be
+/// used in const contexts.
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn socketv6(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut wrapped = None;
+    let (literal, span, socket, options) = next_socketv6(&mut parser, |parser, fields| {
+        parse_socket_options(parser, |opt| {
+            fields(opt) || parse_wrapped_option(opt, &mut wrapped)
+        })
+    });
+
+    if let Some(span) = wrapped {
+        check_wrapped_target(&options.target, span);
+    }
+
+    check_endpoint_target(&options.target, &SocketAddr::V6(socket), span);
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V6(socket), span);
+
+    diagnostics.record(
+        "socketv6",
+        &literal,
+        options.target.type_name(
+            &SocketAddr::V6(socket),
+            if wrapped.is_some() {
+                "SocketAddr"
+            } else {
+                "SocketAddrV6"
+            },
+        ),
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(match options.target {
+            SocketTarget::Net if wrapped.is_some() => {
+                generate_ip_socket_stream(&SocketAddr::V6(socket))
+            }
+            SocketTarget::Net => generate_ipv6_socket_stream(&socket),
+            #[cfg(feature = "nix")]
+            SocketTarget::Nix => generate_nix_socket_stream(&SocketAddr::V6(socket)),
+            #[cfg(feature = "smoltcp")]
+            SocketTarget::Smoltcp => generate_smoltcp_endpoint_stream(&SocketAddr::V6(socket)),
+            #[cfg(feature = "embedded-nal")]
+            SocketTarget::EmbeddedNal if wrapped.is_some() => {
+                generate_embedded_nal_stream(generate_ip_socket_stream(&SocketAddr::V6(socket)))
+            }
+            #[cfg(feature = "embedded-nal")]
+            SocketTarget::EmbeddedNal => {
+                generate_embedded_nal_stream(generate_ipv6_socket_stream(&socket))
+            }
+            #[cfg(feature = "socket2")]
+            SocketTarget::Socket2 => generate_socket2_stream(generate_ipv6_socket_stream(&socket)),
+        })))
+}
+
+/// Generates a socket address from its string representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take the string representation of a
+/// socket address, or the string representation of an address followed by
+/// the port as an integer
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::socket;
+///
+/// assert_eq!(socket!("[::1]:3000"), std::net::SocketAddr::V6(std::net::SocketAddrV6::new(std::net::Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 3000, 0, 0)));
+/// assert_eq!(socket!("192.168.1.5:3000"), std::net::SocketAddr::V4(std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 168, 1, 5), 3000)));
+/// assert_eq!(socket!("10.0.0.1", 53), std::net::SocketAddr::V4(std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(10, 0, 0, 1), 53)));
+/// ```
+///
+/// The `as = nix` option generates either a `nix::sys::socket::SockaddrIn` or
+/// a `nix::sys::socket::SockaddrIn6` depending on the address family (requires
+/// the `nix` feature). The generated expression can't be used in const
+/// contexts.
+///
+/// The `as = smoltcp` option generates a `smoltcp::wire::IpEndpoint` instead
+/// (requires the `smoltcp` feature), the flow information and the scope id of
+/// IPv6 socket addresses being zero.
+///
+/// The `as = embedded_nal` option generates an `embedded_nal::SocketAddr`
+/// instead (requires the `embedded-nal` feature), for the versions of
+/// embedded-nal re-exporting the address types.
+///
+/// The `as = socket2` option generates a `socket2::SockAddr` instead
+/// (requires the `socket2` feature), converted from the `std::net` socket
+/// address. As this conversion isn't const, the generated expression can't be
+/// used in const contexts.
+///
+/// ```ignore
+/// # use fancy_ip::socket;
+///
+/// let server: socket2::SockAddr = socket!("1.2.3.4:80", as = socket2);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn socket(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let port = emit_parse_error(parser.next_integer("port")).flatten();
+
+    let options = parse_socket_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let socket = match port {
+        Some((port, _)) => match IpAddr::from_str(literal.as_str()) {
+            Ok(v) => SocketAddr::new(v, port),
+            Err(_) => report_invalid_address_error(span, &literal, "IP address", parser.origin()),
+        },
+        None => match SocketAddr::from_str(literal.as_str()) {
+            Ok(v) => v,
+            Err(_) => {
+                report_invalid_address_error(span, &literal, "socket address", parser.origin())
+            }
+        },
+    };
+
+    abort_if_dirty();
+
+    check_endpoint_target(&options.target, &socket, span);
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check_socket(&mut diagnostics, &socket, span);
+
+    diagnostics.record(
+        "socket",
+        &literal,
+        options.target.type_name(&socket, "SocketAddr"),
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(match options.target {
+            SocketTarget::Net => generate_ip_socket_stream(&socket),
+            #[cfg(feature = "nix")]
+            SocketTarget::Nix => generate_nix_socket_stream(&socket),
+            #[cfg(feature = "smoltcp")]
+            SocketTarget::Smoltcp => generate_smoltcp_endpoint_stream(&socket),
+            #[cfg(feature = "embedded-nal")]
+            SocketTarget::EmbeddedNal => {
+                generate_embedded_nal_stream(generate_ip_socket_stream(&socket))
+            }
+            #[cfg(feature = "socket2")]
+            SocketTarget::Socket2 => generate_socket2_stream(generate_ip_socket_stream(&socket)),
+        })))
+}
+
+/// Generates the pair of IPv4 and IPv6 socket addresses listening on the same
+/// port
+///
+/// # Syntax
+///
+/// This macro works as a function which take either the port as an integer,
+/// or the string representations of an IPv4 address and of an IPv6 address
+/// followed by the port. The pair is generated as a `[SocketAddr; 2]` array
+/// holding the IPv4 socket address then the IPv6 one, the addresses being the
+/// unspecified addresses `0.0.0.0` and `::` when only the port is given.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::dual_stack;
+/// use std::net::SocketAddr;
+///
+/// const LISTEN: [SocketAddr; 2] = dual_stack!(8080);
+/// assert_eq!(LISTEN, ["0.0.0.0:8080".parse().unwrap(), "[::]:8080".parse().unwrap()]);
+///
+/// let local = dual_stack!("127.0.0.1", "::1", 8080);
+/// assert_eq!(local[1], "[::1]:8080".parse::<SocketAddr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn dual_stack(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut addrs = Vec::new();
+
+    if parser.next_is_string() {
+        for (i, kind) in ["IPv4 address", "IPv6 address"].into_iter().enumerate() {
+            let Some((literal, span)) = report_error(parser.next_string()) else {
+                report_too_few_arguments_error(i, 3);
+            };
+
+            let addr = match IpAddr::from_str(literal.as_str()) {
+                Ok(v) if v.is_ipv4() == (i == 0) => Some(v),
+                Ok(_) => {
+                    emit_error!(
+                        span,
+                        "The address `{}` is not an {}",
+                        literal,
+                        kind;
+                        help = "Give the IPv4 address first, then the IPv6 address"
+                    );
+
+                    None
+                }
+                Err(_) => {
+                    emit_invalid_address_error(span, &literal, kind, parser.origin());
+
+                    None
+                }
+            };
+
+            addrs.push((addr, literal, span));
+        }
+    }
+
+    let Some((port, port_span)) = report_error(parser.next_integer::<u16>("port")) else {
+        report_too_few_arguments_error(addrs.len(), addrs.len() + 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), addrs.len() + 1);
+    }
+
+    abort_if_dirty();
+
+    let sockets: Vec<_> = match addrs.as_slice() {
+        [] => [
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        ]
+        .into_iter()
+        .map(|addr| (SocketAddr::new(addr, port), addr.to_string(), port_span))
+        .collect(),
+        _ => addrs
+            .into_iter()
+            .filter_map(|(addr, literal, span)| Some((SocketAddr::new(addr?, port), literal, span)))
+            .collect(),
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (socket, literal, span) in &sockets {
+        options.checks.check_socket(&mut diagnostics, socket, *span);
+
+        diagnostics.record(
+            "dual_stack",
+            literal,
+            "SocketAddr",
+            socket.to_string(),
+            *span,
+        );
+    }
+
+    let stream = tokens::group(
+        Delimiter::Bracket,
+        tokens::list(
+            sockets
+                .iter()
+                .map(|(socket, _, _)| generate_ip_socket_stream(socket)),
+        ),
+    )
+    .into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates a MAC address from its textual representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of a MAC address, with bytes separated by `:` or `-`. The
+/// address is generated as a `[u8; 6]` array.
+///
+/// The `as = pnet` option generates a `pnet::util::MacAddr` instead (requires
+/// the `pnet` feature).
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::mac;
+///
+/// assert_eq!(mac!("00:1b:63:84:45:e6"), [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6]);
+/// assert_eq!(mac!("00-1B-63-84-45-E6"), [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6]);
+/// ```
+///
+/// ```ignore
+/// # use fancy_ip::mac;
+///
+/// const GATEWAY: pnet::util::MacAddr = mac!("00:1b:63:84:45:e6", as = pnet);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn mac(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_mac_options(&mut parser);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let addr = match mac::parse(literal.as_str()) {
+        Some(v) => v,
+        None => {
+            abort!(
+                span,
+                "The given address `{}` is not a valid MAC address",
+                literal;
+                note =? parser.origin().map(|v| format!("The address is included from {v}"))
+            );
+        }
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record(
+        "mac",
+        &literal,
+        options.target.type_name(),
+        mac::to_string(&addr),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(match options.target {
+            MacTarget::Array => generate_mac_stream(&addr),
+            #[cfg(feature = "pnet")]
+            MacTarget::Pnet => generate_pnet_mac_stream(&addr),
+        })))
+}
+
+/// Generates a 64-bit extended unique identifier (EUI-64) from its textual
+/// representation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an EUI-64, with its eight bytes separated by `:` or `-`.
+/// The identifier is generated as a `[u8; 8]` array.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::eui64;
+///
+/// assert_eq!(eui64!("00-11-22-ff-fe-33-44-55"), [0x00, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55]);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn eui64(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let addr = match mac::parse_bytes::<8>(literal.as_str()) {
+        Some(v) => v,
+        None => {
+            abort!(
+                span,
+                "The given identifier `{}` is not a valid EUI-64, expected eight hexadecimal bytes separated by `:` or `-`",
+                literal;
+                note =? parser.origin().map(|v| format!("The identifier is included from {v}"))
+            );
+        }
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record("eui64", &literal, "[u8; 8]", mac::to_string(&addr), span);
+
+    emit.finish(parser.track(diagnostics.wrap(generate_mac_stream(&addr))))
+}
+
+/// Generates a `hickory_resolver::config::ResolverConfig` from the socket
+/// addresses of its name servers
+///
+/// # Syntax
+///
+/// This macro takes one or more string representations of socket addresses,
+/// followed by options:
+///
+/// - `trust_nx`: trust negative responses (`NXDOMAIN`) from the name servers;
+/// - `protocol = udp` or `protocol = tcp`: only register the name servers for
+///   the given protocol. By default, each name server is registered for both
+///   UDP and TCP, like the predefined hickory configurations.
+///
+/// This macro requires the `hickory` feature. As the configuration is built at
+/// runtime, the generated expression can't be used in const contexts.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::resolver_config;
+///
+/// let config = resolver_config!("9.9.9.9:53", "[2620:fe::fe]:53", trust_nx);
+/// let resolver = hickory_resolver::Resolver::new(config, Default::default()).unwrap();
+/// ```
+#[cfg(feature = "hickory")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn resolver_config(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let mut name_servers = Vec::new();
+    let mut given = 0;
+
+    while let Some((literal, span)) = report_error(parser.next_string()) {
+        given += 1;
+
+        match SocketAddr::from_str(literal.as_str()) {
+            Ok(v) => name_servers.push((v, literal, span)),
+            Err(_) => emit_invalid_address_error(span, &literal, "socket address", parser.origin()),
+        }
+    }
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    let mut protocols = ["Udp", "Tcp"].as_slice();
+    let mut trust_nx = false;
+    let mut checks = Checks::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        match opt.name() {
+            "trust_nx" => {
+                emit_parse_error(opt.flag());
+                trust_nx = true
+            }
+            "protocol" => {
+                protocols = match report_error(opt.value_ident()).as_str() {
+                    "udp" => ["Udp"].as_slice(),
+                    "tcp" => ["Tcp"].as_slice(),
+                    name => {
+                        abort!(opt.span(), "Unknown name server protocol `{}`", name);
+                    }
+                }
+            }
+            _ if checks.parse_option(&opt) => {}
+            _ => report_unknown_option_error(&opt),
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = checks.diagnostics();
+
+    for (socket, literal, span) in &name_servers {
+        checks.check_socket(&mut diagnostics, socket, *span);
+
+        diagnostics.record(
+            "resolver_config",
+            literal,
+            "NameServerConfig",
+            socket.to_string(),
+            *span,
+        );
+    }
+
+    let name_servers: Vec<_> = name_servers.into_iter().map(|(v, _, _)| v).collect();
+
+    parser.track(diagnostics.wrap(generate_resolver_config_stream(
+        &name_servers,
+        protocols,
+        trust_nx,
+    )))
+}
+
+/// Asserts that an address expression can be evaluated at compile time
+///
+/// # Syntax
+///
+/// This macro takes any expression and expands to an anonymous constant item
+/// (`const _: () = ...;`) evaluating it. As a consequence, the compilation
+/// fails if the expression can't be computed in const contexts. This macro
+/// must be used in item position.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::{const_ip, ipv4};
+///
+/// const_ip!(ipv4!("192.168.1.5").to_ipv6_mapped());
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::const_ip;
+///
+/// const_ip!("192.168.1.5".parse::<std::net::Ipv4Addr>().unwrap());
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn const_ip(item: TokenStream) -> TokenStream {
+    if item.is_empty() {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    let body = tokens::let_statement("_", item);
+
+    let mut ret: TokenStream = [
+        tokens::ident("const"),
+        tokens::ident("_"),
+        tokens::punct(':'),
+    ]
+    .into_iter()
+    .collect();
+    ret.extend([
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+        tokens::punct('='),
+        tokens::group(Delimiter::Brace, body),
+        tokens::punct(';'),
+    ]);
+
+    ret
+}
+
+/// Generates a `&'static [SocketAddr]` from the string representations of its
+/// socket addresses
+///
+/// # Syntax
+///
+/// This macro takes one or more string representations of socket addresses,
+/// which are kept in the given order. As `&[SocketAddr]` implements
+/// `ToSocketAddrs`, the generated slice can be given directly to connect
+/// functions, which try each address in turn.
+///
+/// # Example
+///
+/// ```no_run
+/// # use fancy_ip::to_socket_addrs_const;
+/// use std::net::{SocketAddr, TcpStream};
+///
+/// const FALLBACKS: &[SocketAddr] = to_socket_addrs_const!("[2001:db8::10]:443", "192.0.2.10:443");
+///
+/// let stream = TcpStream::connect(FALLBACKS).unwrap();
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn to_socket_addrs_const(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (sockets, given) = parse_address_list::<SocketAddr>(&mut parser, "socket address");
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (socket, literal, span) in &sockets {
+        options.checks.check_socket(&mut diagnostics, socket, *span);
+
+        diagnostics.record(
+            "to_socket_addrs_const",
+            literal,
+            "SocketAddr",
+            socket.to_string(),
+            *span,
+        );
+    }
+
+    let sockets: Vec<_> = sockets.into_iter().map(|(v, _, _)| v).collect();
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_socket_slice_stream(&sockets))))
+}
+
+/// Generates a `&'static [SocketAddr]` interleaving the address families, in
+/// the order expected by Happy Eyeballs connection attempts
+///
+/// # Syntax
+///
+/// This macro takes one or more string representations of socket addresses.
+/// As described by RFC 8305, the generated slice alternates between both
+/// families, starting with IPv6 addresses, while keeping the given order
+/// within each family. When a family runs out of addresses, the remaining
+/// addresses of the other one are appended.
+///
+/// The `prefer = ipv4` option starts with IPv4 addresses instead
+/// (`prefer = ipv6` being the default).
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::{happy_eyeballs, socket};
+/// use std::net::SocketAddr;
+///
+/// const ATTEMPTS: &[SocketAddr] = happy_eyeballs!(
+///     "192.0.2.10:443",
+///     "192.0.2.11:443",
+///     "[2001:db8::10]:443",
+/// );
+///
+/// assert_eq!(ATTEMPTS, [socket!("[2001:db8::10]:443"), socket!("192.0.2.10:443"), socket!("192.0.2.11:443")]);
+/// assert_eq!(
+///     happy_eyeballs!("[2001:db8::10]:443", "[2001:db8::11]:443", "192.0.2.10:443", prefer = ipv4),
+///     [socket!("192.0.2.10:443"), socket!("[2001:db8::10]:443"), socket!("[2001:db8::11]:443")]
+/// );
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn happy_eyeballs(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (sockets, given) = parse_address_list::<SocketAddr>(&mut parser, "socket address");
+
+    let mut prefer_v6 = true;
+    let options = parse_ip_options(&mut parser, |opt| {
+        if opt.name() != "prefer" {
+            return false;
+        }
+
+        match report_error(opt.value_ident()).as_str() {
+            "ipv4" => prefer_v6 = false,
+            "ipv6" => prefer_v6 = true,
+            name => emit_error!(
+                opt.span(),
+                "Unknown address family `{}`, expected `ipv4` or `ipv6`",
+                name
+            ),
+        }
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (socket, literal, span) in &sockets {
+        options.checks.check_socket(&mut diagnostics, socket, *span);
+
+        diagnostics.record(
+            "happy_eyeballs",
+            literal,
+            "SocketAddr",
+            socket.to_string(),
+            *span,
+        );
+    }
+
+    let (preferred, other): (Vec<_>, Vec<_>) = sockets
+        .into_iter()
+        .map(|(v, _, _)| v)
+        .partition(|v| v.is_ipv6() == prefer_v6);
+
+    let mut sockets = Vec::with_capacity(preferred.len() + other.len());
+    let mut preferred = preferred.into_iter();
+    let mut other = other.into_iter();
+
+    loop {
+        match (preferred.next(), other.next()) {
+            (None, None) => break,
+            (first, second) => sockets.extend(first.into_iter().chain(second)),
+        }
+    }
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_socket_slice_stream(&sockets))))
+}
+
+/// Generates an IPv4 network from its CIDR notation, as an address and a
+/// prefix length
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 network, as an address followed by the length of
+/// its prefix. The network is generated as an `(Ipv4Addr, u8)` tuple, which
+/// doesn't require any other crate. The address may have bits set outside of
+/// the prefix.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv4_net;
+///
+/// const PRIVATE: (std::net::Ipv4Addr, u8) = ipv4_net!("192.168.0.0/16");
+/// assert_eq!(PRIVATE, (std::net::Ipv4Addr::new(192, 168, 0, 0), 16));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv4_net(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) if v.addr().is_ipv4() => v,
+        _ => report_invalid_network_error(span, &literal, "IPv4 network", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record(
+        "ipv4_net",
+        &literal,
+        "(Ipv4Addr, u8)",
+        network.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_net_tuple_stream(&network))))
+}
+
+/// Generates an IPv6 network from its CIDR notation, as an address and a
+/// prefix length
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv6 network, as an address followed by the length of
+/// its prefix. The network is generated as an `(Ipv6Addr, u8)` tuple, which
+/// doesn't require any other crate. The address may have bits set outside of
+/// the prefix.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ipv6_net;
+///
+/// const ULA: (std::net::Ipv6Addr, u8) = ipv6_net!("fd00::/8");
+/// assert_eq!(ULA, (std::net::Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0), 8));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ipv6_net(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) if v.addr().is_ipv6() => v,
+        _ => report_invalid_network_error(span, &literal, "IPv6 network", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record(
+        "ipv6_net",
+        &literal,
+        "(Ipv6Addr, u8)",
+        network.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_net_tuple_stream(&network))))
+}
+
+/// Generates the address of a host of a network from its index
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of an IPv4 or IPv6 network in CIDR notation and the index
+/// of the host, which is its offset from the network address. The address of
+/// the host is generated as an `Ipv4Addr` or an `Ipv6Addr`.
+///
+/// The index must designate an address usable by a host: the network address
+/// and the broadcast address of IPv4 networks are excluded, except for the
+/// `/31` and `/32` IPv4 networks and the `/127` and `/128` IPv6 networks,
+/// where every address is usable.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::nth_host;
+///
+/// const GATEWAY: std::net::Ipv4Addr = nth_host!("10.0.0.0/24", 1);
+/// const DNS: std::net::Ipv4Addr = nth_host!("10.0.0.0/24", 5);
+/// assert_eq!(DNS, std::net::Ipv4Addr::new(10, 0, 0, 5));
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::nth_host;
+///
+/// const BROADCAST: std::net::Ipv4Addr = nth_host!("10.0.0.0/24", 255);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn nth_host(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let origin = parser
+        .origin()
+        .map(|v| format!("The network is included from {v}"));
+
+    let (index, index_span) = match report_error(parser.next_integer::<u64>("index")) {
+        Some(v) => v,
+        None => report_too_few_arguments_error(1, 2),
+    };
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    if network.has_host_bits() {
+        emit_host_bits_error(&network, span);
+    }
+
+    abort_if_dirty();
+
+    let Some(addr) = network.host(index as u128) else {
+        let (first, last) = network.host_bounds();
+        let offset = |addr: IpAddr| match (addr, network.addr()) {
+            (IpAddr::V4(addr), IpAddr::V4(net)) => (u32::from(addr) - u32::from(net)) as u128,
+            (IpAddr::V6(addr), IpAddr::V6(net)) => u128::from(addr) - u128::from(net),
+            _ => unreachable!(),
+        };
+
+        abort!(
+            index_span,
+            "The index {} doesn't designate a host of the network `{}`, expected an index between {} and {}",
+            index,
+            network,
+            offset(first),
+            offset(last);
+            note =? origin
+        );
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &addr, span);
+
+    let kind = match addr {
+        IpAddr::V4(_) => "Ipv4Addr",
+        IpAddr::V6(_) => "Ipv6Addr",
+    };
+
+    diagnostics.record("nth_host", &literal, kind, addr.to_string(), span);
+
+    let stream = match addr {
+        IpAddr::V4(v) => generate_ipv4_stream(&v),
+        IpAddr::V6(v) => generate_ipv6_stream(&v),
+    };
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the directed broadcast address of an IPv4 network
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 network in CIDR notation, whose address may be
+/// any address of the network. Its broadcast address, which is its last
+/// address, is generated as an `Ipv4Addr`. The `/31` and `/32` networks have
+/// no broadcast address and are rejected.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::broadcast;
+///
+/// const BROADCAST: std::net::Ipv4Addr = broadcast!("192.168.1.0/24");
+/// assert_eq!(BROADCAST, std::net::Ipv4Addr::new(192, 168, 1, 255));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn broadcast(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, network, options) = next_network(&mut parser, "IPv4 network");
+
+    if network.prefix_len() > 30 {
+        emit_error!(
+            span,
+            "The network `{}` has no broadcast address, as every address of the `/31` and `/32` networks is a host address",
+            network
+        );
+    }
+
+    abort_if_dirty();
+
+    finish_network_address(
+        &parser,
+        options,
+        "broadcast",
+        &literal,
+        span,
+        network.last_addr(),
+    )
+}
+
+/// Generates the network address of a network
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 or IPv6 network in CIDR notation, whose address
+/// may be any address of the network. The network address, which has no bit
+/// set outside of the prefix, is generated as an `Ipv4Addr` or an
+/// `Ipv6Addr`.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::network_address;
+///
+/// const NETWORK: std::net::Ipv4Addr = network_address!("192.168.1.77/24");
+/// assert_eq!(NETWORK, std::net::Ipv4Addr::new(192, 168, 1, 0));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn network_address(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, network, options) = next_network(&mut parser, "network");
+
+    abort_if_dirty();
+
+    finish_network_address(
+        &parser,
+        options,
+        "network_address",
+        &literal,
+        span,
+        network.truncated().addr(),
+    )
+}
+
+/// Generates the first address usable by a host of a network
+///
+/// # Syntax
+///
+/// This macro works as `network_address!`, but generates the first address
+/// usable by a host. It follows the network address, except for the `/31`
+/// and `/32` IPv4 networks and the `/127` and `/128` IPv6 networks, where
+/// every address is usable.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::first_host;
+///
+/// const FIRST: std::net::Ipv4Addr = first_host!("192.168.1.0/24");
+/// assert_eq!(FIRST, std::net::Ipv4Addr::new(192, 168, 1, 1));
+///
+/// const FIRST_V6: std::net::Ipv6Addr = first_host!("2001:db8::/64");
+/// assert_eq!(FIRST_V6, "2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn first_host(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, network, options) = next_network(&mut parser, "network");
+
+    abort_if_dirty();
+
+    finish_network_address(
+        &parser,
+        options,
+        "first_host",
+        &literal,
+        span,
+        network.host_bounds().0,
+    )
+}
+
+/// Generates the last address usable by a host of a network
+///
+/// # Syntax
+///
+/// This macro works as `network_address!`, but generates the last address
+/// usable by a host. It precedes the broadcast address for IPv4 networks, and
+/// is the last address of the network otherwise.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::last_host;
+///
+/// const LAST: std::net::Ipv4Addr = last_host!("192.168.1.0/24");
+/// assert_eq!(LAST, std::net::Ipv4Addr::new(192, 168, 1, 254));
+///
+/// const LAST_V6: std::net::Ipv6Addr = last_host!("2001:db8::/64");
+/// assert_eq!(LAST_V6, "2001:db8::ffff:ffff:ffff:ffff".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn last_host(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, network, options) = next_network(&mut parser, "network");
+
+    abort_if_dirty();
+
+    finish_network_address(
+        &parser,
+        options,
+        "last_host",
+        &literal,
+        span,
+        network.host_bounds().1,
+    )
+}
+
+/// Generates the number of addresses usable by the hosts of a network
+///
+/// # Syntax
+///
+/// This macro works as `network_address!`, but generates the number of
+/// addresses between `first_host!` and `last_host!` of the network, as an
+/// `u32` for an IPv4 network and as an `u128` for an IPv6 network.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::host_count;
+///
+/// const HOSTS: u32 = host_count!("10.0.0.0/20");
+/// assert_eq!(HOSTS, 4094);
+///
+/// const _: () = assert!(host_count!("10.0.0.0/23") >= 500);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn host_count(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (literal, span, network, options) = next_network(&mut parser, "network");
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    let count = network.host_count();
+    let (kind, stream) = match network.addr() {
+        IpAddr::V4(_) => ("u32", tokens::literal(Literal::u32_suffixed(count as u32))),
+        IpAddr::V6(_) => ("u128", tokens::literal(Literal::u128_suffixed(count))),
+    };
+
+    diagnostics.record("host_count", &literal, kind, count.to_string(), span);
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(stream.into())))
+}
+
+/// Generates the network mask of a prefix length
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the length
+/// of a prefix, as an integer. The network mask, whose prefix bits are set, is
+/// generated as an `Ipv4Addr`. The `family = ipv6` option generates the mask
+/// of an IPv6 prefix as an `Ipv6Addr` instead.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::netmask;
+///
+/// const MASK: std::net::Ipv4Addr = netmask!(24);
+/// assert_eq!(MASK, std::net::Ipv4Addr::new(255, 255, 255, 0));
+///
+/// const MASK_V6: std::net::Ipv6Addr = netmask!(64, family = ipv6);
+/// assert_eq!(MASK_V6, "ffff:ffff:ffff:ffff::".parse::<std::net::Ipv6Addr>().unwrap());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn netmask(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (len, span) = match report_error(parser.next_integer::<u8>("prefix length")) {
+        Some(v) => v,
+        None => report_too_few_arguments_error(0, 1),
+    };
+
+    let mut ipv6 = false;
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if opt.name() == "family" {
+            match report_error(opt.value_ident()).as_str() {
+                "ipv4" => ipv6 = false,
+                "ipv6" => ipv6 = true,
+                name => emit_error!(
+                    opt.span(),
+                    "Unknown address family `{}`, expected `ipv4` or `ipv6`",
+                    name
+                ),
+            }
+        } else if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let family = if ipv6 {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    };
+
+    let max = if ipv6 { 128 } else { 32 };
+
+    if len > max {
+        abort!(
+            span,
+            "The prefix length `{}` is out of bounds, expected a length between 0 and {}",
+            len,
+            max
+        );
+    }
+
+    abort_if_dirty();
+
+    let mask = Network::new(family, len).mask();
+    let (kind, addr, stream) = match family {
+        IpAddr::V4(_) => {
+            let addr = Ipv4Addr::from(mask as u32);
+            ("Ipv4Addr", addr.to_string(), generate_ipv4_stream(&addr))
+        }
+        IpAddr::V6(_) => {
+            let addr = Ipv6Addr::from(mask);
+            ("Ipv6Addr", addr.to_string(), generate_ipv6_stream(&addr))
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record("netmask", &len.to_string(), kind, addr, span);
+
+    emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the prefix length of a network mask
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of an IPv4 or IPv6 network mask, whose set bits must all
+/// precede the cleared ones. The length of the prefix, which is its number of
+/// set bits, is generated as an `u8`.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::prefix_of;
+///
+/// const LEN: u8 = prefix_of!("255.255.252.0");
+/// assert_eq!(LEN, 22);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn prefix_of(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let mask = match IpAddr::from_str(literal.as_str()) {
+        Ok(v) => v,
+        Err(_) => report_invalid_address_error(span, &literal, "network mask", parser.origin()),
+    };
+
+    // The bits of an IPv4 mask are left-aligned
+    let bits = match mask {
+        IpAddr::V4(v) => (u32::from(v) as u128) << 96,
+        IpAddr::V6(v) => u128::from(v),
+    };
+
+    let len = bits.leading_ones();
+
+    if bits.checked_shl(len).unwrap_or(0) != 0 {
+        abort!(
+            span,
+            "The given mask `{}` is not a valid network mask, as its set bits are not contiguous",
+            literal;
+            help = "Use `{}` for a prefix of {} bits", Network::new(mask, len as u8).truncated().addr(), len
+        );
+    }
+
+    abort_if_dirty();
+
+    let len = len as u8;
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record("prefix_of", &literal, "u8", len.to_string(), span);
+
+    emit.finish(parser.track(diagnostics.wrap(tokens::literal(Literal::u8_suffixed(len)).into())))
+}
+
+/// Generates the wildcard mask of a network, as used by access control lists
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: either the
+/// string representation of an IPv4 or IPv6 network in CIDR notation, or the
+/// length of an IPv4 prefix as an integer. The wildcard mask, which is the
+/// inverse of the network mask, is generated as an `Ipv4Addr` or an
+/// `Ipv6Addr`.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::wildcard_mask;
+///
+/// const WILDCARD: std::net::Ipv4Addr = wildcard_mask!("10.0.0.0/24");
+/// assert_eq!(WILDCARD, std::net::Ipv4Addr::new(0, 0, 0, 255));
+/// assert_eq!(wildcard_mask!(20), std::net::Ipv4Addr::new(0, 0, 15, 255));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn wildcard_mask(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span, network) = if parser.next_is_string() {
+        let (literal, span) = report_error(parser.next_string()).unwrap();
+
+        match Network::parse(literal.as_str(), false) {
+            Some(v) => (literal, span, v),
+            None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+        }
+    } else {
+        let (len, span) = match report_error(parser.next_integer::<u8>("prefix length")) {
+            Some(v) => v,
+            None => report_too_few_arguments_error(0, 1),
+        };
+
+        if len > 32 {
+            abort!(
+                span,
+                "The prefix length `{}` is out of bounds, expected a length between 0 and 32",
+                len
+            );
+        }
+
+        let network = Network::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), len);
+        (len.to_string(), span, network)
+    };
+
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let (kind, addr, stream) = match network.addr() {
+        IpAddr::V4(_) => {
+            let addr = Ipv4Addr::from(!(network.mask() as u32));
+            ("Ipv4Addr", addr.to_string(), generate_ipv4_stream(&addr))
+        }
+        IpAddr::V6(_) => {
+            let addr = Ipv6Addr::from(!network.mask());
+            ("Ipv6Addr", addr.to_string(), generate_ipv6_stream(&addr))
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record("wildcard_mask", &literal, kind, addr, span);
+
+    emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates an array holding the subnets of a network
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of a network in CIDR notation, followed by the length of
+/// the subnets as an integer. The network is split into the subnets of this
+/// length, generated in address order as an array of `(Ipv4Addr, u8)` or
+/// `(Ipv6Addr, u8)` tuples holding their address and prefix length. The
+/// checks are done on the address of the network.
+///
+/// The `as = ipnet`, `as = ipnetwork` and `as = cidr` options generate an
+/// array of the network types of these crates instead, as `cidr!` does. They
+/// require the feature of the selected output target.
+///
+/// To keep the binary small, the network may be split into at most 256
+/// subnets. The `max = <count>` option changes this limit.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::subnets;
+/// use std::net::Ipv4Addr;
+///
+/// const VLANS: [(Ipv4Addr, u8); 4] = subnets!("10.0.0.0/22", 24);
+/// assert_eq!(VLANS[1], (Ipv4Addr::new(10, 0, 1, 0), 24));
+/// assert_eq!(VLANS[3], (Ipv4Addr::new(10, 0, 3, 0), 24));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn subnets(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let (len, len_span) = match report_error(parser.next_integer::<u8>("prefix length")) {
+        Some(v) => v,
+        None => report_too_few_arguments_error(1, 2),
+    };
+
+    let mut target = NetArrayTarget::Tuple;
+    let mut max = None;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "as" => {
+            target = NetArrayTarget::from_option(opt);
+
+            true
+        }
+        "max" => {
+            if max.is_some() {
+                emit_error!(opt.span(), "The `max` value is given twice");
+            } else {
+                max = emit_parse_error(opt.value_integer::<u32>());
+            }
+
+            true
+        }
+        _ => false,
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    if network.has_host_bits() {
+        emit_host_bits_error(&network, span);
+    }
+
+    let max = max.unwrap_or(DEFAULT_RANGE_MAX);
+
+    if len < network.prefix_len() || len > network.width() {
+        emit_error!(
+            len_span,
+            "The prefix length `{}` is out of bounds, expected a length between {} and {}",
+            len,
+            network.prefix_len(),
+            network.width()
+        );
+    } else if (len - network.prefix_len()) as u32 >= u32::BITS
+        || 1u32 << (len - network.prefix_len()) > max
+    {
+        emit_error!(
+            len_span,
+            "Splitting `{}` into `/{}` subnets generates more than {} networks",
+            network,
+            len,
+            max;
+            help = "Raise the limit with the `max = <count>` option if this is intended"
+        );
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    let kind = format!("[{}; N]", target.type_name(&network.addr()));
+
+    diagnostics.record("subnets", &literal, &kind, network.to_string(), span);
+
+    let stream = generate_net_array_stream(&target, network.subnets(len));
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the aggregated array of a list of networks
+///
+/// # Syntax
+///
+/// This macro works as a function which take a variable number of arguments:
+/// the string representations of IPv4 or IPv6 networks in CIDR notation, all
+/// of the same address family. The networks are summarized into the smallest
+/// set of networks covering the same addresses, by dropping the networks
+/// contained in another one and merging the adjacent ones. This set is
+/// generated in address order as an array of `(Ipv4Addr, u8)` or
+/// `(Ipv6Addr, u8)` tuples, which allows to keep the route tables small.
+///
+/// The `as = ipnet`, `as = ipnetwork` and `as = cidr` options generate an
+/// array of the network types of these crates instead, as `cidr!` does. They
+/// require the feature of the selected output target.
+///
+/// The `deny_overlaps` option rejects the lists holding a network contained in
+/// another one, which is usually a mistake in an allowlist.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::aggregate;
+/// use std::net::Ipv4Addr;
+///
+/// const ROUTES: [(Ipv4Addr, u8); 2] = aggregate!(
+///     "10.0.0.0/24",
+///     "10.0.1.0/24",
+///     "10.0.1.128/25",
+///     "192.168.0.0/16",
+/// );
+/// assert_eq!(ROUTES[0], (Ipv4Addr::new(10, 0, 0, 0), 23));
+/// assert_eq!(ROUTES[1], (Ipv4Addr::new(192, 168, 0, 0), 16));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn aggregate(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (networks, given) = parse_network_list(&mut parser);
+
+    let mut target = NetArrayTarget::Tuple;
+    let mut deny_overlaps = false;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "as" => {
+            target = NetArrayTarget::from_option(opt);
+
+            true
+        }
+        _ => parse_overlaps_option(opt, &mut deny_overlaps),
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    for (network, _, span) in &networks {
+        if network.has_host_bits() {
+            emit_host_bits_error(network, *span);
+        }
+    }
+
+    if deny_overlaps {
+        emit_overlap_errors(&networks);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (network, literal, span) in &networks {
+        options
+            .checks
+            .check(&mut diagnostics, &network.addr(), *span);
+
+        diagnostics.record(
+            "aggregate",
+            literal,
+            target.type_name(&network.addr()),
+            network.to_string(),
+            *span,
+        );
+    }
+
+    let networks: Vec<_> = networks.iter().map(|(v, _, _)| *v).collect();
+    let stream = generate_net_array_stream(&target, Network::aggregate(&networks));
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates the subnets of a network allocated to a list of host counts
+///
+/// # Syntax
+///
+/// This macro works as a function which take two arguments: the string
+/// representation of a network in CIDR notation, followed by an array of host
+/// counts. Each count is given the smallest subnet holding this number of
+/// host addresses, as computed by `host_count!`: two hosts get a
+/// point-to-point `/31` subnet. The subnets are allocated from the start of
+/// the network, the largest ones first so that they stay aligned
+/// (variable-length subnet masking).
+///
+/// The subnets are generated in the order of the counts as an array of
+/// `(Ipv4Addr, u8)` or `(Ipv6Addr, u8)` tuples, and an error is reported when
+/// the network is too small to hold them. The checks are done on the address
+/// of the network.
+///
+/// The `as = ipnet`, `as = ipnetwork` and `as = cidr` options generate an
+/// array of the network types of these crates instead, as `cidr!` does. They
+/// require the feature of the selected output target.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::allocate;
+/// use std::net::Ipv4Addr;
+///
+/// const PLAN: [(Ipv4Addr, u8); 4] = allocate!("10.0.0.0/22", [500, 120, 60, 2]);
+/// assert_eq!(PLAN[0], (Ipv4Addr::new(10, 0, 0, 0), 23));
+/// assert_eq!(PLAN[1], (Ipv4Addr::new(10, 0, 2, 0), 25));
+/// assert_eq!(PLAN[2], (Ipv4Addr::new(10, 0, 2, 128), 26));
+/// assert_eq!(PLAN[3], (Ipv4Addr::new(10, 0, 2, 192), 31));
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn allocate(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 2);
+    };
+
+    let (counts, counts_span) = match report_error(parser.next_integer_array::<u64>("host count")) {
+        Some(v) => v,
+        None => report_too_few_arguments_error(1, 2),
+    };
+
+    let mut target = NetArrayTarget::Tuple;
+    let options = parse_ip_options(&mut parser, |opt| {
+        if opt.name() != "as" {
+            return false;
+        }
+
+        target = NetArrayTarget::from_option(opt);
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 2);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    if network.has_host_bits() {
+        emit_host_bits_error(&network, span);
+    }
+
+    if counts.is_empty() {
+        emit_error!(counts_span, "Expected at least one host count");
+    }
+
+    // The longest prefix holding each count, if the network can hold it
+    let lens: Vec<_> = counts
+        .iter()
+        .map(|&(count, count_span)| {
+            let len = (network.prefix_len()..=network.width())
+                .rev()
+                .find(|&len| Network::new(network.addr(), len).host_count() >= count as u128);
+
+            if count == 0 {
+                emit_error!(count_span, "A subnet must hold at least one host");
+            } else if len.is_none() {
+                emit_error!(
+                    count_span,
+                    "The network `{}` can't hold {} hosts, as it only has {} host addresses",
+                    network,
+                    count,
+                    network.host_count()
+                );
+            }
+
+            len.unwrap_or(network.prefix_len())
+        })
+        .collect();
+
+    abort_if_dirty();
+
+    let Some(subnets) = network.allocate(&lens) else {
+        // The subnets are aligned, so they fit in the shortest prefix
+        // holding their total size
+        let size = lens
+            .iter()
+            .map(|&len| {
+                1u128
+                    .checked_shl((network.width() - len) as u32)
+                    .unwrap_or(u128::MAX)
+            })
+            .fold(0u128, u128::saturating_add);
+        let needed = (network.width() as u32).checked_sub(u128::BITS - (size - 1).leading_zeros());
+
+        abort!(
+            counts_span,
+            "The network `{}` is too small to hold the subnets of these host counts",
+            network;
+            note =? needed.map(|len| format!("The subnets span {size} addresses, which requires a `/{len}` network"))
+        );
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record(
+        "allocate",
+        &literal,
+        &format!("[{}; N]", target.type_name(&network.addr())),
+        network.to_string(),
+        span,
+    );
+
+    let stream = generate_net_array_stream(&target, subnets);
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates a longest prefix match table along with its lookup function
+///
+/// # Syntax
+///
+/// This macro is used in item position, and takes a list of arms written as
+/// the ones of a match: the string representation of a network in CIDR
+/// notation, followed by `=>` and the value associated to the network. The
+/// networks are either IPv4 or IPv6 networks, and the address of each network
+/// must have no bit set outside of its prefix. The checks are done on the
+/// address of each network.
+///
+/// The macro generates a `LOOKUP_TABLE` constant holding the networks as
+/// `(u32, u32, T)` tuples of their address, their network mask and their
+/// value, sorted from the longest prefix to the shortest one, along with a
+/// `const fn lookup(addr: u32) -> T` function returning the value of the
+/// longest prefix holding the address. The IPv4 addresses are given as the
+/// `u32` of their octets in network order, as returned by
+/// `u32::from(Ipv4Addr)`, and the IPv6 addresses are given as an `u128`.
+///
+/// The `_ => <value>` arm gives the value returned when no network holds the
+/// address. Without it, the function returns an `Option<T>`.
+///
+/// The type of the values is taken from the first value given as a suffixed
+/// literal (`1u8`), or is given with the `type = <type>` option. The
+/// `name = <name>` option renames the function, the table being named after
+/// it, and the `pub` option makes both of them public.
+///
+/// The lookup does a linear scan of the table, which suits the small tables
+/// of the embedded routers and firewalls.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::lpm_table;
+/// use std::net::Ipv4Addr;
+///
+/// lpm_table! {
+///     "10.0.0.0/8" => 1u8,
+///     "10.1.0.0/16" => 2,
+///     _ => 0,
+/// }
+///
+/// const ROUTE: u8 = lookup(0x0a01_0203);
+/// assert_eq!(ROUTE, 2);
+/// assert_eq!(lookup(u32::from(Ipv4Addr::new(10, 2, 0, 1))), 1);
+/// assert_eq!(lookup(u32::from(Ipv4Addr::new(192, 168, 0, 1))), 0);
+/// assert_eq!(LOOKUP_TABLE.len(), 2);
+///
+/// lpm_table! {
+///     "2001:db8::/32" => "documentation",
+///     "2001:db8:1::/48" => "lab",
+///     type = &'static str,
+///     name = zone_of,
+/// }
+///
+/// assert_eq!(zone_of(0x2001_0db8_0001_0000_0000_0000_0000_0001), Some("lab"));
+/// assert_eq!(zone_of(1), None);
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn lpm_table(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut entries: Vec<(Network, String, Span, TokenStream)> = Vec::new();
+    let mut fallback = None;
+    let mut given = 0;
+
+    while let Some(arm) = report_error(parser.next_arm()) {
+        given += 1;
+
+        let Some(literal) = arm.key else {
+            if fallback.is_some() {
+                emit_error!(arm.span, "The `_` arm is given twice");
+            }
+
+            fallback = Some(arm.value);
+            continue;
+        };
+
+        let Some(network) = Network::parse(literal.as_str(), false) else {
+            emit_invalid_network_error(arm.span, &literal, "network", parser.origin());
+            continue;
+        };
+
+        if network.has_host_bits() {
+            emit_host_bits_error(&network, arm.span);
+        }
+
+        match entries.first() {
+            Some((first, _, _, _)) if first.addr().is_ipv4() != network.addr().is_ipv4() => {
+                emit_error!(
+                    arm.span,
+                    "The network `{}` is not of the address family of the first network",
+                    literal;
+                    help = "Use two distinct tables for the IPv4 and IPv6 networks"
+                );
+            }
+            _ => {
+                let same = entries
+                    .iter()
+                    .find(|(v, _, _, _)| v.truncated() == network.truncated());
+
+                if let Some((_, other, other_span, _)) = same {
+                    emit_error!(
+                        arm.span,
+                        "The network `{}` is the same as the network `{}` given before",
+                        literal,
+                        other
+                    );
+                    emit_error!(
+                        *other_span,
+                        "The network `{}` is given again as `{}`",
+                        other,
+                        literal
+                    );
+                }
+
+                entries.push((network, literal, arm.span, arm.value));
+            }
+        }
+    }
+
+    let mut name = None;
+    let mut ty = None;
+    let mut public = false;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "name" => {
+            if name.is_some() {
+                emit_error!(opt.span(), "The `name` value is given twice");
+            } else {
+                name = emit_parse_error(opt.value_ident());
+            }
+
+            true
+        }
+        "type" => {
+            if ty.is_some() {
+                emit_error!(opt.span(), "The `type` value is given twice");
+            } else {
+                ty = emit_parse_error(opt.value_type());
+            }
+
+            true
+        }
+        "pub" => {
+            emit_parse_error(opt.flag());
+            public = true;
+
+            true
+        }
+        _ => false,
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    abort_if_dirty();
+
+    let Some((first, _, first_span, _)) = entries.first() else {
+        abort!(
+            Span::call_site(),
+            "Expected at least one network besides the `_` arm"
+        );
+    };
+
+    let ty = ty.or_else(|| {
+        entries
+            .iter()
+            .map(|(_, _, _, value)| value)
+            .chain(&fallback)
+            .find_map(literal_type)
+    });
+
+    let Some(ty) = ty else {
+        abort!(
+            *first_span,
+            "The type of the values can't be inferred from their literals";
+            help = "Give a type suffix to a value, or give the type with the `type = <type>` option"
+        );
+    };
+
+    let (int, digits) = match first.addr() {
+        IpAddr::V4(_) => ("u32", 8),
+        IpAddr::V6(_) => ("u128", 32),
+    };
+
+    let name = name.unwrap_or_else(|| "lookup".to_owned());
+    let table_name = format!("{}_TABLE", name.to_uppercase());
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (network, literal, span, _) in &entries {
+        options
+            .checks
+            .check(&mut diagnostics, &network.addr(), *span);
+
+        diagnostics.record("lpm_table", literal, int, network.to_string(), *span);
+    }
+
+    // The first network holding an address is then its longest prefix
+    entries.sort_by_key(|(network, _, _, _)| std::cmp::Reverse(network.prefix_len()));
+
+    let rows = entries.iter().map(|(network, _, _, value)| {
+        let bits = match network.addr() {
+            IpAddr::V4(addr) => u32::from(addr) as u128,
+            IpAddr::V6(addr) => u128::from(addr),
+        };
+
+        let mut row = TokenStream::new();
+        row.extend([
+            tokens::hex_suffixed(bits, digits, ""),
+            tokens::punct(','),
+            tokens::hex_suffixed(network.mask(), digits, ""),
+            tokens::punct(','),
+        ]);
+        row.extend(value.clone());
+
+        tokens::group(Delimiter::Parenthesis, row).into()
+    });
+
+    let table = tokens::group(Delimiter::Bracket, tokens::list(rows)).into();
+    let table = options.emit.finish(parser.track(diagnostics.wrap(table)));
+
+    let visibility = || public.then(|| tokens::ident("pub"));
+
+    let mut row_type = tokens::list([tokens::path(int), tokens::path(int), ty.clone()]);
+    row_type = tokens::group(Delimiter::Parenthesis, row_type).into();
+    row_type.extend([
+        tokens::punct(';'),
+        tokens::literal(Literal::usize_unsuffixed(entries.len())),
+    ]);
+
+    let mut ret: TokenStream = visibility().into_iter().collect();
+    ret.extend([
+        tokens::ident("const"),
+        tokens::ident(&table_name),
+        tokens::punct(':'),
+        tokens::group(Delimiter::Bracket, row_type),
+        tokens::punct('='),
+    ]);
+    ret.extend(table);
+    ret.extend([tokens::punct(';')]);
+
+    let (missing, return_type) = match &fallback {
+        Some(value) => (value.clone(), ty),
+        None => {
+            let mut return_type = tokens::path("::core::option::Option");
+            return_type.extend([tokens::punct('<')]);
+            return_type.extend(ty);
+            return_type.extend([tokens::punct('>')]);
+
+            (tokens::path("::core::option::Option::None"), return_type)
+        }
+    };
+
+    let mut body = generate_prefix_scan_stream(&table_name, |value| match fallback {
+        Some(_) => value,
+        None => tokens::call("::core::option::Option::Some", [value]),
+    });
+    body.extend(missing);
+
+    let mut param = tokens::path("addr");
+    param.extend([tokens::punct(':')]);
+    param.extend(tokens::path(int));
+
+    ret.extend(visibility());
+    ret.extend([
+        tokens::ident("const"),
+        tokens::ident("fn"),
+        tokens::ident(&name),
+        tokens::group(Delimiter::Parenthesis, param),
+    ]);
+    ret.extend(tokens::op("->"));
+    ret.extend(return_type);
+    ret.extend([tokens::group(Delimiter::Brace, body)]);
+
+    ret
+}
+
+/// Generates the scan of the `table` array of `(address, mask, value)` tuples
+/// returning `found(<value>)` for the first network holding `addr`
+fn generate_prefix_scan_stream(
+    table: &str,
+    found: impl FnOnce(TokenStream) -> TokenStream,
+) -> TokenStream {
+    // `<table>[i].<field>`
+    let field = |field: usize| -> TokenStream {
+        [
+            tokens::ident(table),
+            tokens::group(Delimiter::Bracket, tokens::path("i")),
+            tokens::punct('.'),
+            tokens::literal(Literal::usize_unsuffixed(field)),
+        ]
+        .into_iter()
+        .collect()
+    };
+
+    let mut test = tokens::path("addr");
+    test.extend([tokens::punct('&')]);
+    test.extend(field(1));
+    test.extend(tokens::op("=="));
+    test.extend(field(0));
+
+    let mut found_body: TokenStream = [tokens::ident("return")].into_iter().collect();
+    found_body.extend(found(field(2)));
+    found_body.extend([tokens::punct(';')]);
+
+    let mut loop_body: TokenStream = [tokens::ident("if")].into_iter().collect();
+    loop_body.extend(test);
+    loop_body.extend([tokens::group(Delimiter::Brace, found_body)]);
+    loop_body.extend([tokens::ident("i")]);
+    loop_body.extend(tokens::op("+="));
+    loop_body.extend([
+        tokens::literal(Literal::usize_unsuffixed(1)),
+        tokens::punct(';'),
+    ]);
+
+    let mut body = tokens::let_statement(
+        "mut i",
+        [tokens::literal(Literal::usize_unsuffixed(0))]
+            .into_iter()
+            .collect(),
+    );
+    body.extend([
+        tokens::ident("while"),
+        tokens::ident("i"),
+        tokens::punct('<'),
+    ]);
+    body.extend([tokens::ident(table), tokens::punct('.')]);
+    body.extend(tokens::call("len", []));
+    body.extend([tokens::group(Delimiter::Brace, loop_body)]);
+
+    body
+}
+
+/// Generates the binary search of `addr` in the sorted array of disjoint
+/// ranges `table`, evaluating to whether a range holds it
+fn generate_range_search_stream(table: &str) -> TokenStream {
+    // `<table>[mid].<field>`
+    let field = |field: usize| -> TokenStream {
+        [
+            tokens::ident(table),
+            tokens::group(Delimiter::Bracket, tokens::path("mid")),
+            tokens::punct('.'),
+            tokens::literal(Literal::usize_unsuffixed(field)),
+        ]
+        .into_iter()
+        .collect()
+    };
+
+    let assign = |name: &str, value: TokenStream| -> TokenTree {
+        let mut ret = tokens::path(name);
+        ret.extend([tokens::punct('=')]);
+        ret.extend(value);
+        ret.extend([tokens::punct(';')]);
+
+        tokens::group(Delimiter::Brace, ret)
+    };
+
+    let mut mid = tokens::path("low");
+    mid.extend([tokens::punct('+')]);
+    mid.extend([tokens::group(Delimiter::Parenthesis, {
+        let mut width = tokens::path("high");
+        width.extend([tokens::punct('-')]);
+        width.extend(tokens::path("low"));
+        width
+    })]);
+    mid.extend([
+        tokens::punct('/'),
+        tokens::literal(Literal::usize_unsuffixed(2)),
+    ]);
+
+    let mut next_low = tokens::path("mid");
+    next_low.extend([
+        tokens::punct('+'),
+        tokens::literal(Literal::usize_unsuffixed(1)),
+    ]);
+
+    let mut loop_body = tokens::let_statement("mid", mid);
+    loop_body.extend([
+        tokens::ident("if"),
+        tokens::ident("addr"),
+        tokens::punct('<'),
+    ]);
+    loop_body.extend(field(0));
+    loop_body.extend([
+        assign("high", tokens::path("mid")),
+        tokens::ident("else"),
+        tokens::ident("if"),
+        tokens::ident("addr"),
+        tokens::punct('>'),
+    ]);
+    loop_body.extend(field(1));
+    loop_body.extend([
+        assign("low", next_low),
+        tokens::ident("else"),
+        tokens::group(
+            Delimiter::Brace,
+            [
+                tokens::ident("return"),
+                tokens::ident("true"),
+                tokens::punct(';'),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    ]);
+
+    let mut len = tokens::path(table);
+    len.extend([tokens::punct('.')]);
+    len.extend(tokens::call("len", []));
+
+    let mut ret = tokens::let_statement(
+        "mut low",
+        [tokens::literal(Literal::usize_unsuffixed(0))]
+            .into_iter()
+            .collect(),
+    );
+    ret.extend(tokens::let_statement("mut high", len));
+    ret.extend([
+        tokens::ident("while"),
+        tokens::ident("low"),
+        tokens::punct('<'),
+        tokens::ident("high"),
+        tokens::group(Delimiter::Brace, loop_body),
+        tokens::ident("false"),
+    ]);
+
+    tokens::group(Delimiter::Brace, ret).into()
+}
+
+/// Maximum number of distinct `cfg` predicates given to the entries of
+/// `ip_set!`, which generates the set of each combination of them
+const MAX_SET_PREDICATES: usize = 4;
+
+/// Returns the distinct `cfg` predicates given to the entries of `ip_set!`
+fn set_predicates(attributes: &[Vec<Attribute>]) -> Vec<TokenStream> {
+    let mut ret: Vec<TokenStream> = Vec::new();
+
+    for attribute in attributes.iter().flatten().filter(|v| v.name == "cfg") {
+        let predicate = attribute.arguments.to_string();
+
+        if ret.iter().any(|v| v.to_string() == predicate) {
+            continue;
+        }
+
+        if ret.len() == MAX_SET_PREDICATES {
+            abort!(
+                attribute.span,
+                "The entries of a set can't be given more than {} distinct `cfg` predicates",
+                MAX_SET_PREDICATES;
+                help = "Split the set into several sets, or combine the predicates"
+            );
+        }
+
+        ret.push(attribute.arguments.clone());
+    }
+
+    ret
+}
+
+/// Generates the tables and the membership test of `ip_set!` holding the
+/// given networks, each item being preceded by the `cfg` attribute of the
+/// configuration
+///
+/// The diagnostics and the tracked files are attached to the first table.
+fn generate_ip_set_stream(
+    networks: &[&Network],
+    name: &str,
+    public: bool,
+    cfg: &TokenStream,
+    emit: &Emit,
+    parser: &ArgParser,
+    diagnostics: Diagnostics,
+) -> TokenStream {
+    let mut diagnostics = Some(diagnostics);
+    let visibility = || {
+        let mut ret = cfg.clone();
+        ret.extend(public.then(|| tokens::ident("pub")));
+
+        ret
+    };
+
+    let mut ret = TokenStream::new();
+    let mut arms = Vec::new();
+
+    for (family, int, digits) in [("V4", "u32", 8), ("V6", "u128", 32)] {
+        let mut ranges: Vec<_> = networks
+            .iter()
+            .filter(|network| network.addr().is_ipv4() == (family == "V4"))
+            .map(|network| network.bounds())
+            .collect();
+        ranges.sort_unstable();
+
+        // Merges the overlapping and adjacent ranges
+        let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+
+        for (first, last) in ranges {
+            match merged.last_mut() {
+                Some((_, prev)) if prev.checked_add(1).is_none_or(|v| first <= v) => {
+                    *prev = (*prev).max(last);
+                }
+                _ => merged.push((first, last)),
+            }
+        }
+
+        let table_name = format!("{}_{family}_TABLE", name.to_uppercase());
+
+        let rows = merged.iter().map(|(first, last)| {
+            tokens::group(
+                Delimiter::Parenthesis,
+                tokens::list([
+                    [tokens::hex_suffixed(*first, digits, "")]
+                        .into_iter()
+                        .collect(),
+                    [tokens::hex_suffixed(*last, digits, "")]
+                        .into_iter()
+                        .collect(),
+                ]),
+            )
+            .into()
+        });
+
+        let table = tokens::group(Delimiter::Bracket, tokens::list(rows)).into();
+        // The warnings and the tracked files are attached to the first table
+        let table = match diagnostics.take() {
+            Some(diagnostics) => emit.finish(parser.track(diagnostics.wrap(table))),
+            None => emit.finish(table),
+        };
+
+        let mut row_type: TokenStream = tokens::group(
+            Delimiter::Parenthesis,
+            tokens::list([tokens::path(int), tokens::path(int)]),
+        )
+        .into();
+        row_type.extend([
+            tokens::punct(';'),
+            tokens::literal(Literal::usize_unsuffixed(merged.len())),
+        ]);
+
+        ret.extend(visibility());
+        ret.extend([
+            tokens::ident("const"),
+            tokens::ident(&table_name),
+            tokens::punct(':'),
+            tokens::group(Delimiter::Bracket, row_type),
+            tokens::punct('='),
+        ]);
+        ret.extend(table);
+        ret.extend([tokens::punct(';')]);
+
+        let mut octets = tokens::path("addr");
+        octets.extend([tokens::punct('.')]);
+        octets.extend(tokens::call("octets", []));
+
+        let mut body = tokens::let_statement(
+            "addr",
+            tokens::call(&format!("{int}::from_be_bytes"), [octets]),
+        );
+        body.extend(generate_range_search_stream(&table_name));
+
+        let mut arm = emit.type_path(&format!("IpAddr::{family}"));
+        arm.extend([tokens::group(Delimiter::Parenthesis, tokens::path("addr"))]);
+        arm.extend(tokens::op("=>"));
+        arm.extend([tokens::group(Delimiter::Brace, body)]);
+        arms.push(arm);
+    }
+
+    let mut param = tokens::path("addr");
+    param.extend([tokens::punct(':')]);
+    param.extend(emit.type_path("IpAddr"));
+
+    let mut body: TokenStream = [tokens::ident("match"), tokens::ident("addr")]
+        .into_iter()
+        .collect();
+    body.extend([tokens::group(Delimiter::Brace, tokens::list(arms))]);
+
+    ret.extend(visibility());
+    ret.extend([
+        tokens::ident("const"),
+        tokens::ident("fn"),
+        tokens::ident(name),
+        tokens::group(Delimiter::Parenthesis, param),
+    ]);
+    ret.extend(tokens::op("->"));
+    ret.extend([tokens::ident("bool")]);
+    ret.extend([tokens::group(Delimiter::Brace, body)]);
+
+    ret
+}
+
+/// Generates a set of addresses along with its membership test
+///
+/// # Syntax
+///
+/// This macro is used in item position, and takes a variable number of
+/// arguments: the string representations of IPv4 or IPv6 networks in CIDR
+/// notation, or of single addresses. The address of each network must have no
+/// bit set outside of its prefix. The checks are done on the address of each
+/// network.
+///
+/// The macro generates a `const fn contains(addr: IpAddr) -> bool` function
+/// checking whether the set holds the address. The networks are merged into
+/// disjoint ranges of addresses, stored as `(first, last)` tuples in sorted
+/// arrays: the `CONTAINS_V4_TABLE` array of `(u32, u32)` for IPv4 and the
+/// `CONTAINS_V6_TABLE` array of `(u128, u128)` for IPv6, which the function
+/// searches by dichotomy.
+///
+/// The `name = <name>` option renames the function, the tables being named
+/// after it, and the `pub` option makes both the function and the tables
+/// public. The `deny_overlaps` option rejects the lists holding a network
+/// contained in another one, as this is usually a mistake in an allowlist.
+///
+/// The networks accept the attributes of the entries of `ips!`. As the tables
+/// are merged, the set is generated for each combination of the `cfg`
+/// predicates given to the networks, each one under the matching `cfg`
+/// attribute. The networks can be given at most 4 distinct predicates.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_set;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// ip_set!["10.0.0.0/8", "192.168.1.1", "2001:db8::/32", name = is_allowed];
+///
+/// const ALLOWED: bool = is_allowed(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)));
+/// assert!(ALLOWED);
+/// assert!(is_allowed("192.168.1.1".parse().unwrap()));
+/// assert!(!is_allowed("192.168.1.2".parse().unwrap()));
+/// assert!(is_allowed("2001:db8::1".parse().unwrap()));
+/// assert_eq!(IS_ALLOWED_V4_TABLE.len(), 2);
+///
+/// ip_set!["10.0.0.0/8", #[cfg(debug_assertions)] "127.0.0.0/8", name = is_trusted];
+///
+/// assert_eq!(is_trusted("127.0.0.1".parse().unwrap()), cfg!(debug_assertions));
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn ip_set(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut networks = Vec::new();
+    let mut entry_attributes = Vec::new();
+    let mut given = 0;
+
+    while let Some((attributes, literal, span)) = next_attributed_string(&mut parser) {
+        given += 1;
+
+        let Some(network) = Network::parse(literal.as_str(), true) else {
+            emit_invalid_network_error(span, &literal, "network", parser.origin());
+            continue;
+        };
+
+        if network.has_host_bits() {
+            emit_host_bits_error(&network, span);
+        }
+
+        networks.push((network, literal, span));
+        entry_attributes.push(attributes);
+    }
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    let mut name = None;
+    let mut public = false;
+    let mut deny_overlaps = false;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "name" => {
+            if name.is_some() {
+                emit_error!(opt.span(), "The `name` value is given twice");
+            } else {
+                name = emit_parse_error(opt.value_ident());
+            }
+
+            true
+        }
+        "pub" => {
+            emit_parse_error(opt.flag());
+            public = true;
+
+            true
+        }
+        _ => parse_overlaps_option(opt, &mut deny_overlaps),
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    if deny_overlaps {
+        emit_overlap_errors(&networks);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for ((network, literal, span), attributes) in networks.iter().zip(&entry_attributes) {
+        diagnostics.set_attributes(attribute_stream(attributes));
+        options
+            .checks
+            .check(&mut diagnostics, &network.addr(), *span);
+
+        diagnostics.record("ip_set", literal, "IpAddr", network.to_string(), *span);
+    }
+
+    // The diagnostics are attached to each configuration, but their entries
+    // are only written once
+    abort_if_dirty();
+    inventory::write(&diagnostics.take_inventory());
+
+    let name = name.unwrap_or_else(|| "contains".to_owned());
+    let predicates = set_predicates(&entry_attributes);
+    let mut ret = TokenStream::new();
+
+    for configuration in 0..1usize << predicates.len() {
+        let enabled = |predicate: &TokenStream| {
+            let i = predicates
+                .iter()
+                .position(|v| v.to_string() == predicate.to_string())
+                .unwrap_or_default();
+
+            configuration & (1 << i) != 0
+        };
+
+        let included: Vec<&Network> = networks
+            .iter()
+            .zip(&entry_attributes)
+            .filter(|(_, attributes)| {
+                attributes
+                    .iter()
+                    .filter(|v| v.name == "cfg")
+                    .all(|v| enabled(&v.arguments))
+            })
+            .map(|((network, _, _), _)| network)
+            .collect();
+
+        let cfg = if predicates.is_empty() {
+            TokenStream::new()
+        } else {
+            let all = predicates.iter().map(|predicate| {
+                if enabled(predicate) {
+                    predicate.clone()
+                } else {
+                    tokens::call("not", [predicate.clone()])
+                }
+            });
+
+            let mut cfg: TokenStream = tokens::ident("cfg").into();
+            cfg.extend([tokens::group(
+                Delimiter::Parenthesis,
+                tokens::call("all", all.collect::<Vec<_>>()),
+            )]);
+
+            [tokens::punct('#'), tokens::group(Delimiter::Bracket, cfg)]
+                .into_iter()
+                .collect()
+        };
+
+        ret.extend(generate_ip_set_stream(
+            &included,
+            &name,
+            public,
+            &cfg,
+            &options.emit,
+            &parser,
+            diagnostics.clone(),
+        ));
+    }
+
+    ret
+}
+
+/// Generates a perfect hash map whose keys are addresses
+///
+/// # Syntax
+///
+/// This macro takes a list of arms written as the ones of a match: the string
+/// representation of an IPv4 or IPv6 address, followed by `=>` and the value
+/// associated to the address. The addresses must be of the same family. The
+/// map is generated as a `phf::Map<u32, V>` whose keys are the IPv4 addresses
+/// as the `u32` of their octets in network order, as returned by
+/// `u32::from(Ipv4Addr)`, or as a `phf::Map<u128, V>` whose keys are the
+/// IPv6 addresses as an `u128`.
+///
+/// Only the addresses are parsed and validated: the values are passed through
+/// verbatim, so that they can be any expression usable in the context of the
+/// map, such as enum variants, function pointers or struct expressions.
+///
+/// The perfect hash function is computed at compile time, so that looking up
+/// an address takes a constant time without building the map at runtime. The
+/// generated expression relies on the layout of the maps of phf 0.11 to 0.13.
+///
+/// This macro requires the `phf` feature.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_map;
+/// use std::net::Ipv4Addr;
+///
+/// static HOSTS: phf::Map<u32, &str> = ip_map! {
+///     "10.0.0.1" => "core-router",
+///     "10.0.0.2" => "edge",
+/// };
+///
+/// assert_eq!(HOSTS.get(&u32::from(Ipv4Addr::new(10, 0, 0, 2))), Some(&"edge"));
+/// assert_eq!(HOSTS.get(&0x0a00_0003), None);
+/// ```
+///
+/// With enum variants and function pointers as values:
+///
+/// ```
+/// # use fancy_ip::ip_map;
+/// use std::net::Ipv6Addr;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Role {
+///     Resolver,
+///     Gateway { priority: u8 },
+/// }
+///
+/// fn affine<const FACTOR: u32, const OFFSET: u32>(value: u32) -> u32 {
+///     value * FACTOR + OFFSET
+/// }
+///
+/// static ROLES: phf::Map<u128, Role> = ip_map! {
+///     "2001:db8::53" => Role::Resolver,
+///     "2001:db8::1" => Role::Gateway { priority: 10 },
+/// };
+///
+/// static HANDLERS: phf::Map<u128, fn(u32) -> u32> = ip_map! {
+///     "2001:db8::1" => affine::<2, 1>,
+///     "2001:db8::2" => u32::swap_bytes,
+/// };
+///
+/// let gateway = u128::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+/// assert_eq!(ROLES.get(&gateway), Some(&Role::Gateway { priority: 10 }));
+/// assert_eq!(HANDLERS[&gateway](3), 7);
+/// ```
+#[cfg(feature = "phf")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_map(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let mut entries: Vec<(IpAddr, String, Span, TokenStream)> = Vec::new();
+    let mut given = 0;
+
+    while let Some(arm) = report_error(parser.next_arm()) {
+        given += 1;
+
+        let Some(literal) = arm.key else {
+            emit_error!(
+                arm.span,
+                "A map has no `_` arm, as looking up a missing address returns `None`"
+            );
+            continue;
+        };
+
+        let Ok(addr) = IpAddr::from_str(literal.as_str()) else {
+            emit_invalid_address_error(arm.span, &literal, "IP address", parser.origin());
+            continue;
+        };
+
+        if let Some((first, _, _, _)) = entries.first() {
+            if first.is_ipv4() != addr.is_ipv4() {
+                emit_error!(
+                    arm.span,
+                    "The address `{}` is not of the address family of the first address",
+                    literal;
+                    help = "Use two distinct maps for the IPv4 and IPv6 addresses"
+                );
+                continue;
+            }
+        }
+
+        if let Some((_, other, other_span, _)) = entries.iter().find(|(v, _, _, _)| *v == addr) {
+            emit_error!(
+                arm.span,
+                "The address `{}` is the same as the address `{}` given before",
+                literal,
+                other
+            );
+            emit_error!(
+                *other_span,
+                "The address `{}` is given again as `{}`",
+                other,
+                literal
+            );
+        }
+
+        entries.push((addr, literal, arm.span, arm.value));
+    }
+
+    let options = parse_ip_options(&mut parser, |_| false);
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    if given == 0 {
+        report_too_few_arguments_error(0, 1);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (addr, literal, span, _) in &entries {
+        options.checks.check(&mut diagnostics, addr, *span);
+
+        diagnostics.record(
+            "ip_map",
+            literal,
+            match addr {
+                IpAddr::V4(_) => "Map<u32, V>",
+                IpAddr::V6(_) => "Map<u128, V>",
+            },
+            addr.to_string(),
+            *span,
+        );
+    }
+
+    // The keys are hashed as the little-endian bytes of the integers
+    let keys: Vec<_> = entries
+        .iter()
+        .map(|(addr, _, _, _)| match addr {
+            IpAddr::V4(addr) => u32::from(*addr).to_le_bytes().to_vec(),
+            IpAddr::V6(addr) => u128::from(*addr).to_le_bytes().to_vec(),
+        })
+        .collect();
+
+    let Some(state) = phf::generate(&keys) else {
+        abort!(
+            Span::call_site(),
+            "No perfect hash function was found for the {} addresses of the map",
+            keys.len();
+            help = "Split the addresses into several maps"
+        );
+    };
+
+    let disps = state.disps.iter().map(|(d1, d2)| {
+        tokens::group(
+            Delimiter::Parenthesis,
+            tokens::list([
+                tokens::literal(Literal::u32_unsuffixed(*d1)).into(),
+                tokens::literal(Literal::u32_unsuffixed(*d2)).into(),
+            ]),
+        )
+        .into()
+    });
+
+    let rows = state.map.iter().map(|&i| {
+        let mut row: TokenStream = [match entries[i].0 {
+            IpAddr::V4(addr) => tokens::hex_suffixed(u32::from(addr) as u128, 8, "u32"),
+            IpAddr::V6(addr) => tokens::hex_suffixed(u128::from(addr), 32, "u128"),
+        }]
+        .into_iter()
+        .collect();
+        row.extend([tokens::punct(',')]);
+        row.extend(entries[i].3.clone());
+
+        tokens::group(Delimiter::Parenthesis, row).into()
+    });
+
+    let slice = |items: TokenStream| -> TokenStream {
+        [tokens::punct('&'), tokens::group(Delimiter::Bracket, items)]
+            .into_iter()
+            .collect()
+    };
+
+    let field = |name: &str, value: TokenStream| -> TokenStream {
+        let mut ret: TokenStream = [tokens::ident(name), tokens::punct(':')]
+            .into_iter()
+            .collect();
+        ret.extend(value);
+
+        ret
+    };
+
+    let mut stream = tokens::path("phf::Map");
+    stream.extend([tokens::group(
+        Delimiter::Brace,
+        tokens::list([
+            field(
+                "key",
+                [tokens::hex_suffixed(state.key as u128, 16, "u64")]
+                    .into_iter()
+                    .collect(),
+            ),
+            field("disps", slice(tokens::list(disps))),
+            field("entries", slice(tokens::list(rows))),
+        ]),
+    )]);
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates a network from its CIDR notation
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the string
+/// representation of a network, as an address followed by the length of its
+/// prefix (`10.0.0.0/24`). The network is generated as an `ipnet::Ipv4Net` or
+/// an `ipnet::Ipv6Net` depending on the address family. Like `ipnet`, the
+/// address may have bits set outside of the prefix.
+///
+/// The `wrapped` option generates the network wrapped in an `ipnet::IpNet`.
+///
+/// The `as = ipnetwork` option generates an `ipnetwork::Ipv4Network` or an
+/// `ipnetwork::Ipv6Network` instead, wrapped in an `ipnetwork::IpNetwork` with
+/// the `wrapped` option. The generated expression relies on the const
+/// constructors of ipnetwork 0.21.
+///
+/// The `as = cidr` option generates a `cidr::Ipv4Cidr` or a `cidr::Ipv6Cidr`
+/// instead, wrapped in a `cidr::IpCidr` with the `wrapped` option or in a
+/// `cidr::AnyIpCidr` with the `any` option. As the cidr crate requires it, the
+/// address must have no bit set outside of the prefix.
+///
+/// This macro requires the `ipnet` feature, or the feature of the selected
+/// output target.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::cidr;
+///
+/// const PRIVATE: ipnet::Ipv4Net = cidr!("10.0.0.0/8");
+/// const DOCUMENTATION: ipnet::IpNet = cidr!("2001:db8::/32", wrapped);
+/// const LOOPBACK: ipnetwork::Ipv4Network = cidr!("127.0.0.0/8", as = ipnetwork);
+/// const LINK_LOCAL: cidr::AnyIpCidr = cidr!("fe80::/10", as = cidr, any);
+/// ```
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn cidr(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut wrapped = None;
+    let mut any = None;
+    let mut target = None;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "as" => {
+            target = Some(NetTarget::from_option(opt));
+
+            true
+        }
+        "any" => {
+            emit_parse_error(opt.flag());
+            any = Some(opt.span());
+
+            true
+        }
+        _ => parse_wrapped_option(opt, &mut wrapped),
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let network = match Network::parse(literal.as_str(), false) {
+        Some(v) => v,
+        None => report_invalid_network_error(span, &literal, "network", parser.origin()),
+    };
+
+    abort_if_dirty();
+
+    let target = target.unwrap_or_else(|| NetTarget::default_target(span));
+
+    if let Some(any_span) = any {
+        check_any_cidr_option(&target, any_span, wrapped);
+    }
+
+    if target.requires_network_address() && network.has_host_bits() {
+        report_host_bits_error(&network, span);
+    }
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check(&mut diagnostics, &network.addr(), span);
+
+    diagnostics.record(
+        "cidr",
+        &literal,
+        if any.is_some() {
+            "AnyIpCidr"
+        } else {
+            target.type_name(&network.addr(), wrapped.is_some())
+        },
+        network.to_string(),
+        span,
+    );
+
+    let (mut stream, wrapper) = match target {
+        #[cfg(feature = "ipnet")]
+        NetTarget::Ipnet => (generate_ipnet_stream(&network), "ipnet::IpNet"),
+        #[cfg(feature = "ipnetwork")]
+        NetTarget::Ipnetwork => (generate_ipnetwork_stream(&network), "ipnetwork::IpNetwork"),
+        #[cfg(feature = "cidr")]
+        NetTarget::Cidr if any.is_some() => (generate_cidr_stream(&network), "cidr::AnyIpCidr"),
+        #[cfg(feature = "cidr")]
+        NetTarget::Cidr => (generate_cidr_stream(&network), "cidr::IpCidr"),
+    };
+
+    if wrapped.is_some() || any.is_some() {
+        stream = tokens::call(
+            &match network.addr() {
+                IpAddr::V4(_) => format!("{wrapper}::V4"),
+                IpAddr::V6(_) => format!("{wrapper}::V6"),
+            },
+            [stream],
+        );
+    }
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates a `libc::sockaddr_in` from the string representation of an IPv4
+/// socket address
+///
+/// # Syntax
+///
+/// This macro takes the same arguments as `socketv4!`: the string
+/// representation of a socket address, or the string representation of an
+/// address followed by the port as an integer. The generated block fills the
+/// family, the port and the address in network byte order, the other fields
+/// of the structure being zeroed. It can be used in const contexts since Rust
+/// 1.75.
+///
+/// As the structure is zeroed with `core::mem::zeroed`, the generated block
+/// contains an `unsafe` block.
+///
+/// This macro requires the `libc` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::sockaddr_in;
+///
+/// const SERVER: libc::sockaddr_in = sockaddr_in!("10.0.0.1:9000");
+/// assert_eq!(SERVER.sin_port, 9000u16.to_be());
+/// assert_eq!(SERVER.sin_addr.s_addr, u32::from_ne_bytes([10, 0, 0, 1]));
+/// ```
+#[cfg(feature = "libc")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn sockaddr_in(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span, socket, options) =
+        next_socketv4(&mut parser, |parser| parse_ip_options(parser, |_| false));
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V4(socket), span);
+
+    diagnostics.record(
+        "sockaddr_in",
+        &literal,
+        "sockaddr_in",
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_libc_ipv4_socket_stream(&socket))))
+}
+
+/// Generates a `libc::sockaddr_in6` from the string representation of an IPv6
+/// socket address
+///
+/// # Syntax
+///
+/// This macro takes the same arguments as `socketv6!`, including the
+/// `flow_info = <value>` and `scope_id = <value>` options. The generated block
+/// fills the family, the port in network byte order, the flow information, the
+/// address and the scope id, the other fields of the structure being zeroed.
+/// Like the standard library, the flow information is kept in the host byte
+/// order. It can be used in const contexts since Rust 1.75.
+///
+/// As the structure is zeroed with `core::mem::zeroed`, the generated block
+/// contains an `unsafe` block.
+///
+/// This macro requires the `libc` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::sockaddr_in6;
+///
+/// const SERVER: libc::sockaddr_in6 = sockaddr_in6!("[fe80::1]:9000", scope_id = 2);
+/// assert_eq!(SERVER.sin6_port, 9000u16.to_be());
+/// assert_eq!(SERVER.sin6_scope_id, 2);
+/// ```
+#[cfg(feature = "libc")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn sockaddr_in6(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span, socket, options) = next_socketv6(&mut parser, |parser, fields| {
+        parse_ip_options(parser, fields)
+    });
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V6(socket), span);
+
+    diagnostics.record(
+        "sockaddr_in6",
+        &literal,
+        "sockaddr_in6",
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_libc_ipv6_socket_stream(&socket))))
+}
+
+/// Generates a `windows_sys::Win32::Networking::WinSock::SOCKADDR_IN` from the
+/// string representation of an IPv4 socket address
+///
+/// # Syntax
+///
+/// This macro takes the same arguments as `socketv4!`. The generated
+/// structure holds the port and the address in network byte order, and can be
+/// used in const contexts.
+///
+/// This macro requires the `windows` feature, the crate using it depending on
+/// windows-sys with the `Win32_Networking_WinSock` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::winsock_sockaddr_in;
+/// use windows_sys::Win32::Networking::WinSock::SOCKADDR_IN;
+///
+/// const SERVER: SOCKADDR_IN = winsock_sockaddr_in!("10.0.0.1:9000");
+/// assert_eq!(SERVER.sin_port, 9000u16.to_be());
+/// ```
+#[cfg(feature = "windows")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn winsock_sockaddr_in(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span, socket, options) =
+        next_socketv4(&mut parser, |parser| parse_ip_options(parser, |_| false));
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V4(socket), span);
+
+    diagnostics.record(
+        "winsock_sockaddr_in",
+        &literal,
+        "SOCKADDR_IN",
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_winsock_ipv4_socket_stream(&socket))))
+}
+
+/// Generates a `windows_sys::Win32::Networking::WinSock::SOCKADDR_IN6` from
+/// the string representation of an IPv6 socket address
+///
+/// # Syntax
+///
+/// This macro takes the same arguments as `socketv6!`, including the
+/// `flow_info = <value>` and `scope_id = <value>` options. The generated
+/// structure holds the port in network byte order, and can be used in const
+/// contexts. Like the standard library, the flow information is kept in the
+/// host byte order.
+///
+/// This macro requires the `windows` feature, the crate using it depending on
+/// windows-sys with the `Win32_Networking_WinSock` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::winsock_sockaddr_in6;
+/// use windows_sys::Win32::Networking::WinSock::SOCKADDR_IN6;
+///
+/// const SERVER: SOCKADDR_IN6 = winsock_sockaddr_in6!("[fe80::1]:9000", scope_id = 2);
+/// assert_eq!(SERVER.sin6_port, 9000u16.to_be());
+/// ```
+#[cfg(feature = "windows")]
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn winsock_sockaddr_in6(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span, socket, options) = next_socketv6(&mut parser, |parser, fields| {
+        parse_ip_options(parser, fields)
+    });
+
+    let mut diagnostics = options.checks.diagnostics();
+    options
+        .checks
+        .check_socket(&mut diagnostics, &SocketAddr::V6(socket), span);
+
+    diagnostics.record(
+        "winsock_sockaddr_in6",
+        &literal,
+        "SOCKADDR_IN6",
+        socket.to_string(),
+        span,
+    );
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(generate_winsock_ipv6_socket_stream(&socket))))
+}
+
+/// Generates a port number, validated at compile time
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the port,
+/// either as an integer or as its string representation. The port is
+/// generated as a `u16`.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::port;
+///
+/// const HTTP: u16 = port!(80);
+/// assert_eq!(port!("8080"), 8080u16);
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::port;
+///
+/// const INVALID: u16 = port!("70000");
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn port(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (port, span) = next_port(&mut parser);
+
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record("port", &port.to_string(), "u16", port.to_string(), span);
+
+    emit.finish(parser.track(diagnostics.wrap(tokens::literal(Literal::u16_suffixed(port)).into())))
+}
+
+/// Generates a non-zero port number, validated at compile time
+///
+/// # Syntax
+///
+/// This macro works as `port!`, but generates a `core::num::NonZeroU16` and
+/// fails the compilation when the port is 0.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::nonzero_port;
+/// use core::num::NonZeroU16;
+///
+/// const HTTPS: NonZeroU16 = nonzero_port!(443);
+/// assert_eq!(nonzero_port!("8443").get(), 8443);
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::nonzero_port;
+///
+/// const ANY: core::num::NonZeroU16 = nonzero_port!(0);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn nonzero_port(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (port, span) = next_port(&mut parser);
+
+    if port == 0 {
+        emit_error!(span, "The port can't be 0");
+    }
+
+    let mut emit = Emit::default();
+
+    while let Some(opt) = next_option(&mut parser) {
+        if !emit.parse_option(&opt) {
+            report_unknown_option_error(&opt);
+        }
+    }
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = Diagnostics::new(false);
+    diagnostics.record(
+        "nonzero_port",
+        &port.to_string(),
+        "NonZeroU16",
+        port.to_string(),
+        span,
+    );
+
+    emit.finish(parser.track(diagnostics.wrap(generate_nonzero_stream(
+        "core::num::NonZeroU16",
+        tokens::literal(Literal::u16_unsuffixed(port)).into(),
+    ))))
+}
+
+/// Generates an array of IP addresses from their string representations
+///
+/// # Syntax
+///
+/// This macro takes one or more string representations of IP addresses,
+/// which are kept in the given order, and generates an `[IpAddr; N]` array.
+/// Each address is checked on its own, the errors pointing at the faulty
+/// elements.
+///
+/// Each address can be preceded by `#[cfg(...)]` attributes, which are given
+/// to its element so that one list serves several build configurations, and
+/// by lint attributes such as `#[allow(deprecated)]`, which apply to the
+/// warnings of its checks. As the length of the array then depends on the
+/// configuration, such lists are usually stored as slices. The addresses are
+/// checked whatever the configuration.
+///
+/// The addresses can be grouped as `<name>: [<addresses>]`, so that large
+/// inventories keep their logical structure in the source. The groups are
+/// flattened in place into the generated array, the attributes of a group
+/// applying to each of its addresses. Groups can be nested, and two groups
+/// of the same list can't have the same name. They only hold addresses: a
+/// network such as `"10.1.0.0/24"` is rejected, the networks being listed by
+/// `ip_set!` or `aggregate!` instead, and no constant is generated per group.
+///
+/// The `sort = <order>` option gives the order of the generated addresses:
+/// `source` keeps the given order, which is the default, `ascending` sorts
+/// them in ascending order, the IPv4 addresses first, `family` moves the
+/// IPv4 addresses before the IPv6 ones, and `rfc6724` sorts them in the
+/// destination address order of RFC 6724, using its default policy table.
+/// The sorts are stable, so the generated array only depends on the given
+/// addresses, whatever the platform or the compilation.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::{ip, ips};
+/// use std::net::IpAddr;
+///
+/// const ALLOWED: [IpAddr; 3] = ips!["10.0.0.1", "::1", "192.168.1.7"];
+/// assert_eq!(ALLOWED[1], ip!("::1"));
+///
+/// const RESOLVERS: &[IpAddr] = &ips![
+///     "10.0.0.53",
+///     #[cfg(debug_assertions)]
+///     "127.0.0.53",
+///     #[allow(deprecated)]
+///     "240.0.0.53",
+/// ];
+/// assert_eq!(RESOLVERS.len(), if cfg!(debug_assertions) { 3 } else { 2 });
+///
+/// const ROUTERS: [IpAddr; 4] = ips! {
+///     core: ["10.0.0.1", "10.0.0.2"],
+///     edge: ["10.1.0.1", "10.1.0.2"],
+/// };
+/// assert_eq!(ROUTERS[2], ip!("10.1.0.1"));
+///
+/// const PREFERRED: [IpAddr; 3] = ips!["192.0.2.1", "2001:db8::1", "::1", sort = rfc6724];
+/// assert_eq!(PREFERRED, [ip!("::1"), ip!("2001:db8::1"), ip!("192.0.2.1")]);
+/// ```
+///
+/// The groups don't accept networks:
+///
+/// ```compile_fail
+/// # use fancy_ip::ips;
+/// const ROUTERS: [std::net::IpAddr; 2] = ips! {
+///     core: ["10.0.0.1"],
+///     edge: ["10.1.0.0/24"],
+/// };
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ips(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (mut addrs, given) =
+        parse_attributed_address_list::<IpAddr>(&mut parser, "IP address", true);
+
+    let mut sort = None;
+    let options = parse_ip_options(&mut parser, |opt| parse_sort_option(opt, &mut sort));
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (attributes, addr, literal, span) in &addrs {
+        diagnostics.set_attributes(attribute_stream(attributes));
+        options.checks.check(&mut diagnostics, addr, *span);
+
+        diagnostics.record("ips", literal, "IpAddr", addr.to_string(), *span);
+    }
+
+    sort.unwrap_or_default()
+        .apply(&mut addrs, |(_, addr, _, _)| *addr);
+
+    let stream = tokens::group(
+        Delimiter::Bracket,
+        tokens::list(addrs.iter().map(|(attributes, v, _, _)| {
+            let mut ret = attribute_stream(attributes);
+            ret.extend(generate_ip_stream(v));
+
+            ret
+        })),
+    )
+    .into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Default maximum number of elements generated by `ip_range!` and
+/// `subnets!`
+const DEFAULT_RANGE_MAX: u32 = 256;
+
+/// Generates an array holding every address of a range
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: a range of
+/// IPv4 or IPv6 addresses, written as the string representations of its first
+/// and last addresses separated by `-`. The addresses of the range, bounds
+/// included, are generated as an `[Ipv4Addr; N]` or an `[Ipv6Addr; N]`
+/// array. The checks are done on the bounds of the range.
+///
+/// To keep the binary small, the range may hold at most 256 addresses. The
+/// `max = <count>` option changes this limit.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_range;
+/// use std::net::Ipv4Addr;
+///
+/// const POOL: [Ipv4Addr; 16] = ip_range!("10.0.0.1-10.0.0.16");
+/// assert_eq!(POOL[15], Ipv4Addr::new(10, 0, 0, 16));
+///
+/// const LARGE_POOL: [Ipv4Addr; 512] = ip_range!("10.0.0.0-10.0.1.255", max = 512);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_range(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (literal, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut max = None;
+    let options = parse_ip_options(&mut parser, |opt| {
+        if opt.name() != "max" {
+            return false;
+        }
+
+        if max.is_some() {
+            emit_error!(opt.span(), "The `max` value is given twice");
+        } else {
+            max = emit_parse_error(opt.value_integer::<u32>());
+        }
+
+        true
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let bounds = literal.split_once('-').and_then(|(first, last)| {
+        match (
+            IpAddr::from_str(first.trim()),
+            IpAddr::from_str(last.trim()),
+        ) {
+            (Ok(first @ IpAddr::V4(_)), Ok(last @ IpAddr::V4(_)))
+            | (Ok(first @ IpAddr::V6(_)), Ok(last @ IpAddr::V6(_))) => Some((first, last)),
+            _ => None,
+        }
+    });
+
+    let Some((first, last)) = bounds else {
+        abort!(
+            span,
+            "The given range `{}` is not a valid IP address range, expected `<first>-<last>` with addresses of the same family",
+            literal;
+            note =? parser.origin().map(|v| format!("The range is included from {v}"))
+        );
+    };
+
+    let bits = |addr: &IpAddr| match addr {
+        IpAddr::V4(addr) => u32::from(*addr) as u128,
+        IpAddr::V6(addr) => u128::from(*addr),
+    };
+
+    let max = max.unwrap_or(DEFAULT_RANGE_MAX);
+
+    if bits(&last) < bits(&first) {
+        emit_error!(
+            span,
+            "The range `{}` is empty: its last address is lower than its first one",
+            literal
+        );
+    } else if bits(&last) - bits(&first) >= max as u128 {
+        emit_error!(
+            span,
+            "The range `{}` holds more than {} addresses",
+            literal,
+            max;
+            help = "Raise the limit with the `max = <count>` option if this is intended"
+        );
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+    options.checks.check(&mut diagnostics, &first, span);
+    options.checks.check(&mut diagnostics, &last, span);
+
+    let addrs = (bits(&first)..=bits(&last)).map(|v| match first {
+        IpAddr::V4(_) => generate_ipv4_stream(&Ipv4Addr::from(v as u32)),
+        IpAddr::V6(_) => generate_ipv6_stream(&Ipv6Addr::from(v)),
+    });
+
+    let kind = match first {
+        IpAddr::V4(_) => "[Ipv4Addr; N]",
+        IpAddr::V6(_) => "[Ipv6Addr; N]",
+    };
+
+    diagnostics.record("ip_range", &literal, kind, format!("{first}-{last}"), span);
+
+    let stream = tokens::group(Delimiter::Bracket, tokens::list(addrs)).into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Expands the socket address array macros, `T` being the type of their
+/// elements
+fn socket_array<T: FromStr>(
+    item: TokenStream,
+    name: &'static str,
+    kind: &str,
+    type_name: &'static str,
+    to_socket: fn(&T) -> SocketAddr,
+    generate: fn(&T) -> TokenStream,
+) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+    let (mut sockets, given) = parse_attributed_address_list::<T>(&mut parser, kind, true);
+
+    let mut sort = None;
+    let options = parse_ip_options(&mut parser, |opt| parse_sort_option(opt, &mut sort));
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), given);
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (attributes, socket, literal, span) in &sockets {
+        let socket = to_socket(socket);
+        diagnostics.set_attributes(attribute_stream(attributes));
+        options
+            .checks
+            .check_socket(&mut diagnostics, &socket, *span);
+
+        diagnostics.record(name, literal, type_name, socket.to_string(), *span);
+    }
+
+    sort.unwrap_or_default()
+        .apply(&mut sockets, |(_, socket, _, _)| to_socket(socket));
+
+    let stream = tokens::group(
+        Delimiter::Bracket,
+        tokens::list(sockets.iter().map(|(attributes, v, _, _)| {
+            let mut ret = attribute_stream(attributes);
+            ret.extend(generate(v));
+
+            ret
+        })),
+    )
+    .into();
+
+    options.emit.finish(parser.track(diagnostics.wrap(stream)))
+}
+
+/// Generates an array of socket addresses from their string representations
+///
+/// # Syntax
+///
+/// This macro takes one or more string representations of socket addresses,
+/// which are kept in the given order, and generates a `[SocketAddr; N]`
+/// array. The `socketsv4!` and `socketsv6!` macros generate arrays of a single
+/// address family instead. The addresses accept the same attributes, groups
+/// and `sort` option as the ones of `ips!`, the `ascending` order sorting the
+/// socket addresses of the same IP address by port.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::{socket, sockets};
+/// use std::net::SocketAddr;
+///
+/// const BOOTSTRAP: [SocketAddr; 2] = sockets!["1.1.1.1:53", "[2606:4700:4700::1111]:53"];
+/// assert_eq!(BOOTSTRAP[0], socket!("1.1.1.1:53"));
+///
+/// const LISTEN: &[SocketAddr] = &sockets!["0.0.0.0:80", #[cfg(unix)] "[::]:80"];
+/// assert_eq!(LISTEN.len(), if cfg!(unix) { 2 } else { 1 });
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn sockets(item: TokenStream) -> TokenStream {
+    socket_array::<SocketAddr>(
+        item,
+        "sockets",
+        "socket address",
+        "SocketAddr",
+        |v| *v,
+        generate_ip_socket_stream,
+    )
+}
+
+/// Generates an array of IPv4 socket addresses from their string
+/// representations
+///
+/// # Syntax
+///
+/// This macro works as `sockets!`, but generates a `[SocketAddrV4; N]` array.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::socketsv4;
+/// use std::net::SocketAddrV4;
+///
+/// const SEEDS: [SocketAddrV4; 2] = socketsv4!["192.0.2.1:8333", "192.0.2.2:8333"];
+/// assert_eq!(SEEDS[1].port(), 8333);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn socketsv4(item: TokenStream) -> TokenStream {
+    socket_array::<SocketAddrV4>(
+        item,
+        "socketsv4",
+        "IPv4 socket address",
+        "SocketAddrV4",
+        |v| SocketAddr::V4(*v),
+        generate_ipv4_socket_stream,
+    )
+}
+
+/// Generates an array of IPv6 socket addresses from their string
+/// representations
+///
+/// # Syntax
+///
+/// This macro works as `sockets!`, but generates a `[SocketAddrV6; N]` array.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::socketsv6;
+/// use std::net::SocketAddrV6;
+///
+/// const SEEDS: [SocketAddrV6; 2] = socketsv6!["[2001:db8::1]:8333", "[2001:db8::2]:8333"];
+/// assert_eq!(SEEDS[0].port(), 8333);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn socketsv6(item: TokenStream) -> TokenStream {
+    socket_array::<SocketAddrV6>(
+        item,
+        "socketsv6",
+        "IPv6 socket address",
+        "SocketAddrV6",
+        |v| SocketAddr::V6(*v),
+        generate_ipv6_socket_stream,
+    )
+}
+
+/// Generates an IP address or a socket address from the value of an
+/// environment variable at compile time
+///
+/// # Syntax
+///
+/// This macro works as `env!`: it takes the name of an environment variable,
+/// read when the macro is expanded, and parses its value as an IP address,
+/// generated as an `IpAddr`. The compilation fails when the variable isn't
+/// defined or doesn't hold a valid address.
+///
+/// The `socket` option parses the value as a socket address instead, generated
+/// as a `SocketAddr`.
+///
+/// The compiler tracks the variable, so the crate is rebuilt when its value
+/// changes.
+///
+/// # Example
+///
+/// ```ignore
+/// # use fancy_ip::ip_env;
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// const DNS: IpAddr = ip_env!("DNS_SERVER");
+/// const UPSTREAM: SocketAddr = ip_env!("UPSTREAM_ADDR", socket);
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::ip_env;
+/// const UPSTREAM: std::net::IpAddr = ip_env!("FANCY_IP_UNDEFINED_VARIABLE");
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_env(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (name, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut socket = false;
+    let options = parse_ip_options(&mut parser, |opt| {
+        if opt.name() == "socket" {
+            emit_parse_error(opt.flag());
+            socket = true;
+
+            true
+        } else {
+            false
+        }
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let value = match std::env::var(&name) {
+        Ok(v) => v,
+        Err(std::env::VarError::NotPresent) => {
+            abort!(span, "The environment variable `{}` is not defined", name);
+        }
+        Err(std::env::VarError::NotUnicode(_)) => {
+            abort!(
+                span,
+                "The value of the environment variable `{}` is not valid unicode",
+                name
+            );
+        }
+    };
+    let value = value.trim();
+    let kind = if socket {
+        "socket address"
+    } else {
+        "IP address"
+    };
+    let note = format!("The address is read from the environment variable `{name}`");
+
+    let (addr, stream) = if socket {
+        match SocketAddr::from_str(value) {
+            Ok(v) => (IpOrSocket::Socket(v), generate_ip_socket_stream(&v)),
+            Err(_) => report_invalid_env_address_error(span, value, kind, &note),
+        }
+    } else {
+        match IpAddr::from_str(value) {
+            Ok(v) => (IpOrSocket::Ip(v), generate_ip_stream(&v)),
+            Err(_) => report_invalid_env_address_error(span, value, kind, &note),
+        }
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+    match &addr {
+        IpOrSocket::Ip(v) => options.checks.check(&mut diagnostics, v, span),
+        IpOrSocket::Socket(v) => options.checks.check_socket(&mut diagnostics, v, span),
+    }
+
+    diagnostics.record(
+        "ip_env",
+        &name,
+        match addr {
+            IpOrSocket::Ip(_) => "IpAddr",
+            IpOrSocket::Socket(_) => "SocketAddr",
+        },
+        value.to_string(),
+        span,
+    );
+
+    options.emit.finish(generate_env_tracking_stream(
+        &name,
+        diagnostics.wrap(stream),
+    ))
+}
+
+/// Generates a slice of IP addresses from a file listing them
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the path of
+/// the file, relative to the directory containing the `Cargo.toml` of the
+/// crate. The file lists one address per line, empty lines and comments
+/// starting with `#` being ignored. The addresses are kept in the order of the
+/// file and generated as a `&'static [IpAddr]` slice. The `sort` option of
+/// `ips!` sorts them instead.
+///
+/// Invalid addresses fail the compilation, the errors naming the file and the
+/// line of the address. The compiler tracks the file, so the crate is rebuilt
+/// when it changes.
+///
+/// # Example
+///
+/// With a `tests/fixtures/blocklist.txt` file such as:
+/// ```text
+/// # Scanners
+/// 192.0.2.17
+/// 2001:db8::dead:beef
+/// ```
+///
+/// ```
+/// # use fancy_ip::ip_list_from_file;
+/// use std::net::IpAddr;
+///
+/// const BLOCKLIST: &[IpAddr] = ip_list_from_file!("tests/fixtures/blocklist.txt");
+/// assert_eq!(BLOCKLIST.len(), 2);
+/// assert!(BLOCKLIST[1].is_ipv6());
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn ip_list_from_file(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (path, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut sort = None;
+    let options = parse_ip_options(&mut parser, |opt| parse_sort_option(opt, &mut sort));
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let (content, included) = match Included::read_from_manifest(&path) {
+        Ok(v) => v,
+        Err(message) => abort!(span, "{}", message),
+    };
+
+    let mut addrs = Vec::new();
+
+    for (i, value) in data_lines(&content) {
+        match IpAddr::from_str(value) {
+            Ok(v) => addrs.push((v, value)),
+            Err(_) => {
+                emit_invalid_address_error(span, value, "IP address", Some(&included.at_line(i)))
+            }
+        }
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (addr, literal) in &addrs {
+        options.checks.check(&mut diagnostics, addr, span);
+
+        diagnostics.record(
+            "ip_list_from_file",
+            literal,
+            "IpAddr",
+            addr.to_string(),
+            span,
+        );
+    }
+
+    sort.unwrap_or_default()
+        .apply(&mut addrs, |(addr, _)| *addr);
+
+    let mut body = included.track_stream();
+    body.extend(generate_slice_stream(
+        "ADDRESSES",
+        tokens::path(&format!("{OBJECT_PREFIX}::IpAddr")),
+        addrs.iter().map(|(v, _)| generate_ip_stream(v)),
+    ));
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(tokens::group(Delimiter::Brace, body).into())))
+}
+
+/// Generates a host table from a file in the `hosts(5)` format
+///
+/// # Syntax
+///
+/// This macro works as a function which take only one argument: the path of
+/// the file, relative to the directory containing the `Cargo.toml` of the
+/// crate. Each line of the file holds an IP address followed by one or more
+/// host names separated by whitespaces, empty lines and comments starting with
+/// `#` being ignored, as in `/etc/hosts`.
+///
+/// The table is generated as a `&'static [(IpAddr, &'static str)]` slice
+/// holding one entry per host name, in the order of the file. The `sort`
+/// option of `ips!` sorts the entries by address instead, the names of an
+/// address keeping the order of the file. Invalid
+/// addresses and host names fail the compilation, the errors naming the file
+/// and the line of the entry. The compiler tracks the file, so the crate is
+/// rebuilt when it changes.
+///
+/// # Example
+///
+/// With a `tests/fixtures/hosts` file such as:
+/// ```text
+/// 127.0.0.1   localhost
+/// ::1         localhost ip6-localhost
+/// 192.0.2.10  api.test  # Mock server
+/// ```
+///
+/// ```
+/// # use fancy_ip::hosts;
+/// use std::net::IpAddr;
+///
+/// const HOSTS: &[(IpAddr, &str)] = hosts!("tests/fixtures/hosts");
+/// assert_eq!(HOSTS.len(), 4);
+/// ```
+#[proc_macro_error(proc_macro_hack)]
+#[proc_macro]
+pub fn hosts(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (path, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut sort = None;
+    let options = parse_ip_options(&mut parser, |opt| parse_sort_option(opt, &mut sort));
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let (content, included) = match Included::read_from_manifest(&path) {
+        Ok(v) => v,
+        Err(message) => abort!(span, "{}", message),
+    };
+
+    let mut entries = Vec::new();
+
+    for (i, line) in data_lines(&content) {
+        let origin = format!("The entry is included from {}", included.at_line(i));
+        let mut fields = line.split_whitespace();
+        let value = fields.next().unwrap_or_default();
+
+        let addr = match IpAddr::from_str(value) {
+            Ok(v) => v,
+            Err(_) => {
+                emit_invalid_address_error(span, value, "IP address", Some(&included.at_line(i)));
+
+                continue;
+            }
+        };
+
+        let names: Vec<&str> = fields.collect();
+
+        if names.is_empty() {
+            emit_error!(
+                span,
+                "The address `{}` is given without any host name",
+                value;
+                note = origin
+            );
+        }
+
+        for name in names {
+            if host_name(name) == Some(name) {
+                entries.push((addr, value, name));
+            } else {
+                emit_error!(
+                    span,
+                    "The given name `{}` is not a valid host name",
+                    name;
+                    note = origin
+                );
+            }
+        }
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (addr, literal, _) in &entries {
+        options.checks.check(&mut diagnostics, addr, span);
+
+        diagnostics.record("hosts", literal, "IpAddr", addr.to_string(), span);
+    }
+
+    sort.unwrap_or_default()
+        .apply(&mut entries, |(addr, _, _)| *addr);
+
+    let ty = tokens::group(
+        Delimiter::Parenthesis,
+        tokens::list([
+            tokens::path(&format!("{OBJECT_PREFIX}::IpAddr")),
+            [tokens::punct('&'), tokens::ident("str")]
+                .into_iter()
+                .collect(),
+        ]),
+    );
+
+    let mut body = included.track_stream();
+    body.extend(generate_slice_stream(
+        "HOSTS",
+        ty.into(),
+        entries.iter().map(|(addr, _, name)| {
+            tokens::group(
+                Delimiter::Parenthesis,
+                tokens::list([
+                    generate_ip_stream(addr),
+                    tokens::literal(Literal::string(name)).into(),
+                ]),
+            )
+            .into()
+        }),
+    ));
+
+    options
+        .emit
+        .finish(parser.track(diagnostics.wrap(tokens::group(Delimiter::Brace, body).into())))
+}
+
+/// Generates a table of firewall rules from a file, along with its evaluation
+/// function
+///
+/// # Syntax
+///
+/// This macro is used in item position, and takes one argument: the path of
+/// the file, relative to the directory containing the `Cargo.toml` of the
+/// crate. Each line of the file holds a rule, made of the `allow` or `deny`
+/// action followed by an IPv4 or IPv6 network in CIDR notation or a single
+/// address, empty lines and comments starting with `#` being ignored. The
+/// address of each network must have no bit set outside of its prefix. The
+/// checks are done on the address of each network.
+///
+/// The macro generates a `const fn is_allowed(addr: IpAddr) -> bool`
+/// function applying the action of the first rule matching the address, as
+/// most firewalls do. The rules are kept in the order of the file, stored as
+/// `(address, mask, allowed)` tuples in the `IS_ALLOWED_V4_TABLE` array of
+/// `(u32, u32, bool)` for IPv4 and the `IS_ALLOWED_V6_TABLE` array of
+/// `(u128, u128, bool)` for IPv6, which the function scans linearly.
+///
+/// The `default = allow` or `default = deny` option gives the action applied
+/// to the addresses matched by no rule, which defaults to `deny`. The
+/// `name = <name>` option renames the function, the tables being named after
+/// it, and the `pub` option makes both the function and the tables public.
+///
+/// A rule whose addresses are all matched by a rule given before is never
+/// applied, which fails the compilation. Invalid rules fail the compilation
+/// as well, the errors naming the file and the line of the rule. The compiler
+/// tracks the file, so the crate is rebuilt when it changes.
+///
+/// # Example
+///
+/// With a `tests/fixtures/firewall.rules` file such as:
+/// ```text
+/// # Management network
+/// deny  10.0.99.0/24
+/// allow 10.0.0.0/16
+/// allow 192.0.2.10      # Monitoring
+/// allow 2001:db8::/32
+/// ```
+///
+/// ```
+/// # use fancy_ip::rules_from_file;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// rules_from_file!("tests/fixtures/firewall.rules");
+///
+/// const ALLOWED: bool = is_allowed(IpAddr::V4(Ipv4Addr::new(10, 0, 1, 2)));
+/// assert!(ALLOWED);
+/// assert!(!is_allowed("10.0.99.1".parse().unwrap()));
+/// assert!(!is_allowed("192.0.2.11".parse().unwrap()));
+/// assert_eq!(IS_ALLOWED_V4_TABLE.len(), 3);
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn rules_from_file(item: TokenStream) -> TokenStream {
+    let mut parser = ArgParser::from(item);
+
+    let (path, span) = if let Some(arg) = report_error(parser.next_string()) {
+        arg
+    } else {
+        report_too_few_arguments_error(0, 1);
+    };
+
+    let mut name = None;
+    let mut default = None;
+    let mut public = false;
+    let options = parse_ip_options(&mut parser, |opt| match opt.name() {
+        "name" => {
+            if name.is_some() {
+                emit_error!(opt.span(), "The `name` value is given twice");
+            } else {
+                name = emit_parse_error(opt.value_ident());
+            }
+
+            true
+        }
+        "default" => {
+            if default.is_some() {
+                emit_error!(opt.span(), "The `default` value is given twice");
+            } else {
+                default = match emit_parse_error(opt.value_ident()).as_deref() {
+                    Some("allow") => Some(true),
+                    Some("deny") => Some(false),
+                    Some(value) => {
+                        emit_error!(
+                            opt.span(),
+                            "Unknown default action `{}`",
+                            value;
+                            help = "Expected `allow` or `deny`"
+                        );
+
+                        None
+                    }
+                    None => None,
+                };
+            }
+
+            true
+        }
+        "pub" => {
+            emit_parse_error(opt.flag());
+            public = true;
+
+            true
+        }
+        _ => false,
+    });
+
+    if let Some(span) = report_error(parser.ignore_next()) {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    abort_if_dirty();
+
+    let (content, included) = match Included::read_from_manifest(&path) {
+        Ok(v) => v,
+        Err(message) => abort!(span, "{}", message),
+    };
+
+    let mut rules: Vec<(Network, bool, &str, &str, usize)> = Vec::new();
+
+    for (i, line) in data_lines(&content) {
+        let origin = format!("The rule is included from {}", included.at_line(i));
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        let (action, value) = match fields.as_slice() {
+            [action, value] => (*action, *value),
+            [action] => {
+                emit_error!(
+                    span,
+                    "The rule `{}` is given without any network",
+                    action;
+                    note = origin
+                );
+
+                continue;
+            }
+            _ => {
+                emit_error!(
+                    span,
+                    "The rule `{}` is not of the form `<action> <network>`",
+                    line;
+                    note = origin
+                );
+
+                continue;
+            }
+        };
+
+        let allowed = match action {
+            "allow" => true,
+            "deny" => false,
+            _ => {
+                emit_error!(
+                    span,
+                    "Unknown action `{}`",
+                    action;
+                    help = "Expected `allow` or `deny`";
+                    note = origin
+                );
+
+                continue;
+            }
+        };
+
+        let Some(network) = Network::parse(value, true) else {
+            emit_invalid_network_error(span, value, "network", Some(&included.at_line(i)));
+            continue;
+        };
+
+        if network.has_host_bits() {
+            emit_host_bits_error(&network, span);
+            continue;
+        }
+
+        let shadowing = rules.iter().find(|(other, _, _, _, _)| {
+            other.prefix_len() <= network.prefix_len() && other.contains(&network.addr())
+        });
+
+        if let Some((_, _, other, _, j)) = shadowing {
+            emit_error!(
+                span,
+                "The rule `{}` is never applied, as the rule `{}` of {} matches all its addresses",
+                line,
+                other,
+                included.at_line(*j);
+                help = "Move the rule before the one matching its addresses, or remove it";
+                note = origin
+            );
+        }
+
+        rules.push((network, allowed, line, value, i));
+    }
+
+    abort_if_dirty();
+
+    let mut diagnostics = options.checks.diagnostics();
+
+    for (network, _, _, literal, _) in &rules {
+        options
+            .checks
+            .check(&mut diagnostics, &network.addr(), span);
+
+        diagnostics.record(
+            "rules_from_file",
+            literal,
+            "IpAddr",
+            network.to_string(),
+            span,
+        );
+    }
+
+    let mut diagnostics = Some(diagnostics);
+    let name = name.unwrap_or_else(|| "is_allowed".to_owned());
+    let default = default.unwrap_or(false);
+    let visibility = || public.then(|| tokens::ident("pub"));
+
+    let mut ret = TokenStream::new();
+    let mut arms = Vec::new();
+
+    for (family, int, digits) in [("V4", "u32", 8), ("V6", "u128", 32)] {
+        let family_rules: Vec<_> = rules
+            .iter()
+            .filter(|(network, _, _, _, _)| network.addr().is_ipv4() == (family == "V4"))
+            .collect();
+
+        let table_name = format!("{}_{family}_TABLE", name.to_uppercase());
+
+        let rows = family_rules.iter().map(|(network, allowed, _, _, _)| {
+            let bits = match network.addr() {
+                IpAddr::V4(addr) => u32::from(addr) as u128,
+                IpAddr::V6(addr) => u128::from(addr),
+            };
+
+            tokens::group(
+                Delimiter::Parenthesis,
+                [
+                    tokens::hex_suffixed(bits, digits, ""),
+                    tokens::punct(','),
+                    tokens::hex_suffixed(network.mask(), digits, ""),
+                    tokens::punct(','),
+                    tokens::ident(if *allowed { "true" } else { "false" }),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .into()
+        });
+
+        let table = tokens::group(Delimiter::Bracket, tokens::list(rows)).into();
+        // The warnings and the tracked files are attached to the first table
+        let table = match diagnostics.take() {
+            Some(diagnostics) => {
+                let mut body = included.track_stream();
+                body.extend(parser.track(diagnostics.wrap(table)));
+
+                options
+                    .emit
+                    .finish(tokens::group(Delimiter::Brace, body).into())
+            }
+            None => options.emit.finish(table),
+        };
+
+        let mut row_type: TokenStream = tokens::group(
+            Delimiter::Parenthesis,
+            tokens::list([tokens::path(int), tokens::path(int), tokens::path("bool")]),
+        )
+        .into();
+        row_type.extend([
+            tokens::punct(';'),
+            tokens::literal(Literal::usize_unsuffixed(family_rules.len())),
+        ]);
+
+        ret.extend(visibility());
+        ret.extend([
+            tokens::ident("const"),
+            tokens::ident(&table_name),
+            tokens::punct(':'),
+            tokens::group(Delimiter::Bracket, row_type),
+            tokens::punct('='),
+        ]);
+        ret.extend(table);
+        ret.extend([tokens::punct(';')]);
+
+        let mut octets = tokens::path("addr");
+        octets.extend([tokens::punct('.')]);
+        octets.extend(tokens::call("octets", []));
+
+        let mut body = tokens::let_statement(
+            "addr",
+            tokens::call(&format!("{int}::from_be_bytes"), [octets]),
+        );
+        body.extend(generate_prefix_scan_stream(&table_name, |value| value));
+        body.extend([tokens::ident(if default { "true" } else { "false" })]);
+
+        let mut arm = options.emit.type_path(&format!("IpAddr::{family}"));
+        arm.extend([tokens::group(Delimiter::Parenthesis, tokens::path("addr"))]);
+        arm.extend(tokens::op("=>"));
+        arm.extend([tokens::group(Delimiter::Brace, body)]);
+        arms.push(arm);
+    }
+
+    let mut param = tokens::path("addr");
+    param.extend([tokens::punct(':')]);
+    param.extend(options.emit.type_path("IpAddr"));
+
+    let mut body: TokenStream = [tokens::ident("match"), tokens::ident("addr")]
+        .into_iter()
+        .collect();
+    body.extend([tokens::group(Delimiter::Brace, tokens::list(arms))]);
+
+    ret.extend(visibility());
+    ret.extend([
+        tokens::ident("const"),
+        tokens::ident("fn"),
+        tokens::ident(&name),
+        tokens::group(Delimiter::Parenthesis, param),
+    ]);
+    ret.extend(tokens::op("->"));
+    ret.extend([tokens::ident("bool")]);
+    ret.extend([tokens::group(Delimiter::Brace, body)]);
+
+    ret
+}
+
+/// Declares constants holding addresses
+///
+/// # Syntax
+///
+/// This macro takes declarations of the form `NAME: Type = <arguments>;`,
+/// each one being expanded into a `const` item. The type is one of the
+/// `std::net` address types (`Ipv4Addr`, `Ipv6Addr`, `IpAddr`,
+/// `SocketAddrV4`, `SocketAddrV6` or `SocketAddr`) and the arguments are the
+/// string representation of the address followed by the options of the
+/// address macros, such as `allow_reserved`. Declarations can be preceded by
+/// attributes, including doc comments, and a visibility.
+///
+/// Two declarations can't have the same name or the same value, which catches
+/// the address pasted twice. Two socket addresses of the same IP address and
+/// different ports are reported as well, unless the later one is given the
+/// `allow_same_ip` option.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::addresses;
+/// addresses! {
+///     /// Primary DNS server
+///     pub DNS_PRIMARY: Ipv4Addr = "8.8.8.8";
+///     pub(crate) API: SocketAddr = "10.0.0.5:443";
+///     METRICS: SocketAddr = "10.0.0.5:9100", allow_same_ip;
+///     LOCALHOST: IpAddr = "::1";
+/// }
+///
+/// assert_eq!(DNS_PRIMARY, std::net::Ipv4Addr::new(8, 8, 8, 8));
+/// assert_eq!(API.port(), 443);
+/// assert_eq!(METRICS.ip(), API.ip());
+/// assert!(LOCALHOST.is_loopback());
+/// ```
+///
+/// ```compile_fail
+/// # use fancy_ip::addresses;
+/// addresses! {
+///     DNS_PRIMARY: Ipv4Addr = "8.8.8.8";
+///     DNS_SECONDARY: Ipv4Addr = "8.8.8.8";
+/// }
+/// ```
+#[proc_macro_error]
+#[proc_macro]
+pub fn addresses(item: TokenStream) -> TokenStream {
+    let mut ret = TokenStream::new();
+    let mut entries = Vec::new();
+    let mut declared = Vec::new();
+
+    for declaration in declaration::parse(item) {
+        match declaration {
+            Ok(declaration) => ret.extend(generate_declaration(
+                declaration,
+                &mut entries,
+                &mut declared,
+            )),
+            Err(e) => emit_error!(e.span, "{}", e.message),
+        }
+    }
+
+    abort_if_dirty();
+    inventory::write(&entries);
+
+    ret
+}
+
+/// Returns the name of an address type of `std::net` given to the declaring
+/// macros, reporting the unsupported ones
+fn address_type(ty: &proc_macro::Ident) -> Option<&'static str> {
+    const TYPES: [&str; 6] = [
+        "Ipv4Addr",
+        "Ipv6Addr",
+        "IpAddr",
+        "SocketAddrV4",
+        "SocketAddrV6",
+        "SocketAddr",
+    ];
+
+    let name = ty.to_string();
+    let ret = TYPES.into_iter().find(|v| *v == name);
+
+    if ret.is_none() {
+        emit_error!(
+            ty.span(),
+            "Unsupported type `{}`: expected `Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddrV4`, `SocketAddrV6` or `SocketAddr`",
+            name
+        );
+    }
+
+    ret
+}
+
+/// Returns the name of the address type of a declared field or item, such as
+/// `std::net::SocketAddr`, reporting the unsupported ones
+fn declared_address_type(ty: &[TokenTree]) -> Option<&'static str> {
+    let is_path = ty.iter().all(|token| match token {
+        TokenTree::Ident(_) => true,
+        TokenTree::Punct(punct) => punct.as_char() == ':',
+        _ => false,
+    });
+
+    match ty {
+        [.., TokenTree::Ident(ty)] if is_path => address_type(ty),
+        [token, ..] => {
+            emit_error!(
+                token.span(),
+                "Unsupported type: expected `Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddrV4`, `SocketAddrV6` or `SocketAddr`"
+            );
+
+            None
+        }
+        [] => None,
+    }
+}
+
+/// Parses an address reporting it without aborting when it is invalid
+fn parse_declared_address<T: FromStr>(
+    literal: &str,
+    span: Span,
+    kind: &str,
+    origin: Option<&Included>,
+) -> Option<T> {
+    let ret = T::from_str(literal).ok();
+
+    if ret.is_none() {
+        emit_invalid_address_error(span, literal, kind, origin);
+    }
+
+    ret
+}
+
+/// Generates an address of the type `ty` returned by [`address_type`],
+/// `value` being the arguments an address macro would take and `name` the
+/// name of the macro recorded in the inventory
+///
+/// `extra` parses the options specific to the macro, returning `false` if the
+/// option is unknown. The inventory entry is pushed to `inventory`, so that
+/// the caller writes it once all of its expressions are generated without
+/// errors. Returns the address along with its expression, or `None` once the
+/// errors are reported.
+fn generate_typed_address(
+    ty: &str,
+    value: TokenStream,
+    name: &str,
+    inventory: &mut Vec<String>,
+    extra: impl FnMut(&Opt) -> bool,
+) -> Option<(IpOrSocket, TokenStream)> {
+    let mut parser = ArgParser::from(value);
+    let (literal, span) = emit_parse_error(parser.next_string()).flatten()?;
+
+    let options = parse_ip_options(&mut parser, extra);
+
+    if let Some(span) = emit_parse_error(parser.ignore_next()).flatten() {
+        report_too_many_arguments_error(span, parser.count_arguments(), 1);
+    }
+
+    let origin = parser.origin();
+    let (addr, stream) = match ty {
+        "Ipv4Addr" => {
+            let v = parse_declared_address(&literal, span, "IPv4 address", origin)?;
+            (IpOrSocket::Ip(IpAddr::V4(v)), generate_ipv4_stream(&v))
+        }
+        "Ipv6Addr" => {
+            let v = parse_declared_address(&literal, span, "IPv6 address", origin)?;
+            (IpOrSocket::Ip(IpAddr::V6(v)), generate_ipv6_stream(&v))
+        }
+        "IpAddr" => {
+            let v = parse_declared_address(&literal, span, "IP address", origin)?;
+            (IpOrSocket::Ip(v), generate_ip_stream(&v))
+        }
+        "SocketAddrV4" => {
+            let v = parse_declared_address(&literal, span, "IPv4 socket address", origin)?;
+            (
+                IpOrSocket::Socket(SocketAddr::V4(v)),
+                generate_ipv4_socket_stream(&v),
+            )
+        }
+        "SocketAddrV6" => {
+            let v = parse_declared_address(&literal, span, "IPv6 socket address", origin)?;
+            (
+                IpOrSocket::Socket(SocketAddr::V6(v)),
+                generate_ipv6_socket_stream(&v),
+            )
+        }
+        _ => {
+            let v = parse_declared_address(&literal, span, "socket address", origin)?;
+            (IpOrSocket::Socket(v), generate_ip_socket_stream(&v))
+        }
+    };
+
+    let mut diagnostics = options.checks.diagnostics();
+    match &addr {
+        IpOrSocket::Ip(v) => options.checks.check(&mut diagnostics, v, span),
+        IpOrSocket::Socket(v) => options.checks.check_socket(&mut diagnostics, v, span),
+    }
+
+    diagnostics.record(
+        name,
+        &literal,
+        ty,
+        match &addr {
+            IpOrSocket::Ip(v) => v.to_string(),
+            IpOrSocket::Socket(v) => v.to_string(),
+        },
+        span,
+    );
+    inventory.extend(diagnostics.take_inventory());
+
+    Some((
+        addr,
+        options.emit.finish(parser.track(diagnostics.wrap(stream))),
+    ))
+}
+
+/// Declaration of `addresses!` generated without errors, whose name and value
+/// the next declarations must not give again
+struct Declared {
+    name: proc_macro::Ident,
+    addr: IpOrSocket,
+}
+
+/// Reports the declaration of `addresses!` giving the name or the value of a
+/// declaration given before, `allow_same_ip` accepting the socket addresses
+/// of the same IP address and different ports
+fn check_declared(declared: &[Declared], new: &Declared, allow_same_ip: bool) {
+    let name = new.name.to_string();
+
+    if let Some(other) = declared.iter().find(|v| v.name.to_string() == name) {
+        emit_error!(
+            new.name.span(),
+            "The name `{}` is already given to a declaration",
+            name
+        );
+        emit_error!(
+            other.name.span(),
+            "The name `{}` is given again to a later declaration",
+            name
+        );
+    }
+
+    for other in declared {
+        match (&new.addr, &other.addr) {
+            (IpOrSocket::Ip(a), IpOrSocket::Ip(b)) if a == b => {}
+            (IpOrSocket::Socket(a), IpOrSocket::Socket(b)) if a == b => {}
+            (IpOrSocket::Socket(a), IpOrSocket::Socket(b))
+                if a.ip() == b.ip() && !allow_same_ip =>
+            {
+                emit_error!(
+                    new.name.span(),
+                    "The socket address `{}` of `{}` has the IP address of the socket address `{}` of `{}` given before",
+                    a,
+                    new.name,
+                    b,
+                    other.name;
+                    help = "Add the `allow_same_ip` option if this is intended"
+                );
+                emit_error!(
+                    other.name.span(),
+                    "The IP address of `{}` is given again to `{}`",
+                    other.name,
+                    new.name
+                );
+                return;
+            }
+            _ => continue,
+        }
+
+        let value = match &new.addr {
+            IpOrSocket::Ip(v) => v.to_string(),
+            IpOrSocket::Socket(v) => v.to_string(),
+        };
+
+        emit_error!(
+            new.name.span(),
+            "The value `{}` of `{}` is the same as the value of `{}` given before",
+            value,
+            new.name,
+            other.name
+        );
+        emit_error!(
+            other.name.span(),
+            "The value `{}` of `{}` is given again to `{}`",
+            value,
+            other.name,
+            new.name
+        );
+        return;
+    }
+}
+
+/// Generates the item of a declaration of `addresses!`, returning `None` once
+/// its errors are reported
+fn generate_declaration(
+    declaration: declaration::Declaration,
+    entries: &mut Vec<String>,
+    declared: &mut Vec<Declared>,
+) -> Option<TokenStream> {
+    let ty = address_type(&declaration.ty);
+    let mut allow_same_ip = false;
+    let (addr, expr) =
+        generate_typed_address(ty?, declaration.value, "addresses", entries, |opt| {
+            if opt.name() != "allow_same_ip" {
+                return false;
+            }
+
+            emit_parse_error(opt.flag());
+            allow_same_ip = true;
+
+            true
+        })?;
+
+    let new = Declared {
+        name: declaration.name.clone(),
+        addr,
+    };
+    check_declared(declared, &new, allow_same_ip);
+    declared.push(new);
+
+    let mut ret = declaration.prefix;
+    ret.extend([
+        tokens::ident("const"),
+        TokenTree::Ident(declaration.name),
+        tokens::punct(':'),
+    ]);
+    ret.extend(emit::type_path(ty?));
+    ret.extend([tokens::punct('=')]);
+    ret.extend(expr);
+    ret.extend([tokens::punct(';')]);
+
+    Some(ret)
+}
+
+/// Declares the address of each variant of an enum
+///
+/// # Syntax
+///
+/// This attribute applies to an enum made of unit variants, each one being
+/// given an address with an `#[addr = "<address>"]` attribute. The options of
+/// the address macros can be given after the address with the
+/// `#[addr("<address>", <options>)]` form. The attribute generates:
+///
+/// - a `const fn addr(&self)` method returning the address of the variant;
+/// - an `ALL` constant listing the variants in declaration order.
+///
+/// The addresses are generated as `SocketAddr` by default. Another address
+/// type of `std::net` can be given as argument of the attribute, such as
+/// `#[ip_table(Ipv4Addr)]`.
+///
+/// # Example
+///
+/// ```
+/// # use fancy_ip::ip_table;
+/// #[ip_table]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Server {
+///     #[addr = "10.0.0.5:443"]
+///     Api,
+///     /// Metrics collector
+///     #[addr = "[2001:db8::17]:9090"]
+///     Metrics,
+/// }
+///
+/// assert_eq!(Server::ALL, [Server::Api, Server::Metrics]);
+/// assert_eq!(Server::Api.addr().port(), 443);
+/// ```
+///
+/// ```
+/// # use fancy_ip::ip_table;
+/// #[ip_table(Ipv4Addr)]
+/// enum Resolver {
+///     #[addr = "1.1.1.1"]
+///     Cloudflare,
+///     #[addr("240.0.0.1", allow_reserved)]
+///     Lab,
+///     #[addr = "9.9.9.9"]
+///     Quad9,
+/// }
+///
+/// const QUAD9: std::net::Ipv4Addr = Resolver::Quad9.addr();
+/// # assert_eq!(QUAD9, std::net::Ipv4Addr::new(9, 9, 9, 9));
+/// ```
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn ip_table(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut attr = attr.into_iter();
+    let ty = match (attr.next(), attr.next()) {
+        (None, _) => Some("SocketAddr"),
+        (Some(TokenTree::Ident(ty)), None) => address_type(&ty),
+        (Some(TokenTree::Ident(_)), Some(token)) | (Some(token), _) => {
+            abort!(
+                token.span(),
+                "Unexpected token `{}`: expected the type of the addresses",
+                token
+            );
+        }
+    };
+
+    let (table, errors) = match declaration::parse_enum(item) {
+        Ok(v) => v,
+        Err(e) => abort!(e.span, "{}", e.message),
+    };
+
+    for e in errors {
+        emit_error!(e.span, "{}", e.message);
+    }
+
+    let mut variants = Vec::new();
+    let mut arms = Vec::new();
+    let mut all = Vec::new();
+    let mut entries = Vec::new();
+
+    for variant in table.variants {
+        let mut path = tokens::path("Self::");
+        path.extend([TokenTree::Ident(variant.name.clone())]);
+
+        if let Some((_, expr)) = ty.and_then(|ty| {
+            generate_typed_address(ty, variant.addr, "ip_table", &mut entries, |_| false)
+        }) {
+            let mut arm = path.clone();
+            arm.extend(tokens::op("=>"));
+            arm.extend(expr);
+            arms.push(arm);
+        }
+
+        let mut rebuilt = variant.attributes;
+        rebuilt.extend([TokenTree::Ident(variant.name)]);
+        rebuilt.extend(variant.discriminant);
+        variants.push(rebuilt);
+        all.push(path);
+    }
+
+    abort_if_dirty();
+    inventory::write(&entries);
+
+    let mut ret = table.prefix;
+    ret.extend([
+        TokenTree::Ident(table.name.clone()),
+        tokens::group(Delimiter::Brace, tokens::list(variants)),
+    ]);
+
+    let mut all_const: TokenStream = [
+        tokens::ident("pub"),
+        tokens::ident("const"),
+        tokens::ident("ALL"),
+        tokens::punct(':'),
+    ]
+    .into_iter()
+    .collect();
+    all_const.extend([
+        tokens::group(
+            Delimiter::Bracket,
+            [
+                tokens::ident("Self"),
+                tokens::punct(';'),
+                tokens::literal(Literal::usize_unsuffixed(all.len())),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+        tokens::punct('='),
+        tokens::group(Delimiter::Bracket, tokens::list(all)),
+        tokens::punct(';'),
+    ]);
+
+    let mut addr_fn: TokenStream = [
+        tokens::ident("pub"),
+        tokens::ident("const"),
+        tokens::ident("fn"),
+        tokens::ident("addr"),
+        tokens::group(
+            Delimiter::Parenthesis,
+            [tokens::punct('&'), tokens::ident("self")]
+                .into_iter()
+                .collect(),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    addr_fn.extend(tokens::op("->"));
+    addr_fn.extend(emit::type_path(ty.unwrap()));
+    addr_fn.extend([tokens::group(Delimiter::Brace, {
+        let mut body: TokenStream = [
+            tokens::ident("match"),
+            tokens::punct('*'),
+            tokens::ident("self"),
+        ]
+        .into_iter()
+        .collect();
+        body.extend([tokens::group(Delimiter::Brace, tokens::list(arms))]);
+        body
+    })]);
+
+    let mut body = all_const;
+    body.extend(addr_fn);
+
+    ret.extend([tokens::ident("impl"), TokenTree::Ident(table.name)]);
+    ret.extend([tokens::group(Delimiter::Brace, body)]);
+
+    ret
+}
+
+/// Derives `Default` for a struct holding addresses
+///
+/// # Syntax
+///
+/// The fields annotated with `#[ip(default = "<address>")]` default to the
+/// given address, which can be followed by the options of the address macros.
+/// Their type is one of the `std::net` address types (`Ipv4Addr`, `Ipv6Addr`,
+/// `IpAddr`, `SocketAddrV4`, `SocketAddrV6` or `SocketAddr`), possibly given
+/// with its path. The other fields default to their `Default` value.
+///
+/// Like `#[derive(Default)]`, the implementation of a generic struct keeps
+/// its parameters and `where` clause, and requires its type parameters to
+/// implement `Default`.
+///
+/// # Examples
+///
+/// ```
+/// # use fancy_ip::IpDefaults;
+/// use std::net::{IpAddr, SocketAddr};
+///
+/// #[derive(IpDefaults)]
+/// struct Config {
+///     #[ip(default = "127.0.0.1:8080")]
+///     listen: SocketAddr,
+///     #[ip(default = "::1")]
+///     metrics: std::net::IpAddr,
+///     workers: usize,
+/// }
+///
+/// let config = Config::default();
+/// assert_eq!(config.listen.port(), 8080);
+/// assert_eq!(config.workers, 0);
+/// ```
+///
+/// With a generic struct:
+///
+/// ```
+/// # use fancy_ip::IpDefaults;
+/// use std::net::Ipv4Addr;
+///
+/// #[derive(IpDefaults)]
+/// struct Peer<'a, T, const N: usize = 4>
+/// where
+///     T: Clone,
+/// {
+///     #[ip(default = "10.0.0.1")]
+///     addr: Ipv4Addr,
+///     name: &'a str,
+///     tags: Vec<T>,
+/// }
+///
+/// let peer: Peer<u8, 8> = Peer::default();
+/// assert_eq!(peer.addr, Ipv4Addr::new(10, 0, 0, 1));
+/// assert!(peer.tags.is_empty());
+/// ```
+#[proc_macro_error]
+#[proc_macro_derive(IpDefaults, attributes(ip))]
+pub fn ip_defaults(item: TokenStream) -> TokenStream {
+    let (item, errors) = match declaration::parse_struct(item) {
+        Ok(v) => v,
+        Err(e) => abort!(e.span, "{}", e.message),
+    };
+
+    for e in errors {
+        emit_error!(e.span, "{}", e.message);
+    }
+
+    let mut entries = Vec::new();
+    let default_path = tokens::path("::core::default::Default::default");
+    let mut field_default = |field: declaration::Field| {
+        let Some(value) = field.default else {
+            let mut ret = default_path.clone();
+            ret.extend([tokens::group(Delimiter::Parenthesis, TokenStream::new())]);
+
+            return Some(ret);
+        };
+
+        generate_typed_address(
+            declared_address_type(&field.ty)?,
+            value,
+            "IpDefaults",
+            &mut entries,
+            |_| false,
+        )
+        .map(|(_, expr)| expr)
+    };
+
+    let body: TokenStream = match item.fields {
+        declaration::Fields::Named(fields) => {
+            let fields: Vec<TokenStream> = fields
+                .into_iter()
+                .filter_map(|field| {
+                    let mut ret: TokenStream = field
+                        .name
+                        .clone()
+                        .map(TokenTree::Ident)
+                        .into_iter()
+                        .collect();
+                    ret.extend([tokens::punct(':')]);
+                    ret.extend(field_default(field)?);
+
+                    Some(ret)
+                })
+                .collect();
+
+            [
+                tokens::ident("Self"),
+                tokens::group(Delimiter::Brace, tokens::list(fields)),
+            ]
+            .into_iter()
+            .collect()
+        }
+        declaration::Fields::Unnamed(fields) => tokens::call(
+            "Self",
+            fields
+                .into_iter()
+                .filter_map(field_default)
+                .collect::<Vec<_>>(),
+        ),
+        declaration::Fields::Unit => tokens::path("Self"),
+    };
+
+    abort_if_dirty();
+    inventory::write(&entries);
+
+    let generics = item.generics;
+    let angled = |items: Vec<TokenStream>| -> TokenStream {
+        if items.is_empty() {
+            return TokenStream::new();
+        }
+
+        let mut ret: TokenStream = [tokens::punct('<')].into_iter().collect();
+        ret.extend(tokens::list(items));
+        ret.extend([tokens::punct('>')]);
+
+        ret
+    };
+
+    // Like `#[derive(Default)]`, the type parameters are required to
+    // implement `Default`
+    let mut predicates = generics.predicates;
+    predicates.extend(generics.types.into_iter().map(|ty| {
+        let mut ret: TokenStream = [TokenTree::Ident(ty), tokens::punct(':')]
+            .into_iter()
+            .collect();
+        ret.extend(tokens::path("::core::default::Default"));
+
+        ret
+    }));
+
+    let mut ret: TokenStream = [tokens::ident("impl")].into_iter().collect();
+    ret.extend(angled(generics.params));
+    ret.extend(tokens::path("::core::default::Default"));
+    ret.extend([tokens::ident("for"), TokenTree::Ident(item.name)]);
+    ret.extend(angled(generics.arguments));
+
+    if !predicates.is_empty() {
+        ret.extend([tokens::ident("where")]);
+        ret.extend(tokens::list(predicates));
+    }
+
+    let mut function: TokenStream = [
+        tokens::ident("fn"),
+        tokens::ident("default"),
+        tokens::group(Delimiter::Parenthesis, TokenStream::new()),
+    ]
+    .into_iter()
+    .collect();
+    function.extend(tokens::op("->"));
+    function.extend([tokens::ident("Self"), tokens::group(Delimiter::Brace, body)]);
+
+    ret.extend([tokens::group(Delimiter::Brace, function)]);
+
+    ret
+}
+
+/// Initializes a `const` or `static` item from the string representation of
+/// an address
+///
+/// # Syntax
+///
+/// This attribute applies to a `const` or `static` item initialized with the
+/// string representation of an address, which is replaced by the address. The
+/// declared type is kept as is and selects the address type, one of the
+/// `std::net` address types (`Ipv4Addr`, `Ipv6Addr`, `IpAddr`,
+/// `SocketAddrV4`, `SocketAddrV6` or `SocketAddr`) possibly given with its
+/// path. The options of the address macros are given as arguments of the
+/// attribute.
+///
+/// This attribute isn't named `ip` as the `ip!` macro already uses this name.
+///
+/// # Example
+///
+/// ```
+/// use fancy_ip::address;
+/// use std::net::{Ipv4Addr, SocketAddr};
+///
+/// #[address]
+/// const GATEWAY: Ipv4Addr = "192.168.1.1";
+///
+/// #[address(allow_reserved)]
+/// static LAB: std::net::Ipv4Addr = "240.0.0.1";
+///
+/// # assert_eq!(GATEWAY, Ipv4Addr::new(192, 168, 1, 1));
+/// ```
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn address(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = match declaration::parse_item(item) {
+        Ok(v) => v,
+        Err(e) => abort!(e.span, "{}", e.message),
+    };
+
+    let mut value = item.value;
+
+    if !attr.is_empty() {
+        value.extend([tokens::punct(',')]);
+        value.extend(attr);
+    }
+
+    let mut entries = Vec::new();
+    let expr = declared_address_type(&item.ty)
+        .and_then(|ty| generate_typed_address(ty, value, "address", &mut entries, |_| false))
+        .map(|(_, expr)| expr);
+
+    abort_if_dirty();
+    inventory::write(&entries);
+
+    let mut ret = item.prefix;
+    ret.extend([TokenTree::Ident(item.name), tokens::punct(':')]);
+    ret.extend(item.ty);
+    ret.extend([tokens::punct('=')]);
+    ret.extend(expr);
+    ret.extend([tokens::punct(';')]);
+
+    ret
+}
@@ -0,0 +1,58 @@
+//! Hardware address parsing
+
+/// Parses a hardware address made of `N` hexadecimal bytes separated by `:`
+/// or `-`
+///
+/// The separator must be the same across the whole address.
+pub fn parse_bytes<const N: usize>(value: &str) -> Option<[u8; N]> {
+    let separator = if value.contains(':') { ':' } else { '-' };
+    let mut ret = [0u8; N];
+    let mut parts = value.split(separator);
+
+    for byte in ret.iter_mut() {
+        let part = parts.next()?;
+
+        if part.len() != 2 || !part.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        *byte = u8::from_str_radix(part, 16).ok()?;
+    }
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(ret)
+}
+
+/// Parses a 48-bit MAC address
+pub fn parse(value: &str) -> Option<[u8; 6]> {
+    parse_bytes(value)
+}
+
+/// Formats a hardware address as lowercase bytes separated by `:`
+pub fn to_string(addr: &[u8]) -> String {
+    addr.iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Returns the modified EUI-64 interface identifier of a MAC address, as
+/// defined by RFC 4291
+///
+/// The `ff:fe` bytes are inserted in the middle of the address and the
+/// universal/local bit is inverted.
+pub fn modified_eui64(addr: &[u8; 6]) -> [u8; 8] {
+    [
+        addr[0] ^ 0x02,
+        addr[1],
+        addr[2],
+        0xff,
+        0xfe,
+        addr[3],
+        addr[4],
+        addr[5],
+    ]
+}
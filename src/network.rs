@@ -0,0 +1,304 @@
+//! Network prefixes in CIDR notation
+
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// A network prefix: an address and the length of its network part
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Network {
+    addr: IpAddr,
+    len: u8,
+}
+
+fn max_len(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn to_bits(addr: &IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(addr) => u32::from(*addr) as u128,
+        IpAddr::V6(addr) => u128::from(*addr),
+    }
+}
+
+/// Builds an address of the family of `family` from its bits
+fn from_bits(family: &IpAddr, bits: u128) -> IpAddr {
+    match family {
+        IpAddr::V4(_) => IpAddr::V4((bits as u32).into()),
+        IpAddr::V6(_) => IpAddr::V6(bits.into()),
+    }
+}
+
+impl Network {
+    /// Parses a prefix in the `address/length` notation
+    ///
+    /// When `host_prefix` is set, a bare address is accepted as a prefix
+    /// containing only this address.
+    pub fn parse(value: &str, host_prefix: bool) -> Option<Self> {
+        let (addr, len) = match value.split_once('/') {
+            Some((addr, len)) => {
+                if len.is_empty() || !len.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+
+                (IpAddr::from_str(addr).ok()?, Some(len.parse::<u8>().ok()?))
+            }
+            None if host_prefix => (IpAddr::from_str(value).ok()?, None),
+            None => return None,
+        };
+
+        let len = len.unwrap_or_else(|| max_len(&addr));
+
+        if len > max_len(&addr) {
+            return None;
+        }
+
+        Some(Self { addr, len })
+    }
+
+    /// Builds a prefix from its address and a length fitting the address
+    /// width
+    pub fn new(addr: IpAddr, len: u8) -> Self {
+        debug_assert!(len <= max_len(&addr));
+
+        Self { addr, len }
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    /// Returns the width of the addresses of the network, in bits
+    pub fn width(&self) -> u8 {
+        max_len(&self.addr)
+    }
+
+    /// Returns the child networks of length `len`, in address order
+    pub fn subnets(&self, len: u8) -> impl Iterator<Item = Self> {
+        debug_assert!(self.len <= len && len <= max_len(&self.addr));
+
+        let addr = self.addr;
+        let first = to_bits(&self.truncated().addr);
+        let step = 1u128
+            .checked_shl((max_len(&addr) - len) as u32)
+            .unwrap_or(0);
+        let count = 1u128
+            .checked_shl((len - self.len) as u32)
+            .unwrap_or(u128::MAX);
+
+        (0..count).map(move |i| Self {
+            addr: from_bits(&addr, first + i * step),
+            len,
+        })
+    }
+
+    /// Returns the length of the prefix when it exceeds the width of the
+    /// address, the value being otherwise a valid prefix
+    pub fn out_of_bounds_len(value: &str) -> Option<(&str, u8)> {
+        let (addr, len) = value.split_once('/')?;
+        let addr = IpAddr::from_str(addr).ok()?;
+
+        if len.is_empty() || !len.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        match len.parse::<u8>() {
+            Ok(v) if v <= max_len(&addr) => None,
+            _ => Some((len, max_len(&addr))),
+        }
+    }
+
+    /// Returns the mask of the network part, right-aligned on the address
+    /// width
+    pub fn mask(&self) -> u128 {
+        let width = max_len(&self.addr) as u32;
+        let host_bits = width - self.len as u32;
+        let full = u128::MAX >> (128 - width);
+
+        full & !(u128::MAX.checked_shr(128 - host_bits).unwrap_or(0))
+    }
+
+    /// Checks whether the address has bits set outside of the network part
+    pub fn has_host_bits(&self) -> bool {
+        to_bits(&self.addr) & !self.mask() != 0
+    }
+
+    /// Returns the network with the bits outside of its prefix cleared
+    pub fn truncated(&self) -> Self {
+        Self {
+            addr: from_bits(&self.addr, to_bits(&self.addr) & self.mask()),
+            len: self.len,
+        }
+    }
+
+    /// Returns the last address of the network, which is the broadcast
+    /// address of an IPv4 network
+    pub fn last_addr(&self) -> IpAddr {
+        let width = max_len(&self.addr) as u32;
+        let full = u128::MAX >> (128 - width);
+
+        from_bits(&self.addr, to_bits(&self.addr) | (full & !self.mask()))
+    }
+
+    /// Returns the first and last addresses usable by hosts
+    ///
+    /// The network address is reserved, as well as the broadcast address of
+    /// IPv4 networks, except for the point-to-point IPv4 `/31` (RFC 3021) and
+    /// IPv6 `/127` (RFC 6164) networks and the single address networks.
+    pub fn host_bounds(&self) -> (IpAddr, IpAddr) {
+        let first = to_bits(&self.truncated().addr);
+        let last = to_bits(&self.last_addr());
+
+        if max_len(&self.addr) - self.len <= 1 {
+            return (self.truncated().addr, self.last_addr());
+        }
+
+        match self.addr {
+            IpAddr::V4(_) => (
+                from_bits(&self.addr, first + 1),
+                from_bits(&self.addr, last - 1),
+            ),
+            IpAddr::V6(_) => (from_bits(&self.addr, first + 1), self.last_addr()),
+        }
+    }
+
+    /// Returns the number of addresses usable by hosts, as bounded by
+    /// [`Network::host_bounds`]
+    pub fn host_count(&self) -> u128 {
+        let (first, last) = self.host_bounds();
+
+        // The count of the host addresses of `::/0` still fits, as the
+        // network address is excluded
+        to_bits(&last) - to_bits(&first) + 1
+    }
+
+    /// Allocates subnets of the lengths `lens` from the start of the network,
+    /// returning them in the order of `lens`, or `None` if they don't fit
+    ///
+    /// The longest subnets are allocated first, so that each subnet stays
+    /// aligned on its size without any gap between them.
+    pub fn allocate(&self, lens: &[u8]) -> Option<Vec<Self>> {
+        let mut order: Vec<_> = (0..lens.len()).collect();
+        order.sort_by_key(|&i| lens[i]);
+
+        let end = to_bits(&self.last_addr());
+        let mut next = Some(to_bits(&self.truncated().addr));
+        let mut ret = vec![*self; lens.len()];
+
+        for i in order {
+            let addr = next.filter(|v| *v <= end)?;
+            let subnet = Self::new(from_bits(&self.addr, addr), lens[i]);
+            let last = to_bits(&subnet.last_addr());
+
+            if last > end {
+                return None;
+            }
+
+            ret[i] = subnet;
+            next = last.checked_add(1);
+        }
+
+        Some(ret)
+    }
+
+    /// Returns the bits of the first and last addresses of the network
+    pub fn bounds(&self) -> (u128, u128) {
+        (to_bits(&self.truncated().addr), to_bits(&self.last_addr()))
+    }
+
+    /// Returns the address at `offset` from the network address, if it is a
+    /// host address of the network
+    pub fn host(&self, offset: u128) -> Option<IpAddr> {
+        let (first, last) = self.host_bounds();
+        let bits = to_bits(&self.truncated().addr).checked_add(offset)?;
+
+        (to_bits(&first)..=to_bits(&last))
+            .contains(&bits)
+            .then(|| from_bits(&self.addr, bits))
+    }
+
+    /// Checks whether the network shares addresses with `other`, which is
+    /// then contained in it or contains it
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.contains(&other.addr) || other.contains(&self.addr)
+    }
+
+    /// Returns the smallest set of networks covering exactly the same
+    /// addresses as `networks`, in address order
+    ///
+    /// The networks contained in another one are dropped, and two adjacent
+    /// networks of the same length are merged when they form a network of a
+    /// shorter prefix. The networks must be of the same address family.
+    pub fn aggregate(networks: &[Self]) -> Vec<Self> {
+        let mut sorted: Vec<_> = networks.iter().map(Self::truncated).collect();
+        sorted.sort_by_key(|v| (to_bits(&v.addr), v.len));
+
+        let mut ret: Vec<Self> = Vec::with_capacity(sorted.len());
+
+        for network in sorted {
+            if ret.last().is_some_and(|v| v.contains(&network.addr)) {
+                continue;
+            }
+
+            ret.push(network);
+
+            // The merged network may in turn be merged with the previous one
+            while let [.., first, last] = ret[..] {
+                match first.sibling_parent(&last) {
+                    Some(parent) => {
+                        ret.truncate(ret.len() - 2);
+                        ret.push(parent);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Returns the network made of this network and of `other`, when both
+    /// are the two halves of a network of a shorter prefix
+    fn sibling_parent(&self, other: &Self) -> Option<Self> {
+        if self.len != other.len || self.len == 0 {
+            return None;
+        }
+
+        let parent = Self {
+            addr: self.addr,
+            len: self.len - 1,
+        };
+
+        (!parent.has_host_bits() && other.addr == parent.last_half_addr()).then_some(parent)
+    }
+
+    /// Returns the address of the second half of the network
+    fn last_half_addr(&self) -> IpAddr {
+        let half = 1u128 << (max_len(&self.addr) - self.len - 1);
+
+        from_bits(&self.addr, to_bits(&self.addr) | half)
+    }
+
+    /// Checks whether the address belongs to the network
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        if self.addr.is_ipv4() != addr.is_ipv4() {
+            return false;
+        }
+
+        to_bits(addr) & self.mask() == to_bits(&self.addr) & self.mask()
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
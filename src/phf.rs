@@ -0,0 +1,106 @@
+//! Perfect hash functions in the layout of the maps of the phf crate
+//!
+//! A key is hashed into a bucket index `g` and two displacement factors `f1`
+//! and `f2`. Each bucket is given a pair of displacements `(d1, d2)` so that
+//! `(d2 + f1 * d1 + f2) % len` is a distinct slot for each key, the buckets
+//! holding the most keys being placed first.
+
+use crate::siphash;
+
+/// Average number of keys per bucket, as chosen by `phf_generator`
+const LAMBDA: usize = 5;
+
+/// Number of keys of the hash function tried in turn until every key gets a
+/// slot, which almost always happens with the first one
+const ATTEMPTS: u64 = 64;
+
+/// A perfect hash function of a set of keys
+pub struct HashState {
+    pub key: u64,
+    pub disps: Vec<(u32, u32)>,
+    /// Index of the key held by each slot
+    pub map: Vec<usize>,
+}
+
+struct Hashes {
+    g: u32,
+    f1: u32,
+    f2: u32,
+}
+
+/// Hashes the bytes fed to the hasher by the `PhfHash` implementation of the
+/// key, which are the little-endian bytes of the integers
+fn hash(data: &[u8], key: u64) -> Hashes {
+    let (lower, upper) = siphash::hash128(0, key, data);
+
+    Hashes {
+        g: (lower >> 32) as u32,
+        f1: lower as u32,
+        f2: upper as u32,
+    }
+}
+
+fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
+    d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2)
+}
+
+/// Generates a perfect hash function of `entries`, which must be distinct
+///
+/// Returns `None` if none of the attempted keys gives a perfect hash function.
+pub fn generate(entries: &[Vec<u8>]) -> Option<HashState> {
+    // The keys are fixed so that the expansion is reproducible
+    (1..=ATTEMPTS).find_map(|i| try_generate(entries, i.wrapping_mul(0x9e37_79b9_7f4a_7c15)))
+}
+
+fn try_generate(entries: &[Vec<u8>], key: u64) -> Option<HashState> {
+    let hashes: Vec<_> = entries.iter().map(|v| hash(v, key)).collect();
+    let buckets_len = entries.len().div_ceil(LAMBDA).max(1);
+
+    let mut buckets = vec![Vec::new(); buckets_len];
+
+    for (i, hash) in hashes.iter().enumerate() {
+        buckets[(hash.g % buckets_len as u32) as usize].push(i);
+    }
+
+    let mut order: Vec<_> = (0..buckets_len).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(buckets[i].len()));
+
+    let len = hashes.len() as u32;
+    let mut map = vec![None; hashes.len()];
+    let mut disps = vec![(0, 0); buckets_len];
+
+    for bucket in order {
+        let keys = &buckets[bucket];
+
+        let found = (0..len)
+            .flat_map(|d1| (0..len).map(move |d2| (d1, d2)))
+            .find_map(|(d1, d2)| {
+                let mut slots = Vec::with_capacity(keys.len());
+
+                for &key in keys {
+                    let slot = (displace(hashes[key].f1, hashes[key].f2, d1, d2) % len) as usize;
+
+                    if map[slot].is_some() || slots.contains(&slot) {
+                        return None;
+                    }
+
+                    slots.push(slot);
+                }
+
+                Some(((d1, d2), slots))
+            });
+
+        let (disp, slots) = found?;
+        disps[bucket] = disp;
+
+        for (&key, slot) in keys.iter().zip(slots) {
+            map[slot] = Some(key);
+        }
+    }
+
+    Some(HashState {
+        key,
+        disps,
+        map: map.into_iter().map(Option::unwrap).collect(),
+    })
+}
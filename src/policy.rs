@@ -0,0 +1,408 @@
+//! Address policy read from the `fancy-ip.toml` file of the crate
+//!
+//! The policy file is looked for next to the `Cargo.toml` of the crate using
+//! the macros. It is written in a small subset of TOML:
+//!
+//! ```toml
+//! allowed_ranges = ["10.0.0.0/8", "fd00::/8"]
+//! net_path = "my_shim::net"
+//!
+//! # Decommissioned datacenter
+//! [[deny]]
+//! prefix = "10.20.0.0/16"
+//! message = "The Paris datacenter has been decommissioned"
+//! ```
+//!
+//! Only arrays of tables and `key = value` pairs whose values are strings or
+//! arrays of strings are supported.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::network::Network;
+
+const POLICY_FILE: &str = "fancy-ip.toml";
+
+/// Returns the path of the policy file of the crate being compiled
+fn path() -> Option<PathBuf> {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(|directory| PathBuf::from(directory).join(POLICY_FILE))
+}
+
+/// Checks whether `value` is a path such as `my_shim::net`
+pub fn is_path(value: &str) -> bool {
+    let mut segments = value.strip_prefix("::").unwrap_or(value).split("::");
+
+    segments.all(|segment| {
+        segment.chars().next().is_some_and(|c| !c.is_ascii_digit())
+            && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// Prefix address literals must not belong to
+pub struct DenyEntry {
+    pub prefix: Network,
+    pub message: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Policy {
+    deny: Vec<DenyEntry>,
+    allowed_ranges: Option<Vec<Network>>,
+    /// Prefix of the generated types replacing `std::net` or `core::net`
+    net_path: Option<String>,
+}
+
+enum Value {
+    String(String),
+    Array(Vec<String>),
+}
+
+/// A table of the policy file, `name` being `None` for the root table
+struct Table {
+    name: Option<String>,
+    line: usize,
+    entries: Vec<(String, Value, usize)>,
+}
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+type ParseResult<T> = Result<T, (usize, String)>;
+
+impl<'a> Reader<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> ParseResult<T> {
+        Err((self.line, message.into()))
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+
+        if c == Some('\n') {
+            self.line += 1;
+        }
+
+        c
+    }
+
+    /// Skips spaces and comments, including new lines if `lines` is set
+    fn skip_blank(&mut self, lines: bool) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                ' ' | '\t' | '\r' => {
+                    self.next();
+                }
+                '\n' if lines => {
+                    self.next();
+                }
+                '#' => {
+                    while !matches!(self.chars.peek(), None | Some('\n')) {
+                        self.next();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn end_of_line(&mut self) -> ParseResult<()> {
+        self.skip_blank(false);
+
+        match self.next() {
+            None | Some('\n') => Ok(()),
+            Some(c) => self.error(format!("Unexpected character `{c}`")),
+        }
+    }
+
+    fn key(&mut self) -> ParseResult<String> {
+        let mut ret = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                ret.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        if ret.is_empty() {
+            self.error("Expected a key")
+        } else {
+            Ok(ret)
+        }
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let quote = self.next();
+        let mut ret = String::new();
+
+        loop {
+            match self.next() {
+                None | Some('\n') => return self.error("Unterminated string"),
+                Some(c) if Some(c) == quote => return Ok(ret),
+                Some('\\') if quote == Some('"') => match self.next() {
+                    Some('"') => ret.push('"'),
+                    Some('\\') => ret.push('\\'),
+                    Some('n') => ret.push('\n'),
+                    Some('t') => ret.push('\t'),
+                    _ => return self.error("Unsupported escape sequence"),
+                },
+                Some(c) => ret.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self) -> ParseResult<Value> {
+        match self.chars.peek() {
+            Some('"') | Some('\'') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.next();
+                let mut ret = Vec::new();
+
+                loop {
+                    self.skip_blank(true);
+
+                    match self.chars.peek() {
+                        Some(']') => {
+                            self.next();
+                            return Ok(Value::Array(ret));
+                        }
+                        Some('"') | Some('\'') => ret.push(self.string()?),
+                        _ => return self.error("Expected a string or `]`"),
+                    }
+
+                    self.skip_blank(true);
+
+                    match self.chars.peek() {
+                        Some(',') => {
+                            self.next();
+                        }
+                        Some(']') => {}
+                        _ => return self.error("Expected `,` or `]`"),
+                    }
+                }
+            }
+            _ => self.error("Unsupported value"),
+        }
+    }
+
+    fn tables(mut self) -> ParseResult<Vec<Table>> {
+        let mut ret = vec![Table {
+            name: None,
+            line: 1,
+            entries: Vec::new(),
+        }];
+
+        loop {
+            self.skip_blank(true);
+
+            match self.chars.peek() {
+                None => return Ok(ret),
+                Some('[') => {
+                    let line = self.line;
+                    self.next();
+
+                    if self.next() != Some('[') {
+                        return self.error("Only arrays of tables (`[[name]]`) are supported");
+                    }
+
+                    let name = self.key()?;
+
+                    if self.next() != Some(']') || self.next() != Some(']') {
+                        return self.error("Expected `]]`");
+                    }
+
+                    self.end_of_line()?;
+
+                    ret.push(Table {
+                        name: Some(name),
+                        line,
+                        entries: Vec::new(),
+                    });
+                }
+                Some(_) => {
+                    let line = self.line;
+                    let key = self.key()?;
+                    self.skip_blank(false);
+
+                    if self.next() != Some('=') {
+                        return self.error("Expected `=`");
+                    }
+
+                    self.skip_blank(false);
+                    let value = self.value()?;
+                    self.end_of_line()?;
+
+                    if let Some(table) = ret.last_mut() {
+                        table.entries.push((key, value, line));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Policy {
+    fn parse(content: &str) -> ParseResult<Self> {
+        let reader = Reader {
+            chars: content.chars().peekable(),
+            line: 1,
+        };
+
+        let mut ret = Self::default();
+
+        for table in reader.tables()? {
+            match table.name.as_deref() {
+                None => {
+                    for (key, value, line) in table.entries {
+                        match (key.as_str(), value) {
+                            ("allowed_ranges", Value::Array(values)) => {
+                                let mut ranges = Vec::new();
+
+                                for v in values {
+                                    match Network::parse(&v, true) {
+                                        Some(v) => ranges.push(v),
+                                        None => {
+                                            return Err((line, format!("Invalid prefix `{v}`")));
+                                        }
+                                    }
+                                }
+
+                                ret.allowed_ranges = Some(ranges);
+                            }
+                            ("allowed_ranges", _) => {
+                                return Err((line, format!("Expected an array for `{key}`")));
+                            }
+                            ("net_path", Value::String(v)) => {
+                                if !is_path(&v) {
+                                    return Err((
+                                        line,
+                                        format!("Expected a path such as `my_net`, found `{v}`"),
+                                    ));
+                                }
+
+                                ret.net_path = Some(v);
+                            }
+                            ("net_path", _) => {
+                                return Err((line, format!("Expected a string for `{key}`")));
+                            }
+                            _ => return Err((line, format!("Unknown key `{key}`"))),
+                        }
+                    }
+                }
+                Some("deny") => {
+                    let mut prefix = None;
+                    let mut message = None;
+
+                    for (key, value, line) in table.entries {
+                        match (key.as_str(), value) {
+                            ("prefix", Value::String(v)) => match Network::parse(&v, true) {
+                                Some(v) => prefix = Some(v),
+                                None => {
+                                    return Err((line, format!("Invalid prefix `{v}`")));
+                                }
+                            },
+                            ("message", Value::String(v)) => message = Some(v),
+                            ("prefix" | "message", _) => {
+                                return Err((line, format!("Expected a string for `{key}`")));
+                            }
+                            _ => return Err((line, format!("Unknown key `{key}`"))),
+                        }
+                    }
+
+                    match prefix {
+                        Some(prefix) => ret.deny.push(DenyEntry { prefix, message }),
+                        None => return Err((table.line, String::from("Missing `prefix` key"))),
+                    }
+                }
+                Some(name) => return Err((table.line, format!("Unknown table `{name}`"))),
+            }
+        }
+
+        Ok(ret)
+    }
+
+    fn load(path: Option<&PathBuf>) -> Result<Self, String> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                Self::parse(&content).map_err(|(line, e)| format!("{}:{line}: {e}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Unable to read `{}`: {e}", path.display())),
+        }
+    }
+
+    /// Returns the policy of the crate being compiled
+    ///
+    /// The policy is cached per crate, as a single process may expand the
+    /// macros of several crates, and read again once the file is modified, as
+    /// a persistent process such as rust-analyzer may expand them again after
+    /// an edit.
+    pub fn get() -> Arc<Result<Self, String>> {
+        type Cache = Vec<(
+            Option<PathBuf>,
+            Option<SystemTime>,
+            Arc<Result<Policy, String>>,
+        )>;
+        static POLICIES: Mutex<Cache> = Mutex::new(Vec::new());
+
+        let path = path();
+        let modified = path
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).and_then(|v| v.modified()).ok());
+        let mut policies = POLICIES.lock().unwrap_or_else(|e| e.into_inner());
+
+        match policies.iter_mut().find(|(v, _, _)| *v == path) {
+            Some((_, time, policy)) => {
+                if *time != modified {
+                    *time = modified;
+                    *policy = Arc::new(Self::load(path.as_ref()));
+                }
+
+                policy.clone()
+            }
+            None => {
+                let policy = Arc::new(Self::load(path.as_ref()));
+                policies.push((path, modified, policy.clone()));
+
+                policy
+            }
+        }
+    }
+
+    /// Returns the path of the policy file of the crate being compiled if it
+    /// exists, which the expansions consulting it make the compiler track
+    pub fn file() -> Option<String> {
+        path()
+            .filter(|path| path.is_file())
+            .and_then(|path| path.to_str().map(String::from))
+    }
+
+    /// Checks whether the address belongs to the allowed ranges, when the
+    /// policy restricts them
+    pub fn allowed(&self, addr: &IpAddr) -> bool {
+        match &self.allowed_ranges {
+            Some(ranges) => ranges.iter().any(|range| range.contains(addr)),
+            None => true,
+        }
+    }
+
+    /// Returns the prefix of the generated types given by the policy, if any
+    pub fn net_path(&self) -> Option<&str> {
+        self.net_path.as_deref()
+    }
+
+    /// Returns the deny entry the address matches, if any
+    pub fn denied(&self, addr: &IpAddr) -> Option<&DenyEntry> {
+        self.deny.iter().find(|entry| entry.prefix.contains(addr))
+    }
+}
@@ -0,0 +1,74 @@
+//! 128-bit SipHash-1-3, as used by the phf crate to hash the keys of its
+//! maps
+//!
+//! The hashes must be the same as the ones computed at runtime by
+//! `phf_shared`, which feeds the keys to `siphasher::sip128::SipHasher13`.
+
+struct State {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl State {
+    fn compress(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn absorb(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.compress();
+        self.v0 ^= word;
+    }
+
+    fn finalize(&mut self) -> u64 {
+        for _ in 0..3 {
+            self.compress();
+        }
+
+        self.v0 ^ self.v1 ^ self.v2 ^ self.v3
+    }
+}
+
+/// Returns the two 64-bit halves of the SipHash-1-3 digest of `data`, keyed
+/// with `k0` and `k1`
+pub fn hash128(k0: u64, k1: u64, data: &[u8]) -> (u64, u64) {
+    // The 128-bit variant differs from the 64-bit one by the initial state
+    // of `v1` and by the second finalization
+    let mut state = State {
+        v0: k0 ^ 0x736f6d6570736575,
+        v1: k1 ^ 0x646f72616e646f83,
+        v2: k0 ^ 0x6c7967656e657261,
+        v3: k1 ^ 0x7465646279746573,
+    };
+
+    let mut words = data.chunks_exact(8);
+
+    for word in words.by_ref() {
+        state.absorb(u64::from_le_bytes(word.try_into().unwrap()));
+    }
+
+    // The last word holds the remaining bytes and the length of the data
+    let mut last = [0u8; 8];
+    last[..words.remainder().len()].copy_from_slice(words.remainder());
+    last[7] = data.len() as u8;
+    state.absorb(u64::from_le_bytes(last));
+
+    state.v2 ^= 0xee;
+    let h1 = state.finalize();
+
+    state.v1 ^= 0xdd;
+    let h2 = state.finalize();
+
+    (h1, h2)
+}
@@ -0,0 +1,139 @@
+//! Order of the addresses generated by the list macros, selected with the
+//! `sort` option
+//!
+//! Every sort is stable, so the addresses comparing equal keep the order in
+//! which they are given and the generated list only depends on its input.
+
+use std::cmp::{Ordering, Reverse};
+use std::net::{IpAddr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use proc_macro_error::abort;
+
+use crate::arg_parser::Opt;
+use crate::classify::in_ipv6_prefix;
+use crate::report_error;
+
+/// Default policy table of RFC 6724, as prefixes and precedences
+const PRECEDENCES: [([u16; 8], u32, u8); 9] = [
+    ([0, 0, 0, 0, 0, 0, 0, 1], 128, 50),
+    ([0, 0, 0, 0, 0, 0, 0, 0], 0, 40),
+    ([0, 0, 0, 0, 0, 0xffff, 0, 0], 96, 35),
+    ([0x2002, 0, 0, 0, 0, 0, 0, 0], 16, 30),
+    ([0x2001, 0, 0, 0, 0, 0, 0, 0], 32, 5),
+    ([0xfc00, 0, 0, 0, 0, 0, 0, 0], 7, 3),
+    ([0, 0, 0, 0, 0, 0, 0, 0], 96, 1),
+    ([0xfec0, 0, 0, 0, 0, 0, 0, 0], 10, 1),
+    ([0x3ffe, 0, 0, 0, 0, 0, 0, 0], 16, 1),
+];
+
+/// Order of the generated addresses
+#[derive(Clone, Copy, Default)]
+pub enum Sort {
+    /// The order in which the addresses are given
+    #[default]
+    Source,
+    /// The ascending order of the addresses, the IPv4 ones first
+    Ascending,
+    /// The IPv4 addresses first, then the IPv6 ones
+    Family,
+    /// The destination address order of RFC 6724
+    Rfc6724,
+}
+
+/// Address sorted by the list macros
+pub trait SortKey: Ord {
+    fn ip(&self) -> IpAddr;
+}
+
+impl SortKey for IpAddr {
+    fn ip(&self) -> IpAddr {
+        *self
+    }
+}
+
+impl SortKey for SocketAddr {
+    fn ip(&self) -> IpAddr {
+        SocketAddr::ip(self)
+    }
+}
+
+impl SortKey for SocketAddrV4 {
+    fn ip(&self) -> IpAddr {
+        IpAddr::V4(*SocketAddrV4::ip(self))
+    }
+}
+
+impl SortKey for SocketAddrV6 {
+    fn ip(&self) -> IpAddr {
+        IpAddr::V6(*SocketAddrV6::ip(self))
+    }
+}
+
+/// Returns the precedence of an address in the default policy table of
+/// RFC 6724, IPv4 addresses being looked up as IPv4-mapped ones
+fn precedence(addr: &IpAddr) -> u8 {
+    let addr = match addr {
+        IpAddr::V4(v) => v.to_ipv6_mapped(),
+        IpAddr::V6(v) => *v,
+    };
+
+    PRECEDENCES
+        .iter()
+        .filter(|(network, len, _)| in_ipv6_prefix(&addr, *network, *len))
+        .max_by_key(|(_, len, _)| *len)
+        .map_or(0, |(_, _, precedence)| *precedence)
+}
+
+/// Returns the scope of an address as defined by RFC 6724, smaller scopes
+/// being preferred
+fn scope(addr: &IpAddr) -> u8 {
+    const LINK_LOCAL: u8 = 0x2;
+    const SITE_LOCAL: u8 = 0x5;
+    const GLOBAL: u8 = 0xe;
+
+    match addr {
+        IpAddr::V4(v) if v.is_loopback() || v.is_link_local() => LINK_LOCAL,
+        IpAddr::V4(_) => GLOBAL,
+        IpAddr::V6(v) if v.is_multicast() => (v.segments()[0] & 0xf) as u8,
+        IpAddr::V6(v)
+            if v.is_loopback() || in_ipv6_prefix(v, [0xfe80, 0, 0, 0, 0, 0, 0, 0], 10) =>
+        {
+            LINK_LOCAL
+        }
+        IpAddr::V6(v) if in_ipv6_prefix(v, [0xfec0, 0, 0, 0, 0, 0, 0, 0], 10) => SITE_LOCAL,
+        IpAddr::V6(_) => GLOBAL,
+    }
+}
+
+impl Sort {
+    /// Parses the value of the `sort` option
+    pub fn from_option(opt: &Opt) -> Self {
+        match report_error(opt.value_ident()).as_str() {
+            "source" => Self::Source,
+            "ascending" => Self::Ascending,
+            "family" => Self::Family,
+            "rfc6724" => Self::Rfc6724,
+            name => abort!(
+                opt.span(),
+                "Unknown sort order `{}`, expected `source`, `ascending`, `family` or `rfc6724`",
+                name
+            ),
+        }
+    }
+
+    /// Sorts the items, `key` returning the address of an item
+    pub fn apply<T, K: SortKey>(self, items: &mut [T], key: impl Fn(&T) -> K) {
+        let compare: fn(&K, &K) -> Ordering = match self {
+            Self::Source => return,
+            Self::Ascending => K::cmp,
+            Self::Family => |a, b| a.ip().is_ipv6().cmp(&b.ip().is_ipv6()),
+            Self::Rfc6724 => |a, b| {
+                let rank = |v: &K| (Reverse(precedence(&v.ip())), scope(&v.ip()));
+
+                rank(a).cmp(&rank(b))
+            },
+        };
+
+        items.sort_by(|a, b| compare(&key(a), &key(b)));
+    }
+}
@@ -0,0 +1,382 @@
+//! Output targets selected with the `as` option
+//!
+//! Each macro family has its own target enumeration so that a macro only
+//! accepts the targets it is able to generate.
+
+use std::net::{IpAddr, SocketAddr};
+
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+use proc_macro::Span;
+use proc_macro_error::abort;
+
+use crate::arg_parser::Opt;
+use crate::report_error;
+
+/// Type family generated by the IP address macros
+pub enum IpTarget {
+    Net,
+    #[cfg(feature = "smoltcp")]
+    Smoltcp,
+    /// The address as an `u32`
+    U32,
+    /// The address as an `u128`
+    U128,
+    /// The bytes of the address, in network byte order
+    Octets,
+    /// The bytes of the address, in little-endian byte order
+    LeBytes,
+    /// The bytes of the address, in the byte order of the target platform
+    NeBytes,
+    /// The 16-bit segments of an IPv6 address
+    Segments,
+}
+
+/// Type family generated by the socket macros
+pub enum SocketTarget {
+    Net,
+    #[cfg(feature = "nix")]
+    Nix,
+    #[cfg(feature = "smoltcp")]
+    Smoltcp,
+    #[cfg(feature = "embedded-nal")]
+    EmbeddedNal,
+    #[cfg(feature = "socket2")]
+    Socket2,
+}
+
+/// Type family generated by the network macros
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+pub enum NetTarget {
+    #[cfg(feature = "ipnet")]
+    Ipnet,
+    #[cfg(feature = "ipnetwork")]
+    Ipnetwork,
+    #[cfg(feature = "cidr")]
+    Cidr,
+}
+
+/// Type family of the elements generated by the network array macros
+pub enum NetArrayTarget {
+    /// A tuple of the address and the prefix length
+    Tuple,
+    #[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+    Net(NetTarget),
+}
+
+/// Type family generated by the hardware address macros
+pub enum MacTarget {
+    Array,
+    #[cfg(feature = "pnet")]
+    Pnet,
+}
+
+#[allow(dead_code)]
+fn report_missing_feature_error(opt: &Opt, feature: &str) -> ! {
+    abort!(
+        opt.span(),
+        "The `{}` target requires the `{}` feature of `fancy-ip`",
+        report_error(opt.value_ident()),
+        feature
+    );
+}
+
+fn report_unknown_target_error(opt: &Opt, name: &str) -> ! {
+    abort!(
+        opt.span(),
+        "Unknown or unsupported output target `{}` for this macro",
+        name
+    );
+}
+
+impl IpTarget {
+    /// Parses the target of a macro generating the `std::net` type `default`
+    pub fn from_option(opt: &Opt, default: &str) -> Self {
+        let name = report_error(opt.value_ident());
+
+        let target = match name.as_str() {
+            #[cfg(feature = "smoltcp")]
+            "smoltcp" => Self::Smoltcp,
+            #[cfg(not(feature = "smoltcp"))]
+            "smoltcp" => report_missing_feature_error(opt, "smoltcp"),
+            "u32" => Self::U32,
+            "u128" => Self::U128,
+            "octets" | "be_bytes" => Self::Octets,
+            "le_bytes" => Self::LeBytes,
+            "ne_bytes" => Self::NeBytes,
+            "segments" => Self::Segments,
+            _ => report_unknown_target_error(opt, &name),
+        };
+
+        // The representations have a distinct type for each address family
+        let supported = match target {
+            Self::U32 => default == "Ipv4Addr",
+            Self::U128 | Self::Segments => default == "Ipv6Addr",
+            Self::Octets | Self::LeBytes | Self::NeBytes => default != "IpAddr",
+            _ => true,
+        };
+
+        if !supported {
+            abort!(
+                opt.span(),
+                "The `{}` output target can't represent an `{}`",
+                name,
+                default
+            );
+        }
+
+        target
+    }
+
+    /// Checks whether the target generates an integer or an array instead of
+    /// an address type
+    pub fn is_representation(&self) -> bool {
+        matches!(
+            self,
+            Self::U32 | Self::U128 | Self::Octets | Self::LeBytes | Self::NeBytes | Self::Segments
+        )
+    }
+
+    /// Checks whether the target generates integers, which can be stored in
+    /// either byte order
+    pub fn has_byte_order(&self) -> bool {
+        matches!(self, Self::U32 | Self::U128 | Self::Segments)
+    }
+
+    /// Returns the name of the generated type, `default` being the one of the
+    /// `std::net` type
+    pub fn type_name(&self, default: &'static str) -> &'static str {
+        match self {
+            Self::Net => default,
+            #[cfg(feature = "smoltcp")]
+            Self::Smoltcp => match default {
+                "Ipv4Addr" => "Ipv4Address",
+                "Ipv6Addr" => "Ipv6Address",
+                _ => "IpAddress",
+            },
+            Self::U32 => "u32",
+            Self::U128 => "u128",
+            Self::Octets | Self::LeBytes | Self::NeBytes if default == "Ipv4Addr" => "[u8; 4]",
+            Self::Octets | Self::LeBytes | Self::NeBytes => "[u8; 16]",
+            Self::Segments => "[u16; 8]",
+        }
+    }
+}
+
+impl SocketTarget {
+    pub fn from_option(opt: &Opt) -> Self {
+        let name = report_error(opt.value_ident());
+
+        match name.as_str() {
+            #[cfg(feature = "nix")]
+            "nix" => Self::Nix,
+            #[cfg(not(feature = "nix"))]
+            "nix" => report_missing_feature_error(opt, "nix"),
+            #[cfg(feature = "smoltcp")]
+            "smoltcp" => Self::Smoltcp,
+            #[cfg(not(feature = "smoltcp"))]
+            "smoltcp" => report_missing_feature_error(opt, "smoltcp"),
+            #[cfg(feature = "embedded-nal")]
+            "embedded_nal" => Self::EmbeddedNal,
+            #[cfg(not(feature = "embedded-nal"))]
+            "embedded_nal" => report_missing_feature_error(opt, "embedded-nal"),
+            #[cfg(feature = "socket2")]
+            "socket2" => Self::Socket2,
+            #[cfg(not(feature = "socket2"))]
+            "socket2" => report_missing_feature_error(opt, "socket2"),
+            _ => report_unknown_target_error(opt, &name),
+        }
+    }
+
+    /// Checks whether the target has a type wrapping both address families,
+    /// as required by the `wrapped` option
+    pub fn has_wrapping_type(&self) -> bool {
+        match self {
+            Self::Net => true,
+            #[cfg(feature = "nix")]
+            Self::Nix => false,
+            #[cfg(feature = "smoltcp")]
+            Self::Smoltcp => false,
+            #[cfg(feature = "embedded-nal")]
+            Self::EmbeddedNal => true,
+            #[cfg(feature = "socket2")]
+            Self::Socket2 => false,
+        }
+    }
+
+    /// Returns the name of the generated type, `default` being the one of the
+    /// `std::net` type
+    #[cfg_attr(not(feature = "nix"), allow(unused_variables))]
+    pub fn type_name(&self, socket: &SocketAddr, default: &'static str) -> &'static str {
+        match self {
+            Self::Net => default,
+            #[cfg(feature = "nix")]
+            Self::Nix => match socket {
+                SocketAddr::V4(_) => "SockaddrIn",
+                SocketAddr::V6(_) => "SockaddrIn6",
+            },
+            #[cfg(feature = "smoltcp")]
+            Self::Smoltcp => "IpEndpoint",
+            #[cfg(feature = "embedded-nal")]
+            Self::EmbeddedNal => default,
+            #[cfg(feature = "socket2")]
+            Self::Socket2 => "SockAddr",
+        }
+    }
+
+    /// Checks whether the generated expression can be used in const contexts
+    pub fn is_const(&self) -> bool {
+        match self {
+            Self::Net => true,
+            #[cfg(feature = "nix")]
+            Self::Nix => false,
+            #[cfg(feature = "smoltcp")]
+            Self::Smoltcp => true,
+            #[cfg(feature = "embedded-nal")]
+            Self::EmbeddedNal => true,
+            #[cfg(feature = "socket2")]
+            Self::Socket2 => false,
+        }
+    }
+}
+
+#[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+impl NetTarget {
+    pub fn from_option(opt: &Opt) -> Self {
+        let name = report_error(opt.value_ident());
+
+        match name.as_str() {
+            #[cfg(feature = "ipnet")]
+            "ipnet" => Self::Ipnet,
+            #[cfg(not(feature = "ipnet"))]
+            "ipnet" => report_missing_feature_error(opt, "ipnet"),
+            #[cfg(feature = "ipnetwork")]
+            "ipnetwork" => Self::Ipnetwork,
+            #[cfg(not(feature = "ipnetwork"))]
+            "ipnetwork" => report_missing_feature_error(opt, "ipnetwork"),
+            #[cfg(feature = "cidr")]
+            "cidr" => Self::Cidr,
+            #[cfg(not(feature = "cidr"))]
+            "cidr" => report_missing_feature_error(opt, "cidr"),
+            _ => report_unknown_target_error(opt, &name),
+        }
+    }
+
+    /// Returns the target used without the `as` option, which is always the
+    /// `ipnet` one so that enabling another feature doesn't change the
+    /// generated types
+    #[cfg(feature = "ipnet")]
+    pub fn default_target(_span: Span) -> Self {
+        Self::Ipnet
+    }
+
+    #[cfg(not(feature = "ipnet"))]
+    pub fn default_target(span: Span) -> Self {
+        abort!(
+            span,
+            "Networks are generated as `ipnet` networks by default, which requires the `ipnet` feature of `fancy-ip`";
+            help = "Select the output target with the `as = <target>` option"
+        );
+    }
+
+    /// Checks whether the target has a type holding any network, as required
+    /// by the `any` option
+    pub fn has_any_type(&self) -> bool {
+        match self {
+            #[cfg(feature = "ipnet")]
+            Self::Ipnet => false,
+            #[cfg(feature = "ipnetwork")]
+            Self::Ipnetwork => false,
+            #[cfg(feature = "cidr")]
+            Self::Cidr => true,
+        }
+    }
+
+    /// Checks whether the target requires the address of the network to have
+    /// no bit set outside of the prefix
+    pub fn requires_network_address(&self) -> bool {
+        match self {
+            #[cfg(feature = "ipnet")]
+            Self::Ipnet => false,
+            #[cfg(feature = "ipnetwork")]
+            Self::Ipnetwork => false,
+            #[cfg(feature = "cidr")]
+            Self::Cidr => true,
+        }
+    }
+
+    /// Returns the name of the generated type, for a network of `addr`
+    pub fn type_name(&self, addr: &IpAddr, wrapped: bool) -> &'static str {
+        match (self, wrapped, addr) {
+            #[cfg(feature = "ipnet")]
+            (Self::Ipnet, true, _) => "IpNet",
+            #[cfg(feature = "ipnet")]
+            (Self::Ipnet, false, IpAddr::V4(_)) => "Ipv4Net",
+            #[cfg(feature = "ipnet")]
+            (Self::Ipnet, false, IpAddr::V6(_)) => "Ipv6Net",
+            #[cfg(feature = "ipnetwork")]
+            (Self::Ipnetwork, true, _) => "IpNetwork",
+            #[cfg(feature = "ipnetwork")]
+            (Self::Ipnetwork, false, IpAddr::V4(_)) => "Ipv4Network",
+            #[cfg(feature = "ipnetwork")]
+            (Self::Ipnetwork, false, IpAddr::V6(_)) => "Ipv6Network",
+            #[cfg(feature = "cidr")]
+            (Self::Cidr, true, _) => "IpCidr",
+            #[cfg(feature = "cidr")]
+            (Self::Cidr, false, IpAddr::V4(_)) => "Ipv4Cidr",
+            #[cfg(feature = "cidr")]
+            (Self::Cidr, false, IpAddr::V6(_)) => "Ipv6Cidr",
+        }
+    }
+}
+
+impl NetArrayTarget {
+    pub fn from_option(opt: &Opt) -> Self {
+        #[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+        {
+            Self::Net(NetTarget::from_option(opt))
+        }
+
+        #[cfg(not(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr")))]
+        {
+            let name = report_error(opt.value_ident());
+
+            match name.as_str() {
+                "ipnet" | "ipnetwork" | "cidr" => report_missing_feature_error(opt, &name),
+                _ => report_unknown_target_error(opt, &name),
+            }
+        }
+    }
+
+    /// Returns the name of the generated type, for a network of `addr`
+    pub fn type_name(&self, addr: &IpAddr) -> &'static str {
+        match (self, addr) {
+            (Self::Tuple, IpAddr::V4(_)) => "(Ipv4Addr, u8)",
+            (Self::Tuple, IpAddr::V6(_)) => "(Ipv6Addr, u8)",
+            #[cfg(any(feature = "ipnet", feature = "ipnetwork", feature = "cidr"))]
+            (Self::Net(target), _) => target.type_name(addr, false),
+        }
+    }
+}
+
+impl MacTarget {
+    pub fn from_option(opt: &Opt) -> Self {
+        let name = report_error(opt.value_ident());
+
+        match name.as_str() {
+            #[cfg(feature = "pnet")]
+            "pnet" => Self::Pnet,
+            #[cfg(not(feature = "pnet"))]
+            "pnet" => report_missing_feature_error(opt, "pnet"),
+            _ => report_unknown_target_error(opt, &name),
+        }
+    }
+
+    /// Returns the name of the generated type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Array => "[u8; 6]",
+            #[cfg(feature = "pnet")]
+            Self::Pnet => "MacAddr",
+        }
+    }
+}
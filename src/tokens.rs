@@ -0,0 +1,197 @@
+//! Direct construction of the generated token streams
+//!
+//! The expansions are built token by token instead of being formatted then
+//! lexed again, which matters for the macros generating large tables.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+pub fn ident(name: &str) -> TokenTree {
+    TokenTree::Ident(Ident::new(name, Span::call_site()))
+}
+
+pub fn punct(ch: char) -> TokenTree {
+    TokenTree::Punct(Punct::new(ch, Spacing::Alone))
+}
+
+/// Builds a multi-character operator such as `::` or `=>`
+pub fn op(chars: &str) -> TokenStream {
+    let count = chars.chars().count();
+
+    chars
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| {
+            let spacing = if i + 1 < count {
+                Spacing::Joint
+            } else {
+                Spacing::Alone
+            };
+
+            TokenTree::Punct(Punct::new(ch, spacing))
+        })
+        .collect()
+}
+
+pub fn literal(literal: Literal) -> TokenTree {
+    TokenTree::Literal(literal)
+}
+
+/// Builds an integer using its hexadecimal representation with `digits`
+/// digits
+pub fn hex(value: u64, digits: usize) -> TokenTree {
+    literal(
+        format!("{value:#0width$x}", width = digits + 2)
+            .parse()
+            .unwrap(),
+    )
+}
+
+/// Builds an integer using its hexadecimal representation with `digits`
+/// digits, followed by the type suffix `suffix`
+pub fn hex_suffixed(value: u128, digits: usize, suffix: &str) -> TokenTree {
+    literal(
+        format!("{value:#0width$x}{suffix}", width = digits + 2)
+            .parse()
+            .unwrap(),
+    )
+}
+
+pub fn group(delimiter: Delimiter, stream: TokenStream) -> TokenTree {
+    TokenTree::Group(Group::new(delimiter, stream))
+}
+
+/// Builds a path from its `::` separated representation
+pub fn path(path: &str) -> TokenStream {
+    let mut ret = TokenStream::new();
+
+    for (i, segment) in path.split("::").enumerate() {
+        if i > 0 {
+            ret.extend(op("::"));
+        }
+
+        if !segment.is_empty() {
+            ret.extend([ident(segment)]);
+        }
+    }
+
+    ret
+}
+
+/// Joins items with commas
+pub fn list(items: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let mut ret = TokenStream::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            ret.extend([punct(',')]);
+        }
+
+        ret.extend(item);
+    }
+
+    ret
+}
+
+/// Builds a call of the function at `path`
+pub fn call(path: &str, args: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let mut ret = self::path(path);
+    ret.extend([group(Delimiter::Parenthesis, list(args))]);
+
+    ret
+}
+
+/// Builds the `let <name> = <value>;` statement, `name` possibly starting with
+/// `mut`
+pub fn let_statement(name: &str, value: TokenStream) -> TokenStream {
+    let mut ret: TokenStream = [ident("let")].into_iter().collect();
+    ret.extend(name.split(' ').map(ident));
+    ret.extend([punct('=')]);
+    ret.extend(value);
+    ret.extend([punct(';')]);
+
+    ret
+}
+
+/// Builds the `const _: &str = <name>!("<argument>");` item, which makes the
+/// compiler track the file or environment variable read by the macro
+pub fn tracking_const(name: &str, argument: &str) -> TokenStream {
+    tracking_item(name, literal(Literal::string(argument)).into())
+}
+
+/// Builds the `const _: &str = <name>!(<argument>);` item of
+/// [`tracking_const`] with any argument
+pub fn tracking_item(name: &str, argument: TokenStream) -> TokenStream {
+    [
+        ident("const"),
+        ident("_"),
+        punct(':'),
+        punct('&'),
+        ident("str"),
+        punct('='),
+        ident(name),
+        punct('!'),
+        group(Delimiter::Parenthesis, argument),
+        punct(';'),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// Prints a token stream with a spacing only depending on its tokens, unlike
+/// its `Display` implementation which is the pretty-printer of the compiler
+///
+/// The tokens are separated by a space, except the joint punctuation such as
+/// `::`, the commas and semicolons, and the arguments of a call.
+pub fn to_string(stream: &TokenStream) -> String {
+    let mut ret = String::new();
+    let mut glued = true;
+    let mut callee = false;
+
+    for token in stream.clone() {
+        let attached = match &token {
+            TokenTree::Punct(punct) => matches!(punct.as_char(), ',' | ';'),
+            TokenTree::Group(group) => callee && group.delimiter() == Delimiter::Parenthesis,
+            _ => false,
+        };
+
+        if !glued && !attached {
+            ret.push(' ');
+        }
+
+        glued = false;
+        callee = false;
+
+        match &token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+
+                let inner = to_string(&group.stream());
+
+                if inner.is_empty() {
+                    ret.push_str(open.trim_end());
+                    ret.push_str(close.trim_start());
+                } else {
+                    ret.push_str(open);
+                    ret.push_str(&inner);
+                    ret.push_str(close);
+                }
+            }
+            TokenTree::Punct(punct) => {
+                ret.push(punct.as_char());
+                glued = punct.spacing() == Spacing::Joint;
+            }
+            TokenTree::Ident(ident) => {
+                ret.push_str(&ident.to_string());
+                callee = true;
+            }
+            TokenTree::Literal(literal) => ret.push_str(&literal.to_string()),
+        }
+    }
+
+    ret
+}
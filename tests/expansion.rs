@@ -0,0 +1,79 @@
+//! Stability of the expansions
+//!
+//! Builds the crate of `tests/fixtures/expansion`, whose macro calls use the
+//! `debug_expand` option, and compares the expansions against
+//! `tests/fixtures/expansion.txt`. The expansions are printed by the crate
+//! itself, so that the snapshot doesn't depend on the pretty-printer of the
+//! compiler. Setting the `FANCY_IP_BLESS` environment variable rewrites the
+//! snapshot instead, when an expansion is changed on purpose.
+
+use std::path::Path;
+use std::process::Command;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/expansion");
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/expansion.txt");
+
+/// Returns the expansions reported by the compilation of the fixture crate,
+/// each preceded by the location of its macro call
+fn expand() -> String {
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--locked", "--message-format=short"])
+        .current_dir(FIXTURE)
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("expansion"),
+        )
+        // The variables changing the expansions
+        .env_remove("FANCY_IP_STRICT")
+        .env_remove("FANCY_IP_DEBUG_EXPAND")
+        .env_remove("FANCY_IP_INVENTORY")
+        .env_remove("FANCY_IP_NET_PATH")
+        .env_remove("RUSTFLAGS")
+        .env_remove("CARGO_ENCODED_RUSTFLAGS")
+        .output()
+        .expect("Couldn't run cargo");
+
+    let stderr = String::from_utf8(output.stderr).expect("Invalid compiler output");
+    assert!(
+        output.status.success(),
+        "The fixture doesn't build:\n{stderr}"
+    );
+
+    // Only the location and the expansion printed by the crate are kept, the
+    // wording of the warning belonging to the compiler
+    let mut ret = String::new();
+
+    for line in stderr.lines() {
+        let Some((location, message)) = line.split_once(": warning: ") else {
+            continue;
+        };
+        let Some((_, expansion)) = message.split_once("fancy-ip expanded to `") else {
+            continue;
+        };
+
+        ret.push_str(&format!(
+            "{location}: {}\n",
+            expansion.strip_suffix('`').unwrap_or(expansion)
+        ));
+    }
+
+    ret
+}
+
+#[test]
+fn expansions_match_the_snapshot() {
+    let expanded = expand();
+
+    if std::env::var_os("FANCY_IP_BLESS").is_some() {
+        std::fs::write(SNAPSHOT, &expanded).expect("Couldn't write the snapshot");
+        return;
+    }
+
+    // Compared line by line, the snapshot being checked out with CRLF line
+    // endings
+    let snapshot = std::fs::read_to_string(SNAPSHOT).expect("Couldn't read the snapshot");
+    assert!(
+        snapshot.lines().eq(expanded.lines()),
+        "The expansions differ from the snapshot:\n{expanded}"
+    );
+}
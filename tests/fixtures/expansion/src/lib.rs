@@ -0,0 +1,36 @@
+//! Macro calls covering the integer formats, the ordered outputs and the
+//! tracked files of the expansions
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use fancy_ip::{
+    aggregate, ip, ip_list_from_file, ips, ipv4, ipv6, mac, netmask, socket, socketv6,
+};
+
+pub const GATEWAY: Ipv4Addr = ipv4!("192.0.2.1", debug_expand);
+pub const RESOLVER: Ipv6Addr = ipv6!("2001:db8::53", debug_expand);
+pub const ANY: IpAddr = ip!("::", debug_expand);
+pub const LISTEN: SocketAddr = socket!("192.0.2.1:8443", debug_expand);
+pub const SCOPED: SocketAddrV6 = socketv6!("[fe80::1%3]:443", debug_expand);
+pub const INTERFACE: [u8; 6] = mac!("02:00:5e:10:00:01", debug_expand);
+pub const MASK: Ipv4Addr = netmask!(26, debug_expand);
+pub const BITS: u32 = ipv4!("192.0.2.1", as = u32, debug_expand);
+pub const BITS_V6: u128 = ipv6!("2001:db8::1", as = u128, debug_expand);
+
+pub const SORTED: [IpAddr; 4] = ips!(
+    "2001:db8::2",
+    "192.0.2.2",
+    "2001:db8::1",
+    "192.0.2.1",
+    sort = ascending,
+    debug_expand,
+);
+
+pub const ROUTES: [(Ipv4Addr, u8); 2] = aggregate!(
+    "192.0.2.128/25",
+    "198.51.100.0/24",
+    "192.0.2.0/25",
+    debug_expand,
+);
+
+pub const SERVERS: &[IpAddr] = ip_list_from_file!("servers.txt", debug_expand);
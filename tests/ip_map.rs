@@ -0,0 +1,81 @@
+//! Lookups in the maps generated by `ip_map!`
+
+#![cfg(feature = "phf")]
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use fancy_ip::ip_map;
+
+static V4: phf::Map<u32, u16> = ip_map! {
+    "10.0.0.1" => 1,
+    "10.0.0.2" => 2,
+    "10.0.0.3" => 3,
+    "10.0.1.1" => 4,
+    "172.16.0.1" => 5,
+    "172.16.0.2" => 6,
+    "192.168.0.1" => 7,
+    "192.168.0.2" => 8,
+    "192.168.1.1" => 9,
+    "198.51.100.1" => 10,
+    "203.0.113.1" => 11,
+    "203.0.113.2" => 12,
+};
+
+static V6: phf::Map<u128, &str> = ip_map! {
+    "2001:db8::1" => "first",
+    "2001:db8::2" => "second",
+    "2001:db8:1::1" => "third",
+    "fd00::1" => "unique-local",
+};
+
+#[test]
+fn finds_every_ipv4_key() {
+    let keys = [
+        "10.0.0.1",
+        "10.0.0.2",
+        "10.0.0.3",
+        "10.0.1.1",
+        "172.16.0.1",
+        "172.16.0.2",
+        "192.168.0.1",
+        "192.168.0.2",
+        "192.168.1.1",
+        "198.51.100.1",
+        "203.0.113.1",
+        "203.0.113.2",
+    ];
+
+    assert_eq!(V4.len(), keys.len());
+
+    for (value, key) in (1..).zip(keys) {
+        let addr: Ipv4Addr = key.parse().unwrap();
+        assert_eq!(V4.get(&u32::from(addr)), Some(&value), "{key}");
+    }
+}
+
+#[test]
+fn misses_the_other_ipv4_addresses() {
+    for addr in ["10.0.0.4", "10.0.1.2", "0.0.0.0", "255.255.255.255"] {
+        let addr: Ipv4Addr = addr.parse().unwrap();
+        assert_eq!(V4.get(&u32::from(addr)), None, "{addr}");
+    }
+}
+
+#[test]
+fn finds_every_ipv6_key() {
+    let entries = [
+        ("2001:db8::1", "first"),
+        ("2001:db8::2", "second"),
+        ("2001:db8:1::1", "third"),
+        ("fd00::1", "unique-local"),
+    ];
+
+    assert_eq!(V6.len(), entries.len());
+
+    for (key, value) in entries {
+        let addr: Ipv6Addr = key.parse().unwrap();
+        assert_eq!(V6.get(&u128::from(addr)), Some(&value), "{key}");
+    }
+
+    assert_eq!(V6.get(&u128::from(Ipv6Addr::LOCALHOST)), None);
+}
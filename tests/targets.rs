@@ -0,0 +1,188 @@
+//! Output targets of the `as` option, compared with the values built by the
+//! constructors of their crates
+//!
+//! Each test requires the feature of its target, so that the generated paths
+//! and constructors are compiled against the real types with
+//! `--all-features`.
+
+#[cfg(feature = "nix")]
+#[test]
+fn nix() {
+    use fancy_ip::{socketv4, socketv6};
+    use nix::sys::socket::{SockaddrIn, SockaddrIn6};
+    use std::net::{Ipv6Addr, SocketAddrV6};
+
+    let v4: SockaddrIn = socketv4!("127.0.0.1:3000", as = nix);
+    assert_eq!(v4, SockaddrIn::new(127, 0, 0, 1, 3000));
+
+    let v6: SockaddrIn6 = socketv6!("[::1]:3000", as = nix);
+    let expected = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 3000, 0, 0);
+    assert_eq!(v6, SockaddrIn6::from(expected));
+}
+
+#[cfg(feature = "pnet")]
+#[test]
+fn pnet() {
+    use fancy_ip::mac;
+    use pnet::util::MacAddr;
+
+    const GATEWAY: MacAddr = mac!("00:1b:63:84:45:e6", as = pnet);
+    assert_eq!(GATEWAY, MacAddr::new(0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6));
+}
+
+#[cfg(feature = "hickory")]
+#[test]
+fn hickory() {
+    use fancy_ip::resolver_config;
+    use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig};
+    use std::net::IpAddr;
+
+    let config = resolver_config!("9.9.9.9:53", "[2620:fe::fe]:53", trust_nx);
+    let ips: [IpAddr; 2] = ["9.9.9.9".parse().unwrap(), "2620:fe::fe".parse().unwrap()];
+    let expected = ResolverConfig::from_parts(
+        None,
+        vec![],
+        NameServerConfigGroup::from_ips_clear(&ips, 53, true),
+    );
+    assert_eq!(config, expected);
+}
+
+#[cfg(feature = "ipnet")]
+#[test]
+fn ipnet() {
+    use fancy_ip::cidr;
+    use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    const PRIVATE: Ipv4Net = cidr!("10.0.0.0/8");
+    assert_eq!(
+        PRIVATE,
+        Ipv4Net::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap()
+    );
+
+    const DOCUMENTATION: IpNet = cidr!("2001:db8::/32", wrapped);
+    let addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0);
+    assert_eq!(DOCUMENTATION, IpNet::V6(Ipv6Net::new(addr, 32).unwrap()));
+}
+
+#[cfg(feature = "smoltcp")]
+#[test]
+fn smoltcp() {
+    use fancy_ip::{ip, ipv4, ipv6, socketv6};
+    use smoltcp::wire::{IpAddress, IpEndpoint, Ipv4Address, Ipv6Address};
+
+    const V4: Ipv4Address = ipv4!("10.0.0.1", as = smoltcp);
+    assert_eq!(V4, Ipv4Address::new(10, 0, 0, 1));
+
+    const V6: Ipv6Address = ipv6!("2001:db8::1", as = smoltcp);
+    assert_eq!(V6, Ipv6Address::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+    const ANY: IpAddress = ip!("10.0.0.1", as = smoltcp);
+    assert_eq!(ANY, IpAddress::Ipv4(V4));
+
+    const SERVER: IpEndpoint = socketv6!("[2001:db8::1]:80", as = smoltcp);
+    assert_eq!(SERVER, IpEndpoint::new(IpAddress::Ipv6(V6), 80));
+}
+
+#[cfg(feature = "embedded-nal")]
+#[test]
+fn embedded_nal() {
+    use embedded_nal::{IpAddr, Ipv4Addr, SocketAddr};
+    use fancy_ip::socket;
+
+    const SERVER: SocketAddr = socket!("10.0.0.1:80", as = embedded_nal);
+    let expected = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 80);
+    assert_eq!(SERVER, expected);
+}
+
+#[cfg(feature = "libc")]
+#[test]
+fn libc() {
+    use fancy_ip::{sockaddr_in, sockaddr_in6};
+
+    const V4: libc::sockaddr_in = sockaddr_in!("10.0.0.1:9000");
+    assert_eq!(V4.sin_family, libc::AF_INET as libc::sa_family_t);
+    assert_eq!(V4.sin_port, 9000u16.to_be());
+    assert_eq!(V4.sin_addr.s_addr, u32::from_ne_bytes([10, 0, 0, 1]));
+
+    const V6: libc::sockaddr_in6 = sockaddr_in6!("[fe80::1]:9000", scope_id = 2);
+    assert_eq!(V6.sin6_family, libc::AF_INET6 as libc::sa_family_t);
+    assert_eq!(V6.sin6_port, 9000u16.to_be());
+    assert_eq!(V6.sin6_scope_id, 2);
+    let expected = std::net::Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+    assert_eq!(V6.sin6_addr.s6_addr, expected.octets());
+}
+
+#[cfg(feature = "windows")]
+#[test]
+fn windows() {
+    use fancy_ip::{winsock_sockaddr_in, winsock_sockaddr_in6};
+    use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6, SOCKADDR_IN, SOCKADDR_IN6};
+
+    const V4: SOCKADDR_IN = winsock_sockaddr_in!("10.0.0.1:9000");
+    assert_eq!(V4.sin_family, AF_INET);
+    assert_eq!(V4.sin_port, 9000u16.to_be());
+    assert_eq!(
+        unsafe { V4.sin_addr.S_un.S_addr },
+        u32::from_ne_bytes([10, 0, 0, 1])
+    );
+
+    const V6: SOCKADDR_IN6 = winsock_sockaddr_in6!("[fe80::1]:9000", scope_id = 2);
+    assert_eq!(V6.sin6_family, AF_INET6);
+    assert_eq!(V6.sin6_port, 9000u16.to_be());
+    assert_eq!(unsafe { V6.Anonymous.sin6_scope_id }, 2);
+    let expected = std::net::Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+    assert_eq!(unsafe { V6.sin6_addr.u.Byte }, expected.octets());
+}
+
+#[cfg(feature = "socket2")]
+#[test]
+fn socket2() {
+    use fancy_ip::socket;
+    use socket2::SockAddr;
+    use std::net::SocketAddr;
+
+    let server: SockAddr = socket!("1.2.3.4:80", as = socket2);
+    let expected: SocketAddr = "1.2.3.4:80".parse().unwrap();
+    assert_eq!(server, SockAddr::from(expected));
+}
+
+#[cfg(feature = "ipnetwork")]
+#[test]
+fn ipnetwork() {
+    use fancy_ip::cidr;
+    use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    const LOOPBACK: Ipv4Network = cidr!("127.0.0.0/8", as = ipnetwork);
+    assert_eq!(
+        LOOPBACK,
+        Ipv4Network::new(Ipv4Addr::new(127, 0, 0, 0), 8).unwrap()
+    );
+
+    const LINK_LOCAL: IpNetwork = cidr!("fe80::/10", as = ipnetwork, wrapped);
+    let addr = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0);
+    assert_eq!(
+        LINK_LOCAL,
+        IpNetwork::V6(Ipv6Network::new(addr, 10).unwrap())
+    );
+}
+
+#[cfg(feature = "cidr")]
+#[test]
+fn cidr() {
+    use ::cidr::{AnyIpCidr, IpCidr, Ipv4Cidr, Ipv6Cidr};
+    use fancy_ip::cidr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    const PRIVATE: Ipv4Cidr = cidr!("10.0.0.0/8", as = cidr);
+    assert_eq!(
+        PRIVATE,
+        Ipv4Cidr::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap()
+    );
+
+    const LINK_LOCAL: AnyIpCidr = cidr!("fe80::/10", as = cidr, any);
+    let addr = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0);
+    let expected = IpCidr::V6(Ipv6Cidr::new(addr, 10).unwrap());
+    assert_eq!(LINK_LOCAL, AnyIpCidr::from(expected));
+}